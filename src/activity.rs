@@ -0,0 +1,101 @@
+use url::Url;
+
+use crate::identified::{child_uri, impl_identified};
+use crate::toplevel::impl_toplevel;
+use crate::{Annotation, SbolError};
+
+/// Provenance of the objects which were generated by a design process, ie: a digest or an
+/// assembly (`prov:Activity` of the PROV-O ontology).
+///
+/// Objects refer to the `Activity` which generated them via `generated_by`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Activity {
+    pub identity: Url,
+    pub display_id: Option<String>,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub derived_from: Vec<Url>,
+    pub generated_by: Vec<Url>,
+    pub has_measure: Vec<Url>,
+    pub annotations: Vec<Annotation>,
+    pub namespace: Url,
+    pub has_attachment: Vec<Url>,
+
+    /// Kind of activity, ie: a term of the design-build-test-learn stages.
+    pub r#type: Vec<Url>,
+
+    /// `xsd:dateTime` at which the activity started
+    pub start_time: Option<String>,
+
+    /// `xsd:dateTime` at which the activity ended
+    pub end_time: Option<String>,
+
+    /// Objects used by the activity, and how they were used (`prov:qualifiedUsage`)
+    pub has_usage: Vec<Usage>,
+
+    /// Other activities whose results were used by this activity (`prov:wasInformedBy`)
+    pub informed_by: Vec<Url>,
+}
+
+/// Use of an object by an `Activity` (`prov:Usage` of the PROV-O ontology)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Usage {
+    pub identity: Url,
+    pub display_id: Option<String>,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub derived_from: Vec<Url>,
+    pub generated_by: Vec<Url>,
+    pub has_measure: Vec<Url>,
+    pub annotations: Vec<Annotation>,
+
+    /// Object which was used
+    pub entity: Url,
+
+    /// How the object was used, ie: as a template or as a design
+    pub role: Vec<Url>,
+}
+
+impl Activity {
+    /// Create an empty `Activity` whose URI is `namespace/display_id`
+    pub fn new(namespace: Url, display_id: &str) -> Result<Self, SbolError> {
+        Ok(Self {
+            identity: child_uri(&namespace, display_id)?,
+            display_id: Some(display_id.to_string()),
+            name: None,
+            description: None,
+            derived_from: vec![],
+            generated_by: vec![],
+            has_measure: vec![],
+            annotations: vec![],
+            namespace,
+            has_attachment: vec![],
+            r#type: vec![],
+            start_time: None,
+            end_time: None,
+            has_usage: vec![],
+            informed_by: vec![],
+        })
+    }
+}
+
+impl Usage {
+    /// Create a `Usage` whose URI is `parent/display_id`
+    pub fn new(parent: &Url, display_id: &str, entity: Url) -> Result<Self, SbolError> {
+        Ok(Self {
+            identity: child_uri(parent, display_id)?,
+            display_id: Some(display_id.to_string()),
+            name: None,
+            description: None,
+            derived_from: vec![],
+            generated_by: vec![],
+            has_measure: vec![],
+            annotations: vec![],
+            entity,
+            role: vec![],
+        })
+    }
+}
+
+impl_toplevel!(Activity);
+impl_identified!(Usage);
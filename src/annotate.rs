@@ -0,0 +1,291 @@
+//! Annotation of the `Sequence` of a `Component` with the parts of a library.
+//!
+//! Each `Component` of a library `Document` with a nucleic acid `Sequence` is searched for on both
+//! strands of the target, following its circular topology, and each hit becomes a `Feature` of
+//! the target located by `Range` objects.
+
+use url::Url;
+
+use crate::coordinates::{Coordinates, Span};
+use crate::identified::child_uri;
+use crate::ontologies::Orientation;
+use crate::search::{self, Hit, SearchOptions};
+use crate::uris::child_display_id;
+use crate::{Component, Document, Feature, Location, SbolError, SequenceFeature, SubComponent};
+
+/// Kind of `Feature` which records a hit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FeatureKind {
+    /// `SubComponent` which is an `instance_of` the library part
+    #[default]
+    SubComponent,
+
+    /// `SequenceFeature` with the `name` and `role` of the library part, `derived_from` it
+    SequenceFeature,
+}
+
+/// Parameters of an annotation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct AnnotationOptions {
+    pub kind: FeatureKind,
+
+    /// Parts with fewer elements are not searched, as they would match by chance.
+    pub min_length: usize,
+}
+
+/// Add a `Feature` to `component` for every occurrence of a part of `library` within its
+/// nucleic acid `Sequence`, and return the URIs of the new features.
+///
+/// Ambiguity codes of the parts are matched against the bases they represent. The `Feature` of
+/// each hit is identified by [`child_display_id`], and hits which are already annotated by a
+/// `Feature` for the same part at the same location are skipped.
+pub fn annotate(
+    document: &mut Document,
+    component: &Url,
+    library: &Document,
+    options: &AnnotationOptions,
+) -> Result<Vec<Url>, SbolError> {
+    let target = document
+        .component(component)
+        .ok_or_else(|| SbolError::UnresolvedReference(component.clone()))?;
+    let sequence = document.nucleic_acid_sequence(target).ok_or_else(|| {
+        SbolError::Validation(format!("{component} has no nucleic acid Sequence"))
+    })?;
+    let elements = sequence.nucleic_acid_elements()?;
+    let has_sequence = sequence.identity.clone();
+    let coordinates = Coordinates::of(document, target)?;
+    let search = SearchOptions {
+        both_strands: true,
+        ..SearchOptions::of(target)
+    };
+
+    let mut hits: Vec<(&Component, Hit)> = vec![];
+    for part in &library.components {
+        let Some(part_elements) = library
+            .nucleic_acid_sequence(part)
+            .and_then(|sequence| sequence.nucleic_acid_elements().ok())
+        else {
+            continue;
+        };
+        if &part.identity == component
+            || part_elements.is_empty()
+            || part_elements.len() < options.min_length
+        {
+            continue;
+        }
+        for hit in search::find(elements, part_elements, &search)? {
+            if !annotated(target, &coordinates, part, &hit)? {
+                hits.push((part, hit));
+            }
+        }
+    }
+
+    let target = document
+        .component_mut(component)
+        .ok_or_else(|| SbolError::UnresolvedReference(component.clone()))?;
+    let mut features = vec![];
+    for (part, hit) in hits {
+        let class = match options.kind {
+            FeatureKind::SubComponent => "SubComponent",
+            FeatureKind::SequenceFeature => "SequenceFeature",
+        };
+        let display_id = child_display_id(class, target.children());
+        let identity = child_uri(&target.identity, &display_id)?;
+        let locations: Vec<Location> = hit
+            .ranges(&identity, &has_sequence, coordinates.length)?
+            .into_iter()
+            .map(Location::Range)
+            .collect();
+        match options.kind {
+            FeatureKind::SubComponent => {
+                let mut sub_component =
+                    SubComponent::new(&target.identity, &display_id, part.identity.clone())?;
+                sub_component.has_location = locations;
+                target.sub_components.push(sub_component);
+            }
+            FeatureKind::SequenceFeature => {
+                let mut sequence_feature = SequenceFeature::new(&target.identity, &display_id)?;
+                sequence_feature.name = part.name.clone();
+                sequence_feature.role = part.role.clone();
+                sequence_feature.derived_from = vec![part.identity.clone()];
+                sequence_feature.has_location = locations;
+                target.sequence_features.push(sequence_feature);
+            }
+        }
+        features.push(identity);
+    }
+    Ok(features)
+}
+
+/// Whether `target` already has a `Feature` for `part` at the location of `hit`, on the same
+/// strand regardless of which term records the orientation
+fn annotated(
+    target: &Component,
+    coordinates: &Coordinates,
+    part: &Component,
+    hit: &Hit,
+) -> Result<bool, SbolError> {
+    let same_location = |feature: &dyn Feature, locations: &[Location]| {
+        let span: Option<Span> = coordinates.span(locations)?;
+        let orientation = locations
+            .iter()
+            .find_map(|location| location.orientation())
+            .or(feature.orientation());
+        let reverse = |orientation: &Orientation| {
+            matches!(
+                orientation,
+                Orientation::ReverseComplement | Orientation::ReverseComplementAlt
+            )
+        };
+        Ok::<_, SbolError>(
+            span == Some(hit.span)
+                && orientation
+                    .is_some_and(|orientation| reverse(orientation) == reverse(&hit.orientation)),
+        )
+    };
+    for sub_component in &target.sub_components {
+        if sub_component.instance_of == part.identity
+            && same_location(sub_component, &sub_component.has_location)?
+        {
+            return Ok(true);
+        }
+    }
+    for sequence_feature in &target.sequence_features {
+        if sequence_feature.derived_from.contains(&part.identity)
+            && same_location(sequence_feature, &sequence_feature.has_location)?
+        {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ontologies::{ComponentRole, TopologyOntology};
+    use crate::test_utils::{dna, uri};
+    use crate::validation::hierarchy::sequence_mismatches;
+
+    /// Circular, double-stranded plasmid with `promoter` at 3..8 and, on the other strand,
+    /// `terminator` spanning the origin at 11..2
+    fn plasmid() -> (Document, Url) {
+        let mut document = Document::new();
+        let plasmid = dna(
+            &mut document,
+            &uri("https://example.org"),
+            "plasmid",
+            "tgttgacaaccacgac",
+            &[TopologyOntology::Circular, TopologyOntology::DoubleStranded],
+        );
+        (document, plasmid)
+    }
+
+    fn library() -> Document {
+        let namespace = uri("https://parts.org");
+        let mut library = Document::new();
+        dna(&mut library, &namespace, "promoter", "TTGACA", &[]);
+        dna(&mut library, &namespace, "terminator", "CAGTCGTG", &[]);
+        dna(&mut library, &namespace, "spacer", "AAAAAA", &[]);
+        library.components[0].role = vec![ComponentRole::Promoter];
+        library.components[0].name = Some("Promoter".to_string());
+        library
+    }
+
+    #[test]
+    fn test_annotate_sub_components() {
+        let (mut document, plasmid) = plasmid();
+        let library = library();
+        let features = annotate(
+            &mut document,
+            &plasmid,
+            &library,
+            &AnnotationOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            features,
+            [
+                uri("https://example.org/plasmid/SubComponent1"),
+                uri("https://example.org/plasmid/SubComponent2"),
+            ]
+        );
+        let component = document.component(&plasmid).unwrap();
+        let terminator = &component.sub_components[1];
+        assert_eq!(terminator.instance_of, uri("https://parts.org/terminator"));
+        let bounds: Vec<_> = terminator
+            .has_location
+            .iter()
+            .map(|location| match location {
+                Location::Range(range) => (range.start, range.end),
+                _ => panic!("Expected a Range, found {location:?}"),
+            })
+            .collect();
+        assert_eq!(bounds, [(1, 2), (11, 16)]);
+        assert_eq!(
+            terminator.has_location[0].orientation(),
+            Some(&Orientation::ReverseComplement)
+        );
+
+        let mut merged = document.clone();
+        merged.extend(library.clone());
+        assert!(sequence_mismatches(&merged).is_empty());
+
+        // Annotating again finds nothing new
+        let features = annotate(
+            &mut document,
+            &plasmid,
+            &library,
+            &AnnotationOptions::default(),
+        )
+        .unwrap();
+        assert!(features.is_empty());
+
+        // Nor when the orientation is recorded with the SBOL3 terms
+        let component = document.component_mut(&plasmid).unwrap();
+        for sub_component in &mut component.sub_components {
+            for location in &mut sub_component.has_location {
+                if let Location::Range(range) = location {
+                    range.orientation = match range.orientation {
+                        Some(Orientation::Inline) => Some(Orientation::InlineAlt),
+                        _ => Some(Orientation::ReverseComplementAlt),
+                    };
+                }
+            }
+        }
+        let features = annotate(
+            &mut document,
+            &plasmid,
+            &library,
+            &AnnotationOptions::default(),
+        )
+        .unwrap();
+        assert!(features.is_empty());
+    }
+
+    #[test]
+    fn test_annotate_sequence_features() {
+        let (mut document, plasmid) = plasmid();
+        let options = AnnotationOptions {
+            kind: FeatureKind::SequenceFeature,
+            min_length: 7,
+        };
+        let features = annotate(&mut document, &plasmid, &library(), &options).unwrap();
+        assert_eq!(
+            features,
+            [uri("https://example.org/plasmid/SequenceFeature1")]
+        );
+
+        let options = AnnotationOptions {
+            kind: FeatureKind::SequenceFeature,
+            min_length: 0,
+        };
+        annotate(&mut document, &plasmid, &library(), &options).unwrap();
+        let component = document.component(&plasmid).unwrap();
+        let promoter = &component.sequence_features[1];
+        assert_eq!(promoter.name.as_deref(), Some("Promoter"));
+        assert_eq!(promoter.role, [ComponentRole::Promoter]);
+        assert_eq!(promoter.derived_from, [uri("https://parts.org/promoter")]);
+        assert!(component.sub_components.is_empty());
+    }
+}
@@ -0,0 +1,54 @@
+use url::Url;
+
+/// Application-specific property of an `Identified` object, which is not defined by SBOL.
+///
+/// Annotations allow data from other tools (ie: the status of a part in a repository) or from
+/// other versions of SBOL to be carried along with the object that it describes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Annotation {
+    /// URI of the property, which must not be in the SBOL namespace
+    pub property: Url,
+    pub value: AnnotationValue,
+}
+
+/// Value of an `Annotation`, or of any RDF property
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum AnnotationValue {
+    Uri(Url),
+    Literal(String),
+}
+
+impl Annotation {
+    /// Annotation whose value is a reference to another resource
+    pub fn uri(property: Url, value: Url) -> Self {
+        Self {
+            property,
+            value: AnnotationValue::Uri(value),
+        }
+    }
+
+    /// Annotation whose value is a string literal
+    pub fn literal(property: Url, value: impl Into<String>) -> Self {
+        Self {
+            property,
+            value: AnnotationValue::Literal(value.into()),
+        }
+    }
+}
+
+impl AnnotationValue {
+    pub fn as_uri(&self) -> Option<&Url> {
+        match self {
+            Self::Uri(uri) => Some(uri),
+            Self::Literal(_) => None,
+        }
+    }
+
+    /// Lexical form of a literal, or the text of a URI
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Uri(uri) => uri.as_str(),
+            Self::Literal(literal) => literal,
+        }
+    }
+}
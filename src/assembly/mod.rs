@@ -0,0 +1,617 @@
+//! Computing the `Sequence` of a `Component` from the sequences of its `SubComponent` objects.
+//!
+//! This is the "lower level" to "higher level" mapping described by [`Component::has_feature`]:
+//! the elements of each included `Component` are placed, in their `orientation`, at the position
+//! of their `SubComponent` within the parent.
+
+use std::collections::HashMap;
+
+use url::Url;
+
+use crate::coordinates::{Coordinates, Span};
+use crate::ontologies::{Encoding, Orientation, Restriction};
+use crate::sequence::nucleic_acid;
+use crate::uris::child_display_id;
+use crate::{Component, Document, Location, Range, SbolError, Sequence, SubComponent};
+
+/// Position of a `SubComponent` within an assembled sequence
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Placement {
+    /// `SubComponent` being placed
+    pub sub_component: Url,
+
+    /// Inclusive, 1-based position of the first element of the `SubComponent`
+    pub start: usize,
+
+    /// Inclusive, 1-based position of the last element of the `SubComponent`, which precedes
+    /// `start` if the `SubComponent` spans the origin of a circular sequence
+    pub end: usize,
+
+    pub orientation: Orientation,
+}
+
+/// Elements of a `Component` computed from its `SubComponent` objects
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Assembly {
+    pub elements: String,
+
+    /// Placement of each `SubComponent`, in order of position
+    pub placements: Vec<Placement>,
+}
+
+/// `SubComponent` whose elements are known
+struct Part<'a> {
+    sub_component: &'a SubComponent,
+    /// Elements in the orientation of the `SubComponent`
+    elements: String,
+    orientation: Orientation,
+    /// Region covered by the `Range` locations of the `SubComponent`
+    span: Option<Span>,
+}
+
+/// Compute the nucleic acid elements of the `Component` identified by `component` from the
+/// sequences of its `SubComponent` objects.
+///
+/// Each `SubComponent` contributes the nucleic acid `Sequence` of the `Component` that it is an
+/// instance of, restricted to its `source_location` if set. Included `Component` objects without
+/// a `Sequence` are assembled recursively. Elements are reverse complemented when the `Range` or
+/// the `SubComponent` has a reverse complement `orientation`.
+///
+/// The elements must be IUPAC nucleotide codes, and the `Range` locations of a `SubComponent`
+/// must form a single region, which may span the origin of a circular `Component`. When every
+/// `SubComponent` has a `Range` location, parts are placed at their positions: the parts must
+/// cover the whole sequence without gaps, and overlapping parts must agree. Otherwise parts are
+/// concatenated in the order given by `precedes`, `strictlyPrecedes` and `meets` constraints,
+/// unconstrained parts keeping their order within the `Component`.
+pub fn assemble(document: &Document, component: &Url) -> Result<Assembly, SbolError> {
+    assemble_component(document, component, &mut vec![])
+}
+
+/// Assemble the `Component` identified by `component` (see [`assemble`]) into a new nucleic acid
+/// `Sequence` identified by `display_id` within the namespace of the `Component`.
+///
+/// The new `Sequence` becomes the first `has_sequence` of the `Component`, and each
+/// `SubComponent` without a `Range` location is given one on the new `Sequence`. Returns the URI of
+/// the new `Sequence`.
+pub fn assemble_sequence(
+    document: &mut Document,
+    component: &Url,
+    display_id: &str,
+) -> Result<Url, SbolError> {
+    let assembly = assemble(document, component)?;
+    let parent = document
+        .component_mut(component)
+        .ok_or_else(|| SbolError::UnresolvedReference(component.clone()))?;
+    let mut sequence = Sequence::new(parent.namespace.clone(), display_id)?;
+    let uri = sequence.identity.clone();
+    sequence.elements = Some(assembly.elements);
+    sequence.encoding = Some(Encoding::NucleicAcid);
+
+    for placement in assembly.placements {
+        let Some(sub_component) = parent
+            .sub_components
+            .iter_mut()
+            .find(|sub_component| sub_component.identity == placement.sub_component)
+        else {
+            continue;
+        };
+        let located = sub_component
+            .has_location
+            .iter()
+            .any(|location| matches!(location, Location::Range(_)));
+        if !located {
+            let display_id = child_display_id(
+                "Range",
+                sub_component
+                    .has_location
+                    .iter()
+                    .chain(&sub_component.source_location),
+            );
+            let mut range = Range::new(
+                &sub_component.identity,
+                &display_id,
+                uri.clone(),
+                placement.start,
+                placement.end,
+            )?;
+            range.orientation = Some(placement.orientation);
+            sub_component.has_location.push(Location::Range(range));
+        }
+    }
+    parent.has_sequence.insert(0, uri.clone());
+    document.sequences.push(sequence);
+    Ok(uri)
+}
+
+fn assemble_component(
+    document: &Document,
+    uri: &Url,
+    stack: &mut Vec<Url>,
+) -> Result<Assembly, SbolError> {
+    if stack.contains(uri) {
+        return Err(SbolError::Validation(format!(
+            "{uri} includes itself through its SubComponent objects"
+        )));
+    }
+    let component = document
+        .component(uri)
+        .ok_or_else(|| SbolError::UnresolvedReference(uri.clone()))?;
+    if component.sub_components.is_empty() {
+        return Err(SbolError::Validation(format!(
+            "{uri} has no Sequence and no SubComponent to assemble it from"
+        )));
+    }
+
+    // The assembled sequence ends with the last position of a `Range`
+    let length = component
+        .sub_components
+        .iter()
+        .flat_map(ranges)
+        .map(|range| range.start.max(range.end))
+        .max()
+        .unwrap_or_default();
+    let coordinates = Coordinates::new(length, component.is_circular());
+
+    stack.push(uri.clone());
+    let mut parts = vec![];
+    for sub_component in &component.sub_components {
+        let range = ranges(sub_component).next();
+        let span = coordinates.span(&sub_component.has_location)?;
+        if range.is_some() && span.is_none() {
+            return Err(SbolError::Validation(format!(
+                "The Range locations of {} do not form a single region",
+                sub_component.identity
+            )));
+        }
+        let orientation = range
+            .and_then(|range| range.orientation.clone())
+            .or_else(|| sub_component.orientation.clone())
+            .unwrap_or(Orientation::Inline);
+        let elements = part_elements(document, sub_component, stack)?;
+        nucleic_acid::validate(&elements)?;
+        parts.push(Part {
+            sub_component,
+            elements: nucleic_acid::orient(&elements, &orientation)?,
+            orientation,
+            span,
+        });
+    }
+    stack.pop();
+
+    if parts.iter().all(|part| part.span.is_some()) {
+        by_position(&coordinates, parts)
+    } else {
+        by_order(component, parts)
+    }
+}
+
+/// `Range` locations of `sub_component`
+fn ranges(sub_component: &SubComponent) -> impl Iterator<Item = &Range> {
+    sub_component
+        .has_location
+        .iter()
+        .filter_map(|location| match location {
+            Location::Range(range) => Some(range),
+            _ => None,
+        })
+}
+
+/// Elements of the `Component` that `sub_component` is an instance of, restricted to its
+/// `source_location`
+fn part_elements(
+    document: &Document,
+    sub_component: &SubComponent,
+    stack: &mut Vec<Url>,
+) -> Result<String, SbolError> {
+    let child = document
+        .component(&sub_component.instance_of)
+        .ok_or_else(|| SbolError::UnresolvedReference(sub_component.instance_of.clone()))?;
+    let elements = match document.nucleic_acid_sequence(child) {
+        Some(sequence) => sequence.nucleic_acid_elements()?.to_string(),
+        None => assemble_component(document, &child.identity, stack)?.elements,
+    };
+    sub_component.source_elements(&elements)
+}
+
+/// Place every part at the position of its `Range` locations, on a sequence of `coordinates`
+fn by_position(coordinates: &Coordinates, mut parts: Vec<Part>) -> Result<Assembly, SbolError> {
+    parts.sort_by_key(|part| part.span.map(|span| (span.start, span.end)));
+    let mut placed: Vec<Option<char>> = vec![None; coordinates.length];
+    let mut placements = vec![];
+    for part in &parts {
+        let Some(span) = part.span else {
+            continue;
+        };
+        let identity = &part.sub_component.identity;
+        if coordinates.len(span)? != part.elements.len() {
+            return Err(SbolError::Validation(format!(
+                "{identity} is located at {}..{}, which does not fit its {} elements",
+                span.start,
+                span.end,
+                part.elements.len()
+            )));
+        }
+        for (i, element) in part.elements.chars().enumerate() {
+            let position = (span.start - 1 + i) % coordinates.length;
+            placed[position].get_or_insert(element);
+        }
+        placements.push(Placement {
+            sub_component: identity.clone(),
+            start: span.start,
+            end: span.end,
+            orientation: part.orientation.clone(),
+        });
+    }
+    if let Some(start) = placed.iter().position(Option::is_none) {
+        let end = placed[start..]
+            .iter()
+            .position(Option::is_some)
+            .map_or(placed.len(), |length| start + length);
+        return Err(SbolError::Validation(format!(
+            "No SubComponent covers positions {}..{end}",
+            start + 1
+        )));
+    }
+    let elements: String = placed.into_iter().flatten().collect();
+
+    // Elements which overlap other parts must agree with them
+    for part in &parts {
+        let Some(span) = part.span else {
+            continue;
+        };
+        if !coordinates
+            .extract(&elements, span)?
+            .eq_ignore_ascii_case(&part.elements)
+        {
+            return Err(SbolError::Validation(format!(
+                "{} does not match the overlapping SubComponent objects at {}..{}",
+                part.sub_component.identity, span.start, span.end
+            )));
+        }
+    }
+    Ok(Assembly {
+        elements,
+        placements,
+    })
+}
+
+/// Concatenate parts in the order given by the constraints of `component`
+fn by_order(component: &Component, parts: Vec<Part>) -> Result<Assembly, SbolError> {
+    let index: HashMap<&Url, usize> = parts
+        .iter()
+        .enumerate()
+        .map(|(i, part)| (&part.sub_component.identity, i))
+        .collect();
+    let mut successors = vec![vec![]; parts.len()];
+    let mut predecessors = vec![0; parts.len()];
+    for constraint in &component.has_constraint {
+        let ordering = matches!(
+            constraint.restriction,
+            Restriction::Precedes | Restriction::StrictlyPrecedes | Restriction::Meets
+        );
+        if let (true, Some(&subject), Some(&object)) = (
+            ordering,
+            index.get(&constraint.subject),
+            index.get(&constraint.object),
+        ) {
+            successors[subject].push(object);
+            predecessors[object] += 1;
+        }
+    }
+
+    // Topological sort, which keeps the order of the `Component` among unconstrained parts
+    let mut parts: Vec<Option<Part>> = parts.into_iter().map(Some).collect();
+    let mut elements = String::new();
+    let mut placements = vec![];
+    while let Some(next) = (0..parts.len()).find(|&i| parts[i].is_some() && predecessors[i] == 0) {
+        let Some(part) = parts[next].take() else {
+            break;
+        };
+        for &successor in &successors[next] {
+            predecessors[successor] -= 1;
+        }
+        placements.push(Placement {
+            sub_component: part.sub_component.identity.clone(),
+            start: elements.len() + 1,
+            end: elements.len() + part.elements.len(),
+            orientation: part.orientation,
+        });
+        elements.push_str(&part.elements);
+    }
+    if parts.iter().any(Option::is_some) {
+        return Err(SbolError::Validation(format!(
+            "The constraints of {} order its SubComponent objects in a cycle",
+            component.identity
+        )));
+    }
+    Ok(Assembly {
+        elements,
+        placements,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ontologies::{ComponentTypeOntology, TopologyOntology};
+    use crate::test_utils::{dna, uri};
+    use crate::{ComponentType, Constraint};
+
+    /// Document with one part `Component` per `(display_id, elements)` pair, and an empty
+    /// `device` Component
+    fn library(parts: &[(&str, &str)]) -> Document {
+        let namespace = uri("https://example.org");
+        let mut document = Document::new();
+        for (display_id, elements) in parts {
+            dna(&mut document, &namespace, display_id, elements, &[]);
+        }
+        document
+            .components
+            .push(Component::new(namespace, "device").unwrap());
+        document
+    }
+
+    fn add_part(document: &mut Document, display_id: &str, part: &str) -> Url {
+        let device = document
+            .component_mut(&uri("https://example.org/device"))
+            .unwrap();
+        let sub_component = SubComponent::new(
+            &device.identity,
+            display_id,
+            uri(&format!("https://example.org/{part}")),
+        )
+        .unwrap();
+        let identity = sub_component.identity.clone();
+        device.sub_components.push(sub_component);
+        identity
+    }
+
+    #[test]
+    fn test_by_order() {
+        let mut document = library(&[("promoter", "ttgaca"), ("cds", "atgaaa")]);
+        let cds = add_part(&mut document, "cds", "cds");
+        let promoter = add_part(&mut document, "promoter", "promoter");
+        let device = document
+            .component_mut(&uri("https://example.org/device"))
+            .unwrap();
+        device.sub_components[0].orientation = Some(Orientation::ReverseComplement);
+        let constraint = Constraint::new(
+            &device.identity,
+            "order",
+            Restriction::Precedes,
+            promoter.clone(),
+            cds.clone(),
+        )
+        .unwrap();
+        device.has_constraint.push(constraint);
+
+        let assembly = assemble(&document, &uri("https://example.org/device")).unwrap();
+        assert_eq!(assembly.elements, "ttgacatttcat");
+        assert_eq!(
+            assembly.placements[1],
+            Placement {
+                sub_component: cds,
+                start: 7,
+                end: 12,
+                orientation: Orientation::ReverseComplement,
+            }
+        );
+
+        // A cycle cannot be ordered
+        let device = document
+            .component_mut(&uri("https://example.org/device"))
+            .unwrap();
+        let constraint = Constraint::new(
+            &device.identity,
+            "cycle",
+            Restriction::Meets,
+            device.sub_components[0].identity.clone(),
+            promoter,
+        )
+        .unwrap();
+        device.has_constraint.push(constraint);
+        let result = assemble(&document, &uri("https://example.org/device"));
+        assert!(matches!(result, Err(SbolError::Validation(_))));
+    }
+
+    #[test]
+    fn test_by_position() {
+        let mut document = library(&[("promoter", "ttgaca"), ("cds", "acaatg")]);
+        add_part(&mut document, "promoter", "promoter");
+        add_part(&mut document, "cds", "cds");
+        let sequence = uri("https://example.org/device_seq");
+        let device = document
+            .component_mut(&uri("https://example.org/device"))
+            .unwrap();
+        for (sub_component, start) in device.sub_components.iter_mut().zip([1, 4]) {
+            let range = Range::new(
+                &sub_component.identity,
+                "range",
+                sequence.clone(),
+                start,
+                start + 5,
+            )
+            .unwrap();
+            sub_component.has_location.push(Location::Range(range));
+        }
+        // Overlapping parts which agree are merged
+        let assembly = assemble(&document, &uri("https://example.org/device")).unwrap();
+        assert_eq!(assembly.elements, "ttgacaatg");
+
+        let device = document
+            .component_mut(&uri("https://example.org/device"))
+            .unwrap();
+        let Location::Range(range) = &mut device.sub_components[1].has_location[0] else {
+            unreachable!()
+        };
+        (range.start, range.end) = (8, 13);
+        let result = assemble(&document, &uri("https://example.org/device"));
+        assert!(matches!(result, Err(SbolError::Validation(msg)) if msg.contains("7..7")));
+
+        // A part split across ranges which leave a gap is rejected
+        let device = document
+            .component_mut(&uri("https://example.org/device"))
+            .unwrap();
+        let sub_component = &mut device.sub_components[1];
+        let range = Range::new(&sub_component.identity, "range2", sequence, 15, 16).unwrap();
+        sub_component.has_location.push(Location::Range(range));
+        let result = assemble(&document, &uri("https://example.org/device"));
+        assert!(matches!(result, Err(SbolError::Validation(msg)) if msg.contains("single region")));
+
+        // Elements which are not nucleotides are rejected rather than sliced
+        let mut document = library(&[("promoter", "ttgaça"), ("cds", "acaatg")]);
+        for (display_id, start) in [("promoter", 1), ("cds", 4)] {
+            let part = add_part(&mut document, display_id, display_id);
+            let device = document
+                .component_mut(&uri("https://example.org/device"))
+                .unwrap();
+            let sub_component = device
+                .sub_components
+                .iter_mut()
+                .find(|sub_component| sub_component.identity == part)
+                .unwrap();
+            let sequence = uri("https://example.org/device_seq");
+            let range = Range::new(&part, "range", sequence, start, start + 5).unwrap();
+            sub_component.has_location.push(Location::Range(range));
+        }
+        let result = assemble(&document, &uri("https://example.org/device"));
+        assert!(matches!(result, Err(SbolError::Validation(_))));
+    }
+
+    #[test]
+    fn test_origin_spanning_part() {
+        let mut document = library(&[("promoter", "ttgaca"), ("cds", "atgaaa")]);
+        add_part(&mut document, "promoter", "promoter");
+        let cds = add_part(&mut document, "cds", "cds");
+        let sequence = uri("https://example.org/device_seq");
+        let device = document
+            .component_mut(&uri("https://example.org/device"))
+            .unwrap();
+        device.r#type = vec![
+            ComponentType::Type(ComponentTypeOntology::DNA),
+            ComponentType::Topology(TopologyOntology::Circular),
+        ];
+        let bounds = [vec![(4, 9)], vec![(10, 12), (1, 3)]];
+        for (sub_component, bounds) in device.sub_components.iter_mut().zip(bounds) {
+            for (i, (start, end)) in bounds.into_iter().enumerate() {
+                let display_id = format!("Range{}", i + 1);
+                let range = Range::new(
+                    &sub_component.identity,
+                    &display_id,
+                    sequence.clone(),
+                    start,
+                    end,
+                )
+                .unwrap();
+                sub_component.has_location.push(Location::Range(range));
+            }
+        }
+        let assembly = assemble(&document, &uri("https://example.org/device")).unwrap();
+        assert_eq!(assembly.elements, "aaattgacaatg");
+        assert_eq!(
+            assembly.placements[1],
+            Placement {
+                sub_component: cds,
+                start: 10,
+                end: 3,
+                orientation: Orientation::Inline,
+            }
+        );
+
+        // Only the ranges of a circular Component are joined across the origin
+        let device = document
+            .component_mut(&uri("https://example.org/device"))
+            .unwrap();
+        device.r#type.pop();
+        let result = assemble(&document, &uri("https://example.org/device"));
+        assert!(matches!(result, Err(SbolError::Validation(msg)) if msg.contains("single region")));
+    }
+
+    #[test]
+    fn test_assemble_sequence() {
+        let mut document = library(&[("promoter", "ttgaca"), ("cds", "atgaaa")]);
+        add_part(&mut document, "promoter", "promoter");
+        add_part(&mut document, "cds", "cds");
+        // The device is itself assembled as part of a larger construct
+        let mut construct = Component::new(uri("https://example.org"), "construct").unwrap();
+        let mut sub_component = SubComponent::new(
+            &construct.identity,
+            "device",
+            uri("https://example.org/device"),
+        )
+        .unwrap();
+        let source = Range::new(
+            &sub_component.identity,
+            "source",
+            uri("https://example.org/device_seq"),
+            4,
+            9,
+        )
+        .unwrap();
+        sub_component.source_location.push(Location::Range(source));
+        construct.sub_components.push(sub_component);
+        document.components.push(construct);
+
+        let construct_uri = uri("https://example.org/construct");
+        let assembly = assemble(&document, &construct_uri).unwrap();
+        assert_eq!(assembly.elements, "acaatg");
+
+        let sequence = assemble_sequence(
+            &mut document,
+            &uri("https://example.org/device"),
+            "device_seq",
+        )
+        .unwrap();
+        assert_eq!(
+            document.sequence(&sequence).unwrap().elements.as_deref(),
+            Some("ttgacaatgaaa")
+        );
+        let device = document
+            .component(&uri("https://example.org/device"))
+            .unwrap();
+        assert_eq!(device.has_sequence, vec![sequence.clone()]);
+        let Location::Range(range) = &device.sub_components[1].has_location[0] else {
+            panic!("Expected a Range");
+        };
+        assert_eq!((range.start, range.end), (7, 12));
+        assert_eq!(range.has_sequence, sequence);
+    }
+
+    #[test]
+    fn test_source_ranges() {
+        let mut document = library(&[("promoter", "ttgacatataat")]);
+        add_part(&mut document, "promoter", "promoter");
+        let sequence = uri("https://example.org/promoter_seq");
+        let device = document
+            .component_mut(&uri("https://example.org/device"))
+            .unwrap();
+        let sub_component = &mut device.sub_components[0];
+        // The -35 box, followed by the reverse complement of the -10 box
+        for (display_id, start, end) in [("minus35", 1, 6), ("minus10", 7, 12)] {
+            let mut range = Range::new(
+                &sub_component.identity,
+                display_id,
+                sequence.clone(),
+                start,
+                end,
+            )
+            .unwrap();
+            if display_id == "minus10" {
+                range.orientation = Some(Orientation::ReverseComplement);
+            }
+            sub_component.source_location.push(Location::Range(range));
+        }
+        let assembly = assemble(&document, &uri("https://example.org/device")).unwrap();
+        assert_eq!(assembly.elements, "ttgacaattata");
+
+        let device = document
+            .component_mut(&uri("https://example.org/device"))
+            .unwrap();
+        let Location::Range(range) = &mut device.sub_components[0].source_location[1] else {
+            unreachable!()
+        };
+        range.start = 0;
+        let result = assemble(&document, &uri("https://example.org/device"));
+        assert!(matches!(result, Err(SbolError::Validation(msg)) if msg.contains("starts at 0")));
+    }
+}
@@ -0,0 +1,363 @@
+//! Digest of DNA by restriction enzymes.
+//!
+//! Each recognition site found on either strand is cut on both strands, at the positions given
+//! by its [`Enzyme`]. When the two cuts differ, the fragments keep single-stranded overhangs
+//! ("sticky ends"), which is why a [`Fragment`] spans the elements of both of its strands.
+
+use url::Url;
+
+use crate::ontologies::{so_role, Orientation};
+use crate::search::{self, SearchOptions};
+use crate::sequence::nucleic_acid;
+use crate::{Component, Cut, Document, Location, SbolError, SequenceFeature};
+
+use super::{new_activity, new_dna, Enzyme};
+
+/// End of a `Fragment` which was cut by an enzyme
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct End {
+    /// Number of elements of the fragment before the cut of the top strand
+    pub top: usize,
+
+    /// Number of elements of the fragment before the cut of the bottom strand
+    pub bottom: usize,
+}
+
+impl End {
+    /// Length of the single-stranded overhang: positive for a 5' overhang, negative for a 3'
+    /// overhang and zero for a blunt end.
+    pub fn overhang(&self) -> i64 {
+        self.bottom as i64 - self.top as i64
+    }
+}
+
+/// Double-stranded piece of DNA resulting from a digest
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fragment {
+    /// Elements of the top strand, extended by the overhangs of the bottom strand
+    pub elements: String,
+
+    /// 0-based position of the first element of the fragment within the digested elements
+    pub offset: usize,
+
+    /// Cut at the beginning of the fragment, `None` at the beginning of a linear molecule
+    pub left: Option<End>,
+
+    /// Cut at the end of the fragment, `None` at the end of a linear molecule
+    pub right: Option<End>,
+}
+
+impl Fragment {
+    /// Elements of the top strand
+    pub fn top_strand(&self) -> &str {
+        let start = self.left.map_or(0, |end| end.top);
+        let end = self.right.map_or(self.elements.len(), |end| end.top);
+        &self.elements[start..end]
+    }
+
+    /// Single-stranded elements of `end`, as read on the top strand
+    pub fn overhang(&self, end: &End) -> &str {
+        &self.elements[end.top.min(end.bottom)..end.top.max(end.bottom)]
+    }
+}
+
+/// Positions after which the top and bottom strands are cut, in elements of the top strand
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct Cleavage {
+    top: i64,
+    bottom: i64,
+}
+
+/// Fragments of `elements` digested by `enzymes`, in the order of their cuts.
+///
+/// Sites are searched on both strands, and across the origin when `circular` is set. Cuts outside
+/// of a linear molecule are ignored. Returns no fragment if no site is found.
+pub fn fragments(
+    elements: &str,
+    circular: bool,
+    enzymes: &[&Enzyme],
+) -> Result<Vec<Fragment>, SbolError> {
+    nucleic_acid::validate(elements)?;
+    let length = elements.len() as i64;
+    let options = SearchOptions {
+        degenerate: true,
+        both_strands: true,
+        circular,
+    };
+    let mut cleavages = vec![];
+    for enzyme in enzymes {
+        for hit in search::find(elements, enzyme.site, &options)? {
+            let start = hit.span.start as i64 - 1;
+            let end = start + enzyme.site.len() as i64;
+            let (top, bottom) = match hit.orientation {
+                Orientation::ReverseComplement => (end - enzyme.bottom_cut, end - enzyme.top_cut),
+                _ => (start + enzyme.top_cut, start + enzyme.bottom_cut),
+            };
+            if circular {
+                let shift = top.rem_euclid(length) - top;
+                cleavages.push(Cleavage {
+                    top: top + shift,
+                    bottom: bottom + shift,
+                });
+            } else if [top, bottom].iter().all(|cut| (1..length).contains(cut)) {
+                cleavages.push(Cleavage { top, bottom });
+            }
+        }
+    }
+    cleavages.sort();
+    cleavages.dedup();
+
+    let bounds: Vec<(Option<Cleavage>, Option<Cleavage>)> = if circular {
+        (0..cleavages.len())
+            .map(|i| {
+                let right = match cleavages.get(i + 1) {
+                    Some(right) => *right,
+                    None => Cleavage {
+                        top: cleavages[0].top + length,
+                        bottom: cleavages[0].bottom + length,
+                    },
+                };
+                (Some(cleavages[i]), Some(right))
+            })
+            .collect()
+    } else if cleavages.is_empty() {
+        vec![]
+    } else {
+        let mut ends: Vec<Option<Cleavage>> = vec![None];
+        ends.extend(cleavages.iter().copied().map(Some));
+        ends.push(None);
+        ends.windows(2).map(|pair| (pair[0], pair[1])).collect()
+    };
+
+    let bytes = elements.as_bytes();
+    let mut fragments = vec![];
+    for (left, right) in bounds {
+        let first = left.map_or(0, |cut| cut.top.min(cut.bottom));
+        let last = right.map_or(length, |cut| cut.top.max(cut.bottom));
+        // Sites too close to each other leave no fragment between their cuts
+        if let (Some(left), Some(right)) = (left, right) {
+            if right.top < left.top || right.bottom < left.bottom {
+                continue;
+            }
+        }
+        let end = |cut: Cleavage| End {
+            top: (cut.top - first) as usize,
+            bottom: (cut.bottom - first) as usize,
+        };
+        fragments.push(Fragment {
+            elements: (first..last)
+                .map(|position| bytes[position.rem_euclid(length) as usize] as char)
+                .collect(),
+            offset: first.rem_euclid(length) as usize,
+            left: left.map(end),
+            right: right.map(end),
+        });
+    }
+    Ok(fragments)
+}
+
+/// Digest the nucleic acid `Sequence` of `component` with `enzymes`, and add each fragment to
+/// `document` as a linear DNA `Component`. Returns the URIs of the fragments, in the order of their
+/// cuts.
+///
+/// Fragments are named after `component` within its namespace, and each end which was cut is
+/// recorded by a `SequenceFeature` whose role is the kind of cleavage site (5' sticky, 3' sticky or
+/// blunt) and whose `Cut` locations are the cuts of the top strand (inline) and of the bottom
+/// strand (reverse complement).
+pub fn digest(
+    document: &mut Document,
+    component: &Url,
+    enzymes: &[&Enzyme],
+) -> Result<Vec<Url>, SbolError> {
+    let source = document
+        .component(component)
+        .ok_or_else(|| SbolError::UnresolvedReference(component.clone()))?;
+    let sequence = document.nucleic_acid_sequence(source).ok_or_else(|| {
+        SbolError::Validation(format!("{component} has no nucleic acid Sequence"))
+    })?;
+    let fragments = fragments(
+        sequence.nucleic_acid_elements()?,
+        source.is_circular(),
+        enzymes,
+    )?;
+    let source_sequence = sequence.identity.clone();
+    let namespace = source.namespace.clone();
+    let prefix = source
+        .display_id
+        .clone()
+        .unwrap_or_else(|| "fragment".to_string());
+
+    let names: Vec<&str> = enzymes.iter().map(|enzyme| enzyme.name).collect();
+    let activity = new_activity(
+        document,
+        &namespace,
+        &format!("{prefix}_digest"),
+        format!("Digest with {}", names.join(", ")),
+        std::slice::from_ref(component),
+    )?;
+    let activity_uri = activity.identity.clone();
+    document.activities.push(activity);
+
+    let mut uris = vec![];
+    for (i, fragment) in fragments.iter().enumerate() {
+        let (mut dna, mut sequence) = new_dna(
+            document,
+            &namespace,
+            &format!("{prefix}_fragment{}", i + 1),
+            fragment.elements.clone(),
+            false,
+        )?;
+        dna.derived_from = vec![component.clone()];
+        dna.generated_by = vec![activity_uri.clone()];
+        sequence.derived_from = vec![source_sequence.clone()];
+        for (display_id, end) in [("left_end", fragment.left), ("right_end", fragment.right)] {
+            if let Some(end) = end {
+                let feature = end_feature(&dna, &sequence.identity, display_id, &end)?;
+                dna.sequence_features.push(feature);
+            }
+        }
+        uris.push(dna.identity.clone());
+        document.sequences.push(sequence);
+        document.components.push(dna);
+    }
+    Ok(uris)
+}
+
+/// `SequenceFeature` of `fragment` which records the cuts of `end`
+fn end_feature(
+    fragment: &Component,
+    sequence: &Url,
+    display_id: &str,
+    end: &End,
+) -> Result<SequenceFeature, SbolError> {
+    let mut feature = SequenceFeature::new(&fragment.identity, display_id)?;
+    let site = match end.overhang() {
+        0 => "blunt_end_restriction_enzyme_cleavage_site",
+        overhang if overhang > 0 => "five_prime_sticky_end_restriction_enzyme_cleavage_site",
+        _ => "three_prime_sticky_end_restriction_enzyme_cleavage_site",
+    };
+    feature.role = so_role(site).into_iter().collect();
+    for (display_id, at, orientation) in [
+        ("top", end.top, Orientation::Inline),
+        ("bottom", end.bottom, Orientation::ReverseComplement),
+    ] {
+        let mut cut = Cut::new(&feature.identity, display_id, sequence.clone(), at)?;
+        cut.orientation = Some(orientation);
+        feature.has_location.push(Location::Cut(cut));
+    }
+    Ok(feature)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cloning::enzymes;
+    use crate::ontologies::TopologyOntology;
+    use crate::test_utils::{dna, uri};
+    use crate::Identified;
+
+    fn enzyme(name: &str) -> &'static Enzyme {
+        enzymes::find(name).unwrap()
+    }
+
+    fn digested(elements: &str, circular: bool, names: &[&str]) -> Vec<Fragment> {
+        let enzymes: Vec<_> = names.iter().map(|name| enzyme(name)).collect();
+        fragments(elements, circular, &enzymes).unwrap()
+    }
+
+    #[test]
+    fn test_fragments() {
+        // EcoRI leaves 5' AATT overhangs
+        let fragments = digested("ccGAATTCgg", false, &["EcoRI"]);
+        assert_eq!(fragments.len(), 2);
+        assert_eq!(fragments[0].elements, "ccGAATT");
+        assert_eq!(fragments[0].top_strand(), "ccG");
+        assert_eq!(fragments[1].elements, "AATTCgg");
+        assert_eq!(fragments[1].top_strand(), "AATTCgg");
+        let end = fragments[1].left.unwrap();
+        assert_eq!(end.overhang(), 4);
+        assert_eq!(fragments[1].overhang(&end), "AATT");
+        assert_eq!(fragments[1].offset, 3);
+
+        // PstI leaves 3' overhangs, EcoRV blunt ends
+        let fragments = digested("aCTGCAGa", false, &["ecorv", "PstI"]);
+        assert_eq!(fragments[0].right.unwrap().overhang(), -4);
+        assert_eq!(fragments[0].top_strand(), "aCTGCA");
+        let fragments = digested("aaGATATCaa", false, &["EcoRV"]);
+        assert_eq!(fragments[0].right.unwrap().overhang(), 0);
+        assert!(digested("aaaa", false, &["EcoRI"]).is_empty());
+    }
+
+    #[test]
+    fn test_type_iis_fragments() {
+        // BsaI cuts downstream of GGTCTC, and upstream of GAGACC on the other strand
+        let elements = "GGTCTCaAATGcccGCTTaGAGACC";
+        let fragments = digested(elements, false, &["BsaI"]);
+        assert_eq!(fragments.len(), 3);
+        let insert = &fragments[1];
+        assert_eq!(insert.top_strand(), "AATGccc");
+        assert_eq!(insert.overhang(&insert.left.unwrap()), "AATG");
+        assert_eq!(insert.overhang(&insert.right.unwrap()), "GCTT");
+
+        // A single site on a circular molecule linearizes it
+        let fragments = digested("GGTCTCaAATGccc", true, &["BsaI"]);
+        assert_eq!(fragments.len(), 1);
+        assert_eq!(fragments[0].elements, "AATGcccGGTCTCaAATG");
+        assert_eq!(fragments[0].top_strand(), "AATGcccGGTCTCa");
+    }
+
+    #[test]
+    fn test_digest() {
+        let mut document = Document::new();
+        let plasmid_uri = dna(
+            &mut document,
+            &uri("https://example.org"),
+            "plasmid",
+            "ccGAATTCggGGATCCtt",
+            &[TopologyOntology::Circular],
+        );
+
+        let fragments = digest(
+            &mut document,
+            &plasmid_uri,
+            &[enzyme("EcoRI"), enzyme("BamHI")],
+        )
+        .unwrap();
+        assert_eq!(fragments.len(), 2);
+        let fragment = document.component(&fragments[1]).unwrap();
+        assert_eq!(
+            fragment.identity.as_str(),
+            "https://example.org/plasmid_fragment2"
+        );
+        assert_eq!(
+            document
+                .nucleic_acid_sequence(fragment)
+                .unwrap()
+                .elements
+                .as_deref(),
+            Some("GATCCttccGAATT")
+        );
+        assert_eq!(fragment.derived_from, std::slice::from_ref(&plasmid_uri));
+        let activity = document.activity(&fragment.generated_by[0]).unwrap();
+        assert_eq!(activity.name.as_deref(), Some("Digest with EcoRI, BamHI"));
+        assert_eq!(activity.has_usage[0].entity, plasmid_uri);
+
+        let right_end = &fragment.sequence_features[1];
+        assert_eq!(right_end.display_id().as_deref(), Some("right_end"));
+        assert_eq!(
+            right_end.role,
+            so_role("five_prime_sticky_end_restriction_enzyme_cleavage_site")
+                .into_iter()
+                .collect::<Vec<_>>()
+        );
+        let cuts: Vec<_> = right_end
+            .has_location
+            .iter()
+            .map(|location| match location {
+                Location::Cut(cut) => cut.at,
+                _ => panic!("Expected a Cut, found {location:?}"),
+            })
+            .collect();
+        assert_eq!(cuts, [10, 14]);
+    }
+}
@@ -0,0 +1,77 @@
+//! Catalog of common restriction enzymes.
+//!
+//! Cut positions follow the REBASE notation: they are counted from the first element of the
+//! recognition site, on the top strand, so that `GGTCTC(1/5)` (BsaI) cuts the top strand 7 and the
+//! bottom strand 11 elements after the start of its site.
+
+/// Restriction enzyme, which cuts both strands at fixed positions relative to its recognition site
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Enzyme {
+    pub name: &'static str,
+
+    /// Recognition site, 5' to 3' on the top strand, as IUPAC nucleotide codes
+    pub site: &'static str,
+
+    /// Number of elements, from the start of the site, after which the top strand is cut
+    pub top_cut: i64,
+
+    /// Number of elements of the top strand, from the start of the site, after which the bottom
+    /// strand is cut
+    pub bottom_cut: i64,
+}
+
+impl Enzyme {
+    /// Length of the single-stranded overhang left by a cut: positive for a 5' overhang, negative
+    /// for a 3' overhang and zero for blunt ends.
+    pub fn overhang(&self) -> i64 {
+        self.bottom_cut - self.top_cut
+    }
+
+    /// Whether the enzyme cuts outside of its recognition site, as type IIS enzymes do
+    pub fn cuts_outside(&self) -> bool {
+        let length = self.site.len() as i64;
+        [self.top_cut, self.bottom_cut]
+            .iter()
+            .any(|cut| !(0..=length).contains(cut))
+    }
+}
+
+const fn enzyme(name: &'static str, site: &'static str, top_cut: i64, bottom_cut: i64) -> Enzyme {
+    Enzyme {
+        name,
+        site,
+        top_cut,
+        bottom_cut,
+    }
+}
+
+/// Common restriction enzymes, including the type IIS enzymes of Golden Gate and MoClo assembly
+pub const ENZYMES: [Enzyme; 20] = [
+    enzyme("EcoRI", "GAATTC", 1, 5),
+    enzyme("BamHI", "GGATCC", 1, 5),
+    enzyme("HindIII", "AAGCTT", 1, 5),
+    enzyme("XbaI", "TCTAGA", 1, 5),
+    enzyme("SpeI", "ACTAGT", 1, 5),
+    enzyme("PstI", "CTGCAG", 5, 1),
+    enzyme("NotI", "GCGGCCGC", 2, 6),
+    enzyme("XhoI", "CTCGAG", 1, 5),
+    enzyme("NcoI", "CCATGG", 1, 5),
+    enzyme("NdeI", "CATATG", 2, 4),
+    enzyme("KpnI", "GGTACC", 5, 1),
+    enzyme("SacI", "GAGCTC", 5, 1),
+    enzyme("SalI", "GTCGAC", 1, 5),
+    enzyme("BglII", "AGATCT", 1, 5),
+    enzyme("EcoRV", "GATATC", 3, 3),
+    enzyme("SmaI", "CCCGGG", 3, 3),
+    enzyme("BsaI", "GGTCTC", 7, 11),
+    enzyme("BsmBI", "CGTCTC", 7, 11),
+    enzyme("BbsI", "GAAGAC", 8, 12),
+    enzyme("SapI", "GCTCTTC", 8, 11),
+];
+
+/// Enzyme of [`ENZYMES`] with the given name, regardless of case
+pub fn find(name: &str) -> Option<&'static Enzyme> {
+    ENZYMES
+        .iter()
+        .find(|enzyme| enzyme.name.eq_ignore_ascii_case(name))
+}
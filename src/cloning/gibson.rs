@@ -0,0 +1,338 @@
+//! Gibson assembly, and other assemblies of fragments through homologous overlaps.
+//!
+//! Consecutive fragments are joined where the end of one is identical to the beginning of the
+//! next. Junctions which lack such an overlap are given one by PCR: the fragments on both sides
+//! are amplified with primers whose tails copy the end of their neighbour.
+
+use url::Url;
+
+use crate::sequence::nucleic_acid;
+use crate::{Document, SbolError};
+
+use super::primers::{self, PrimerOptions};
+use super::{add_sub_component, new_activity, new_dna};
+
+/// Parameters of a Gibson assembly
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GibsonOptions {
+    /// Minimum length of the overlaps found between fragments, and length of the overlaps added
+    /// by primers
+    pub overlap: usize,
+
+    /// Join the last fragment to the first, as when assembling a plasmid
+    pub circular: bool,
+
+    /// Parameters of the primers which add missing overlaps
+    pub primers: PrimerOptions,
+}
+
+impl Default for GibsonOptions {
+    fn default() -> Self {
+        Self {
+            overlap: 20,
+            circular: true,
+            primers: PrimerOptions::default(),
+        }
+    }
+}
+
+/// Outcome of a Gibson assembly
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GibsonAssembly {
+    /// URI of the assembled `Component`
+    pub product: Url,
+
+    /// URIs of the primers which amplify fragments with missing overlaps, forward then reverse
+    /// for each amplified fragment
+    pub primers: Vec<Url>,
+}
+
+/// Fragment to assemble
+struct Fragment {
+    uri: Url,
+    display_id: String,
+    sequence: Url,
+    elements: String,
+}
+
+/// Length of the longest overlap of at least `min_length` elements between the end of `left`
+/// and the beginning of `right`, regardless of case, or 0 when there is none.
+pub fn overlap(left: &str, right: &str, min_length: usize) -> usize {
+    let max_length = left.len().min(right.len());
+    (min_length.max(1)..=max_length)
+        .rev()
+        .find(|length| left[left.len() - length..].eq_ignore_ascii_case(&right[..*length]))
+        .unwrap_or(0)
+}
+
+/// Simulate the assembly of `fragments`, in order, and add the product to `document` as a
+/// `Component` named after `display_id` in the namespace of the first fragment.
+///
+/// Junctions which already overlap by at least `options.overlap` elements are joined as is.
+/// Otherwise, primers are designed for both neighbouring fragments, and added to the document as
+/// well: the reverse primer of the left fragment and the forward primer of the right fragment
+/// share the overlap between their tails. The product has a `SubComponent` for each fragment,
+/// whose `source_location` excludes the overlap with the next fragment. An overlap which spans a
+/// whole fragment is an error, as it would leave nothing of the fragment in the product.
+pub fn assemble(
+    document: &mut Document,
+    fragments: &[Url],
+    display_id: &str,
+    options: &GibsonOptions,
+) -> Result<GibsonAssembly, SbolError> {
+    let fragments = fragments
+        .iter()
+        .map(|uri| fragment(document, uri))
+        .collect::<Result<Vec<_>, _>>()?;
+    let Some(first) = fragments.first() else {
+        return Err(SbolError::Validation("No fragment to assemble".to_string()));
+    };
+    let count = fragments.len();
+    let junctions = if options.circular { count } else { count - 1 };
+
+    // Overlap of each fragment with the next one, and the tails of the primers of each fragment
+    let mut overlaps = vec![0; count];
+    let mut tails = vec![(String::new(), String::new()); count];
+    for left in 0..junctions {
+        let right = (left + 1) % count;
+        let (left_elements, right_elements) =
+            (&fragments[left].elements, &fragments[right].elements);
+        overlaps[left] = overlap(left_elements, right_elements, options.overlap);
+        if overlaps[left] >= left_elements.len().min(right_elements.len()) {
+            return Err(SbolError::Validation(format!(
+                "{} and {} overlap over the whole of a fragment",
+                fragments[left].uri, fragments[right].uri
+            )));
+        }
+        if overlaps[left] > 0 {
+            continue;
+        }
+        let head = options.overlap / 2;
+        let tail = options.overlap - head;
+        if left_elements.len() < tail || right_elements.len() < head {
+            return Err(SbolError::Validation(format!(
+                "{} and {} are too short for an overlap of {} elements",
+                fragments[left].uri, fragments[right].uri, options.overlap
+            )));
+        }
+        tails[right].0 = left_elements[left_elements.len() - tail..].to_string();
+        tails[left].1 = nucleic_acid::reverse_complement(&right_elements[..head])?;
+    }
+
+    let mut primers = vec![];
+    for (fragment, (forward_tail, reverse_tail)) in fragments.iter().zip(&tails) {
+        if forward_tail.is_empty() && reverse_tail.is_empty() {
+            continue;
+        }
+        let (forward, reverse) = primers::design(
+            &fragment.elements,
+            forward_tail,
+            reverse_tail,
+            &options.primers,
+        )?;
+        for (suffix, primer) in [("forward", &forward), ("reverse", &reverse)] {
+            let display_id = format!("{}_{suffix}", fragment.display_id);
+            primers.push(primers::add_primer(
+                document,
+                &fragment.uri,
+                &display_id,
+                primer,
+            )?);
+        }
+    }
+
+    let namespace = document
+        .component(&first.uri)
+        .map(|component| component.namespace.clone())
+        .ok_or_else(|| SbolError::UnresolvedReference(first.uri.clone()))?;
+    let lengths: Vec<usize> = fragments
+        .iter()
+        .zip(&overlaps)
+        .map(|(fragment, overlap)| fragment.elements.len() - overlap)
+        .collect();
+    let elements: String = fragments
+        .iter()
+        .zip(&lengths)
+        .map(|(fragment, length)| &fragment.elements[..*length])
+        .collect();
+    let (mut product, sequence) =
+        new_dna(document, &namespace, display_id, elements, options.circular)?;
+    let used: Vec<Url> = fragments
+        .iter()
+        .map(|fragment| fragment.uri.clone())
+        .chain(primers.iter().cloned())
+        .collect();
+    let activity = new_activity(
+        document,
+        &namespace,
+        &format!(
+            "{}_assembly",
+            product.display_id.as_deref().unwrap_or(display_id)
+        ),
+        "Gibson assembly".to_string(),
+        &used,
+    )?;
+    product.derived_from = fragments
+        .iter()
+        .map(|fragment| fragment.uri.clone())
+        .collect();
+    product.generated_by = vec![activity.identity.clone()];
+
+    let mut position = 0;
+    for (fragment, length) in fragments.iter().zip(lengths) {
+        add_sub_component(
+            &mut product,
+            &fragment.display_id,
+            &fragment.uri,
+            (&sequence.identity, position + 1, position + length),
+            (&fragment.sequence, &[(1, length)]),
+        )?;
+        position += length;
+    }
+
+    let uri = product.identity.clone();
+    document.activities.push(activity);
+    document.sequences.push(sequence);
+    document.components.push(product);
+    Ok(GibsonAssembly {
+        product: uri,
+        primers,
+    })
+}
+
+/// Elements and identifiers of the fragment `uri`
+fn fragment(document: &Document, uri: &Url) -> Result<Fragment, SbolError> {
+    let component = document
+        .component(uri)
+        .ok_or_else(|| SbolError::UnresolvedReference(uri.clone()))?;
+    let sequence = document
+        .nucleic_acid_sequence(component)
+        .ok_or_else(|| SbolError::Validation(format!("{uri} has no nucleic acid Sequence")))?;
+    let elements = sequence.nucleic_acid_elements()?;
+    nucleic_acid::validate(elements)?;
+    Ok(Fragment {
+        uri: uri.clone(),
+        display_id: component
+            .display_id
+            .clone()
+            .unwrap_or_else(|| "fragment".to_string()),
+        sequence: sequence.identity.clone(),
+        elements: elements.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ontologies::{so_role, TopologyOntology};
+    use crate::test_utils::{dna, uri};
+    use crate::validation::hierarchy::sequence_mismatches;
+
+    fn linear_dna(document: &mut Document, display_id: &str, elements: &str) -> Url {
+        let namespace = uri("https://example.org");
+        dna(
+            document,
+            &namespace,
+            display_id,
+            elements,
+            &[TopologyOntology::Linear],
+        )
+    }
+
+    #[test]
+    fn test_overlap() {
+        assert_eq!(overlap("aaaGATTACA", "gattacaCCC", 4), 7);
+        assert_eq!(overlap("aaaGATTACA", "gattacaCCC", 8), 0);
+        assert_eq!(overlap("aaaa", "cccc", 1), 0);
+    }
+
+    #[test]
+    fn test_assemble() {
+        let insert = "ATGGCTAGCAAAGGAGAAGAACTTTTCACTGGAGTTGTCCCAATTCTTGTTGAATTAGATGGTGATGTTAATGGG";
+        let vector = "TTACCAATGCTTAATCAGTGAGGCACCTATCTCAGCGATCTGTCTATTTCGTTCATCCATAGTTGCCTGACTCCCC";
+        // The vector ends with the beginning of the insert, but the insert does not end with the
+        // beginning of the vector
+        let backbone = vector.len();
+        let vector = format!("{vector}{}", &insert[..25]);
+        let mut document = Document::new();
+        let vector = linear_dna(&mut document, "vector", &vector);
+        let insert_uri = linear_dna(&mut document, "insert", insert);
+
+        let options = GibsonOptions::default();
+        let assembly = assemble(
+            &mut document,
+            &[vector.clone(), insert_uri.clone()],
+            "plasmid",
+            &options,
+        )
+        .unwrap();
+        assert_eq!(assembly.primers.len(), 4);
+
+        let product = document.component(&assembly.product).unwrap();
+        assert!(product.is_circular());
+        let elements = document
+            .nucleic_acid_sequence(product)
+            .unwrap()
+            .elements
+            .clone()
+            .unwrap();
+        assert_eq!(elements.len(), backbone + insert.len());
+        assert!(elements.ends_with(insert));
+        assert_eq!(product.derived_from, [vector.clone(), insert_uri.clone()]);
+        let activity = document.activity(&product.generated_by[0]).unwrap();
+        assert_eq!(activity.has_usage.len(), 6);
+
+        // The primers of the closing junction carry its overlap
+        let primer = document.component(&assembly.primers[0]).unwrap();
+        assert_eq!(primer.display_id.as_deref(), Some("vector_forward"));
+        assert_eq!(primer.role, [so_role("primer").unwrap()]);
+        let elements = document
+            .nucleic_acid_sequence(primer)
+            .unwrap()
+            .elements
+            .clone()
+            .unwrap();
+        assert!(elements.starts_with(&insert[insert.len() - 10..]));
+        assert!(sequence_mismatches(&document).is_empty());
+    }
+
+    #[test]
+    fn test_whole_fragment_overlap() {
+        let elements = "ATGGCTAGCAAAGGAGAAGAACTTTTCACTGGAGTTGTC";
+        let mut document = Document::new();
+        let fragment = linear_dna(&mut document, "fragment", elements);
+        let copy = linear_dna(&mut document, "copy", elements);
+
+        // A single fragment joined to itself
+        let result = assemble(
+            &mut document,
+            std::slice::from_ref(&fragment),
+            "plasmid",
+            &GibsonOptions::default(),
+        );
+        assert!(matches!(result, Err(SbolError::Validation(msg)) if msg.contains("whole")));
+
+        // Identical fragments
+        let options = GibsonOptions {
+            circular: false,
+            ..Default::default()
+        };
+        let result = assemble(&mut document, &[fragment, copy], "product", &options);
+        assert!(matches!(result, Err(SbolError::Validation(msg)) if msg.contains("whole")));
+        assert_eq!(document.sequences.len(), 2);
+    }
+
+    #[test]
+    fn test_invalid_fragment() {
+        let mut document = Document::new();
+        let left = linear_dna(&mut document, "left", "ATGGCTAGCAAAGGAGAAGAé");
+        let right = linear_dna(&mut document, "right", "ATGGCTAGCAAAGGAGAAGA");
+        let result = assemble(
+            &mut document,
+            &[left, right],
+            "product",
+            &Default::default(),
+        );
+        assert!(matches!(result, Err(SbolError::Validation(_))));
+    }
+}
@@ -0,0 +1,250 @@
+//! Golden Gate assembly with type IIS enzymes, as used by MoClo.
+//!
+//! Type IIS enzymes cut outside of their recognition site, which lets each part carry arbitrary
+//! overhangs. During the reaction, fragments which still contain a site are cut again, so that
+//! only fragments without a site accumulate, ligated through their matching overhangs. [`assemble`]
+//! simulates this outcome: a circular product made of one such fragment of each part.
+
+use url::Url;
+
+use crate::search::{self, SearchOptions};
+use crate::{Document, SbolError};
+
+use super::digest::{self, End, Fragment};
+use super::{add_sub_component, new_activity, new_dna, Enzyme};
+
+/// Fragments of a part which may be ligated
+struct Part {
+    uri: Url,
+    display_id: String,
+    sequence: Url,
+    length: usize,
+    fragments: Vec<Fragment>,
+}
+
+/// Simulate the Golden Gate assembly of `parts` with `enzyme`, and add the circular product to
+/// `document` as a `Component` named after `display_id` in the namespace of the first part.
+/// Returns the URI of the product.
+///
+/// Each part is digested, and its fragments which are cut at both ends and free of sites are
+/// candidates. The product is made of one candidate of each part, ligated in the order given by
+/// their overhangs, starting with the first part. It has a `SubComponent` for each part, located
+/// at the position of its fragment, with a `source_location` on the `Sequence` of the part.
+pub fn assemble(
+    document: &mut Document,
+    parts: &[Url],
+    enzyme: &Enzyme,
+    display_id: &str,
+) -> Result<Url, SbolError> {
+    let candidates = parts
+        .iter()
+        .map(|part| candidates(document, part, enzyme))
+        .collect::<Result<Vec<_>, _>>()?;
+    let Some(first) = candidates.first() else {
+        return Err(SbolError::Validation("No part to assemble".to_string()));
+    };
+    let chain = (0..first.fragments.len())
+        .find_map(|i| {
+            let mut chain = vec![(0, i)];
+            ligate(&candidates, &mut chain).then_some(chain)
+        })
+        .ok_or_else(|| {
+            SbolError::Validation(format!(
+                "The overhangs of the {} fragments of the parts do not form a circle",
+                enzyme.name
+            ))
+        })?;
+
+    let namespace = document
+        .component(&first.uri)
+        .map(|component| component.namespace.clone())
+        .ok_or_else(|| SbolError::UnresolvedReference(first.uri.clone()))?;
+    let elements: String = chain
+        .iter()
+        .map(|(part, i)| candidates[*part].fragments[*i].top_strand())
+        .collect();
+    let (mut product, sequence) = new_dna(document, &namespace, display_id, elements, true)?;
+    let activity = new_activity(
+        document,
+        &namespace,
+        &format!(
+            "{}_assembly",
+            product.display_id.as_deref().unwrap_or(display_id)
+        ),
+        format!("Golden Gate assembly with {}", enzyme.name),
+        parts,
+    )?;
+    product.derived_from = parts.to_vec();
+    product.generated_by = vec![activity.identity.clone()];
+
+    let mut position = 0;
+    for (part, i) in chain {
+        let part = &candidates[part];
+        let fragment = &part.fragments[i];
+        let length = fragment.top_strand().len();
+        // The top strand of the fragment may span the origin of a circular part
+        let start = (fragment.offset + fragment.left.map_or(0, |end| end.top)) % part.length;
+        let bounds = if start + length > part.length {
+            vec![(start + 1, part.length), (1, start + length - part.length)]
+        } else {
+            vec![(start + 1, start + length)]
+        };
+        add_sub_component(
+            &mut product,
+            &part.display_id,
+            &part.uri,
+            (&sequence.identity, position + 1, position + length),
+            (&part.sequence, &bounds),
+        )?;
+        position += length;
+    }
+
+    let uri = product.identity.clone();
+    document.activities.push(activity);
+    document.sequences.push(sequence);
+    document.components.push(product);
+    Ok(uri)
+}
+
+/// Fragments of `part` which are cut at both ends and contain no site of `enzyme`
+fn candidates(document: &Document, part: &Url, enzyme: &Enzyme) -> Result<Part, SbolError> {
+    let component = document
+        .component(part)
+        .ok_or_else(|| SbolError::UnresolvedReference(part.clone()))?;
+    let sequence = document
+        .nucleic_acid_sequence(component)
+        .ok_or_else(|| SbolError::Validation(format!("{part} has no nucleic acid Sequence")))?;
+    let elements = sequence.nucleic_acid_elements()?;
+    let options = SearchOptions {
+        degenerate: true,
+        both_strands: true,
+        circular: false,
+    };
+    let mut fragments = vec![];
+    for fragment in digest::fragments(elements, component.is_circular(), &[enzyme])? {
+        let sticky = [fragment.left, fragment.right]
+            .iter()
+            .all(|end| end.is_some_and(|end| end.overhang() != 0));
+        if sticky && search::find(&fragment.elements, enzyme.site, &options)?.is_empty() {
+            fragments.push(fragment);
+        }
+    }
+    if fragments.is_empty() {
+        return Err(SbolError::Validation(format!(
+            "{part} has no fragment between two {} sites",
+            enzyme.name
+        )));
+    }
+    Ok(Part {
+        uri: part.clone(),
+        display_id: component
+            .display_id
+            .clone()
+            .unwrap_or_else(|| "part".to_string()),
+        sequence: sequence.identity.clone(),
+        length: elements.len(),
+        fragments,
+    })
+}
+
+/// Extend `chain`, made of (part, fragment) indices, with a fragment of every other part, such
+/// that the overhangs of consecutive fragments match and close the circle.
+fn ligate(parts: &[Part], chain: &mut Vec<(usize, usize)>) -> bool {
+    let fragment = |(part, i): (usize, usize)| &parts[part].fragments[i];
+    let last = fragment(chain[chain.len() - 1]);
+    if chain.len() == parts.len() {
+        return matches(last, fragment(chain[0]));
+    }
+    for part in 0..parts.len() {
+        if chain.iter().any(|(used, _)| *used == part) {
+            continue;
+        }
+        for i in 0..parts[part].fragments.len() {
+            if matches(last, fragment((part, i))) {
+                chain.push((part, i));
+                if ligate(parts, chain) {
+                    return true;
+                }
+                chain.pop();
+            }
+        }
+    }
+    false
+}
+
+/// Whether the right end of `left` can be ligated to the left end of `right`
+fn matches(left: &Fragment, right: &Fragment) -> bool {
+    let (Some(left_end), Some(right_end)) = (left.right, right.left) else {
+        return false;
+    };
+    let overhang = |fragment: &Fragment, end: &End| fragment.overhang(end).to_ascii_uppercase();
+    left_end.overhang() == right_end.overhang()
+        && overhang(left, &left_end) == overhang(right, &right_end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cloning::enzymes;
+    use crate::ontologies::TopologyOntology;
+    use crate::test_utils::{dna, uri};
+    use crate::validation::hierarchy::sequence_mismatches;
+
+    /// Circular plasmid with `insert` between BsaI sites which cut out its first and last four
+    /// elements as overhangs
+    fn plasmid(document: &mut Document, display_id: &str, insert: &str, backbone: &str) -> Url {
+        dna(
+            document,
+            &uri("https://example.org"),
+            display_id,
+            &format!("{backbone}GGTCTCa{insert}aGAGACC"),
+            &[TopologyOntology::Circular],
+        )
+    }
+
+    #[test]
+    fn test_assemble() {
+        let mut document = Document::new();
+        let promoter = plasmid(&mut document, "promoter", "GGAGttgacaTACT", "cccc");
+        let cds = plasmid(&mut document, "cds", "TACTatgaaaGCTT", "gggg");
+        // The insert of the backbone spans its origin
+        let backbone = plasmid(&mut document, "backbone", "GCTTcgcgGGAG", "");
+        let bsai = enzymes::find("BsaI").unwrap();
+
+        let parts = [promoter.clone(), backbone.clone(), cds.clone()];
+        let product = assemble(&mut document, &parts, bsai, "construct").unwrap();
+        let component = document.component(&product).unwrap();
+        assert!(component.is_circular());
+        assert_eq!(
+            document
+                .nucleic_acid_sequence(component)
+                .unwrap()
+                .elements
+                .as_deref(),
+            Some("GGAGttgacaTACTatgaaaGCTTcgcg")
+        );
+        let order: Vec<_> = component
+            .sub_components
+            .iter()
+            .map(|sub_component| &sub_component.instance_of)
+            .collect();
+        assert_eq!(order, [&promoter, &cds, &backbone]);
+        assert_eq!(component.derived_from, parts);
+        let activity = document.activity(&component.generated_by[0]).unwrap();
+        assert_eq!(activity.has_usage.len(), 3);
+        assert!(sequence_mismatches(&document).is_empty());
+    }
+
+    #[test]
+    fn test_mismatched_overhangs() {
+        let mut document = Document::new();
+        let promoter = plasmid(&mut document, "promoter", "GGAGttgacaTACT", "cccc");
+        let cds = plasmid(&mut document, "cds", "AATGatgaaaGCTT", "gggg");
+        let no_site = plasmid(&mut document, "no_site", "", "");
+        let bsai = enzymes::find("BsaI").unwrap();
+        assert!(assemble(&mut document, &[promoter.clone(), cds], bsai, "construct").is_err());
+        document.sequences[2].elements = Some("aaaa".to_string());
+        assert!(assemble(&mut document, &[promoter, no_site], bsai, "construct").is_err());
+        assert!(assemble(&mut document, &[], bsai, "construct").is_err());
+    }
+}
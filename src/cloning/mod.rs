@@ -0,0 +1,126 @@
+//! Simulation of cloning: restriction digests, primer design and the assembly of DNA fragments.
+//!
+//! Products are added to the `Document` as new DNA `Component` objects, each with a nucleic acid
+//! `Sequence`. They are `derived_from` the components they were made from, and `generated_by` an
+//! `Activity` whose `has_usage` records these components.
+
+pub mod digest;
+pub mod enzymes;
+pub mod gibson;
+pub mod golden_gate;
+pub mod primers;
+
+use url::Url;
+
+use crate::identified::{child_uri, unused_display_id};
+use crate::ontologies::{ComponentTypeOntology, Encoding, TopologyOntology};
+use crate::uris::child_display_id;
+use crate::{
+    Activity, Component, ComponentType, Document, Location, Range, SbolError, Sequence,
+    SubComponent, Usage,
+};
+
+pub use enzymes::Enzyme;
+pub use primers::{Primer, PrimerOptions};
+
+/// `prefix`, or `prefix` followed by a number, such that `namespace/display_id` identifies no
+/// `TopLevel` of `document`
+fn unused_top_level_display_id(document: &Document, namespace: &Url, prefix: &str) -> String {
+    unused_display_id(prefix, |display_id| {
+        child_uri(namespace, display_id).is_ok_and(|uri| document.contains(&uri))
+    })
+}
+
+/// Double-stranded DNA `Component` named after `prefix` in `namespace`, and its `Sequence` of
+/// `elements`, which are not yet added to `document`
+fn new_dna(
+    document: &Document,
+    namespace: &Url,
+    prefix: &str,
+    elements: String,
+    circular: bool,
+) -> Result<(Component, Sequence), SbolError> {
+    let display_id = unused_top_level_display_id(document, namespace, prefix);
+    let mut component = Component::new(namespace.clone(), &display_id)?;
+    let sequence_display_id =
+        unused_top_level_display_id(document, namespace, &format!("{display_id}_seq"));
+    let mut sequence = Sequence::new(namespace.clone(), &sequence_display_id)?;
+    sequence.elements = Some(elements);
+    sequence.encoding = Some(Encoding::NucleicAcid);
+
+    let topology = if circular {
+        TopologyOntology::Circular
+    } else {
+        TopologyOntology::Linear
+    };
+    component.r#type = vec![
+        ComponentType::Type(ComponentTypeOntology::DNA),
+        ComponentType::Topology(topology),
+        ComponentType::Topology(TopologyOntology::DoubleStranded),
+    ];
+    component.has_sequence = vec![sequence.identity.clone()];
+    Ok((component, sequence))
+}
+
+/// Add to `product` a `SubComponent` called `name`, which is an instance of `part`. It is located
+/// at `location`, the 1-based bounds of a region of the `Sequence` of the product, and its
+/// `source_location` is made of the regions `source` of the `Sequence` of the part.
+fn add_sub_component(
+    product: &mut Component,
+    name: &str,
+    part: &Url,
+    location: (&Url, usize, usize),
+    source: (&Url, &[(usize, usize)]),
+) -> Result<(), SbolError> {
+    let display_id = child_display_id("SubComponent", product.children());
+    let mut sub_component = SubComponent::new(&product.identity, &display_id, part.clone())?;
+    sub_component.name = Some(name.to_string());
+    let (sequence, start, end) = location;
+    let range = Range::new(
+        &sub_component.identity,
+        "Range1",
+        sequence.clone(),
+        start,
+        end,
+    )?;
+    sub_component.has_location.push(Location::Range(range));
+    let (sequence, bounds) = source;
+    for (start, end) in bounds {
+        let display_id = child_display_id(
+            "Range",
+            sub_component
+                .has_location
+                .iter()
+                .chain(&sub_component.source_location),
+        );
+        let range = Range::new(
+            &sub_component.identity,
+            &display_id,
+            sequence.clone(),
+            *start,
+            *end,
+        )?;
+        sub_component.source_location.push(Location::Range(range));
+    }
+    product.sub_components.push(sub_component);
+    Ok(())
+}
+
+/// `Activity` named after `prefix` in `namespace`, which uses each object of `used`
+fn new_activity(
+    document: &Document,
+    namespace: &Url,
+    prefix: &str,
+    name: String,
+    used: &[Url],
+) -> Result<Activity, SbolError> {
+    let display_id = unused_top_level_display_id(document, namespace, prefix);
+    let mut activity = Activity::new(namespace.clone(), &display_id)?;
+    activity.name = Some(name);
+    for entity in used {
+        let display_id = child_display_id("Usage", &activity.has_usage);
+        let usage = Usage::new(&activity.identity, &display_id, entity.clone())?;
+        activity.has_usage.push(usage);
+    }
+    Ok(activity)
+}
@@ -0,0 +1,189 @@
+//! Design of PCR primers, which anneal to the ends of a template and may carry 5' tails.
+//!
+//! Tails are not copied from the template: they add elements to the ends of the amplified
+//! product, ie: the overlaps of a Gibson assembly.
+
+use url::Url;
+
+use crate::ontologies::{so_role, Orientation, TopologyOntology};
+use crate::sequence::nucleic_acid;
+use crate::{ComponentType, Document, Location, Range, SbolError, SubComponent};
+
+use super::new_dna;
+
+/// Parameters of the design of primers
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PrimerOptions {
+    /// Minimum number of elements which anneal to the template
+    pub min_length: usize,
+
+    /// Maximum number of elements which anneal to the template
+    pub max_length: usize,
+
+    /// Melting temperature (°C) of the annealing elements, as estimated by
+    /// [`nucleic_acid::melting_temperature`]
+    pub melting_temperature: f64,
+}
+
+impl Default for PrimerOptions {
+    fn default() -> Self {
+        Self {
+            min_length: 18,
+            max_length: 30,
+            melting_temperature: 60.0,
+        }
+    }
+}
+
+/// Single-stranded DNA which primes the amplification of one end of a template
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Primer {
+    /// 5' elements, which do not anneal to the template
+    pub tail: String,
+
+    /// 3' elements, which anneal to the template
+    pub anneal: String,
+
+    /// Strand of the template read by `anneal`: `Inline` for a forward primer, which anneals to
+    /// the beginning of the template, and `ReverseComplement` for a reverse primer, which anneals
+    /// to its end.
+    pub orientation: Orientation,
+}
+
+impl Primer {
+    /// Elements of the primer, 5' to 3'
+    pub fn elements(&self) -> String {
+        format!("{}{}", self.tail, self.anneal)
+    }
+}
+
+/// Forward and reverse primers which amplify `template`, with `forward_tail` and `reverse_tail`
+/// as their tails, 5' to 3'.
+///
+/// Each primer anneals with the fewest elements reaching the melting temperature of `options`,
+/// or with `max_length` elements when none does.
+pub fn design(
+    template: &str,
+    forward_tail: &str,
+    reverse_tail: &str,
+    options: &PrimerOptions,
+) -> Result<(Primer, Primer), SbolError> {
+    nucleic_acid::validate(template)?;
+    if template.len() < options.min_length {
+        return Err(SbolError::Validation(format!(
+            "A template of {} elements is too short for primers of at least {} elements",
+            template.len(),
+            options.min_length
+        )));
+    }
+    let reverse = nucleic_acid::reverse_complement(template)?;
+    let forward = Primer {
+        tail: forward_tail.to_string(),
+        anneal: template[..anneal_length(template, options)?].to_string(),
+        orientation: Orientation::Inline,
+    };
+    let reverse = Primer {
+        tail: reverse_tail.to_string(),
+        anneal: reverse[..anneal_length(&reverse, options)?].to_string(),
+        orientation: Orientation::ReverseComplement,
+    };
+    Ok((forward, reverse))
+}
+
+/// Number of elements at the beginning of `strand` which anneal to the template
+fn anneal_length(strand: &str, options: &PrimerOptions) -> Result<usize, SbolError> {
+    let max_length = options.max_length.min(strand.len());
+    for length in options.min_length..max_length {
+        if nucleic_acid::melting_temperature(&strand[..length])? >= options.melting_temperature {
+            return Ok(length);
+        }
+    }
+    Ok(max_length)
+}
+
+/// Add `primer` of `template` to `document`, as a single-stranded DNA `Component` named after
+/// `display_id` in the namespace of the template. Returns the URI of the primer.
+///
+/// The primer has a `SubComponent` which is an instance of the template, located at its annealing
+/// elements, and whose `source_location` is the region of the `Sequence` of the template they
+/// anneal to.
+pub fn add_primer(
+    document: &mut Document,
+    template: &Url,
+    display_id: &str,
+    primer: &Primer,
+) -> Result<Url, SbolError> {
+    let component = document
+        .component(template)
+        .ok_or_else(|| SbolError::UnresolvedReference(template.clone()))?;
+    let sequence = document
+        .nucleic_acid_sequence(component)
+        .ok_or_else(|| SbolError::Validation(format!("{template} has no nucleic acid Sequence")))?;
+    let length = sequence.nucleic_acid_elements()?.len();
+    let template_sequence = sequence.identity.clone();
+    let namespace = component.namespace.clone();
+
+    let (mut dna, sequence) = new_dna(document, &namespace, display_id, primer.elements(), false)?;
+    for r#type in dna.r#type.iter_mut() {
+        if *r#type == ComponentType::Topology(TopologyOntology::DoubleStranded) {
+            *r#type = ComponentType::Topology(TopologyOntology::SingleStranded);
+        }
+    }
+    dna.role = so_role("primer").into_iter().collect();
+
+    let mut sub_component = SubComponent::new(&dna.identity, "SubComponent1", template.clone())?;
+    sub_component.name = Some("template".to_string());
+    let (tail, anneal) = (primer.tail.len(), primer.anneal.len());
+    let range = Range::new(
+        &sub_component.identity,
+        "Range1",
+        sequence.identity.clone(),
+        tail + 1,
+        tail + anneal,
+    )?;
+    sub_component.has_location.push(Location::Range(range));
+    let (start, end) = match primer.orientation {
+        Orientation::ReverseComplement => (length - anneal + 1, length),
+        _ => (1, anneal),
+    };
+    let mut source = Range::new(
+        &sub_component.identity,
+        "Range2",
+        template_sequence,
+        start,
+        end,
+    )?;
+    source.orientation = Some(primer.orientation.clone());
+    sub_component.source_location.push(Location::Range(source));
+    dna.sub_components.push(sub_component);
+
+    let uri = dna.identity.clone();
+    document.sequences.push(sequence);
+    document.components.push(dna);
+    Ok(uri)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_design() {
+        let template = "ATGGCTAGCAAAGGAGAAGAACTTTTCACTGGAGTTGTCCCAATTCTTGTTGAATTAGATGGTGATGTTAATGGGCACAAATTTTCTGTCCGTGGAGAGGGTGAAGGTGATGCTACAAACGGAAAACTCACCCTTAAATTTATTTGCACTACTGGAAAACTACCTGTTCCATGGCCAACACTTGTCACTACTTTGACTTAA";
+        let options = PrimerOptions::default();
+        let (forward, reverse) = design(template, "gcgc", "", &options).unwrap();
+        assert!(template.starts_with(&forward.anneal));
+        assert!(forward.elements().starts_with("gcgcATGG"));
+        assert_eq!(
+            nucleic_acid::reverse_complement(&reverse.anneal).unwrap(),
+            template[template.len() - reverse.anneal.len()..]
+        );
+        for primer in [&forward, &reverse] {
+            let length = primer.anneal.len();
+            assert!((options.min_length..=options.max_length).contains(&length));
+            let temperature = nucleic_acid::melting_temperature(&primer.anneal).unwrap();
+            assert!(temperature >= options.melting_temperature || length == options.max_length);
+        }
+        assert!(design("ATGGCT", "", "", &options).is_err());
+    }
+}
@@ -1,13 +1,30 @@
 use url::Url;
 
-use crate::ontologies::{ComponentRole, ComponentTypeOntology, TopologyOntology};
-use crate::Sequence;
+use crate::identified::child_uri;
+use crate::ontologies::{ComponentRole, ComponentTypeOntology, Ontology, TopologyOntology};
+use crate::toplevel::impl_toplevel;
+use crate::{
+    Annotation, ComponentReference, Constraint, Identified, Interaction, Interface, SbolError,
+    SequenceFeature, SubComponent,
+};
 
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ComponentType {
     Type(ComponentTypeOntology),
     Topology(TopologyOntology),
 }
 
+impl From<Url> for ComponentType {
+    /// Known topology terms become `ComponentType::Topology`, anything else is a
+    /// `ComponentType::Type`
+    fn from(uri: Url) -> Self {
+        match TopologyOntology::from_uri(&uri) {
+            Some(topology) => Self::Topology(topology),
+            None => Self::Type(uri.into()),
+        }
+    }
+}
+
 /// Represents the structureal and/or functional entities of a biological design.
 ///
 /// The primary usage is to represent entities with designed sequences, such as DNA, RNA, and
@@ -15,13 +32,25 @@ pub enum ComponentType {
 /// as simple chemicals, molecular complexes, strains, media, light, and abstract functional
 /// groupings of other entities.
 ///
-/// The component class uses the following properties: `type`, `role, `has_sequence`, `has_feature`,
+/// The component class uses the following properties: `type`, `role`, `has_sequence`, `has_feature`,
 /// `has_constraint`, `has_interaction`, `has_interface`, and `has_model`.
 ///
 /// The `has_sequence`, `has_feature`, and `has_constraint` properties are used to represent
 /// structural information, while the `has_interaction`, `has_interface`, and `has_model` are used
 /// to represent functional information.
-pub trait Component {
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Component {
+    pub identity: Url,
+    pub display_id: Option<String>,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub derived_from: Vec<Url>,
+    pub generated_by: Vec<Url>,
+    pub has_measure: Vec<Url>,
+    pub annotations: Vec<Annotation>,
+    pub namespace: Url,
+    pub has_attachment: Vec<Url>,
+
     /// Specifies the category of biochemical or physical entity.
     ///
     /// A `Component` must have one or more `type` properties that describe the entity for the
@@ -69,7 +98,7 @@ pub trait Component {
     /// features may be mapped or identified across this junction. _Double stranded_ instructs
     /// software to apply sequence searches to both strands (ie; sequence and reverse complement of
     /// sequence).
-    fn r#type(&self) -> Vec<ComponentType>;
+    pub r#type: Vec<ComponentType>,
 
     /// Identify terms that are consistent with the `type` property.
     ///
@@ -103,9 +132,9 @@ pub trait Component {
     ///
     /// Any component that can be well described by one of the above must use the URI for that term
     /// as a `role`.
-    fn role(&self) -> Vec<ComponentRole>;
+    pub role: Vec<ComponentRole>,
 
-    /// Return an arbitrary number of `Sequence` object URIs.
+    /// An arbitrary number of `Sequence` object URIs.
     ///
     /// These objects define the primary structure or structures of the `Component`. If a `Feature`
     /// of a `Component` refers to a `Location`, and this `Location` refers to a `Sequence`, then
@@ -113,9 +142,71 @@ pub trait Component {
     ///
     /// Many `Component` objects will have exactly one `has_sequence` property that refers to a
     /// `Sequence` object. In this case, the `Sequence` must have appropriate IUPAC `encoding`.
-    fn has_sequence(&self) -> Vec<Sequence>;
+    pub has_sequence: Vec<Url>,
+
+    /// `SubComponent` features, which link to the `Component` of each included part.
+    pub sub_components: Vec<SubComponent>,
+
+    /// `SequenceFeature` features, which annotate regions of the `Sequence` of this `Component`.
+    pub sequence_features: Vec<SequenceFeature>,
+
+    /// `ComponentReference` features, which refer to the features of included `Component` objects.
+    pub component_references: Vec<ComponentReference>,
+
+    /// Restrictions on the relative positioning or identity of the features of this `Component`.
+    pub has_constraint: Vec<Constraint>,
+
+    /// Functional relationships between the features of this `Component`.
+    pub has_interaction: Vec<Interaction>,
+
+    /// Features which are exposed to the `Component` objects that include this `Component`.
+    pub has_interface: Option<Interface>,
 
-    /// Return an arbitrary number of `Feature` objects URIs.
+    pub has_model: Vec<Url>,
+}
+
+impl Component {
+    /// Create an empty `Component` whose URI is `namespace/display_id`
+    pub fn new(namespace: Url, display_id: &str) -> Result<Self, SbolError> {
+        Ok(Self {
+            identity: child_uri(&namespace, display_id)?,
+            display_id: Some(display_id.to_string()),
+            name: None,
+            description: None,
+            derived_from: vec![],
+            generated_by: vec![],
+            has_measure: vec![],
+            annotations: vec![],
+            namespace,
+            has_attachment: vec![],
+            r#type: vec![],
+            role: vec![],
+            has_sequence: vec![],
+            sub_components: vec![],
+            sequence_features: vec![],
+            component_references: vec![],
+            has_constraint: vec![],
+            has_interaction: vec![],
+            has_interface: None,
+            has_model: vec![],
+        })
+    }
+
+    /// Whether `type` includes the circular topology, so that the ends of its sequence are
+    /// arbitrary
+    pub fn is_circular(&self) -> bool {
+        self.r#type
+            .contains(&ComponentType::Topology(TopologyOntology::Circular))
+    }
+
+    /// Whether `type` includes the double-stranded topology, so that both strands of its
+    /// sequence should be searched
+    pub fn is_double_stranded(&self) -> bool {
+        self.r#type
+            .contains(&ComponentType::Topology(TopologyOntology::DoubleStranded))
+    }
+
+    /// URIs of all `Feature` objects of this `Component`.
     ///
     /// The set of relations between `Feature` and Component objects must be strictly acyclic.
     ///
@@ -147,13 +238,31 @@ pub trait Component {
     /// `SubComponent` that refers to a "lower level" `Component` that also refers to a `Sequence`
     /// could be "gatta" or perhaps "tgta" if the `SubComponent` is positioned by a `Location` with
     /// an `orientation` of "reverse complement".
-    fn has_feature(&self) -> Vec<Url>;
+    pub fn has_feature(&self) -> Vec<Url> {
+        self.sub_components
+            .iter()
+            .map(|feature| feature.identity())
+            .chain(self.sequence_features.iter().map(|f| f.identity()))
+            .chain(self.component_references.iter().map(|f| f.identity()))
+            .collect()
+    }
 
-    fn has_constraint(&self) -> Vec<Url>;
-
-    fn has_interaction(&self) -> Vec<Url>;
-
-    fn has_interface(&self) -> Vec<Url>;
-
-    fn has_model(&self) -> Vec<Url>;
+    /// Direct child objects of this `Component`: its features, constraints, interactions and
+    /// interface
+    pub fn children(&self) -> Vec<&dyn Identified> {
+        let features = self.sub_components.iter().map(|f| f as &dyn Identified);
+        features
+            .chain(self.sequence_features.iter().map(|f| f as &dyn Identified))
+            .chain(
+                self.component_references
+                    .iter()
+                    .map(|f| f as &dyn Identified),
+            )
+            .chain(self.has_constraint.iter().map(|c| c as &dyn Identified))
+            .chain(self.has_interaction.iter().map(|i| i as &dyn Identified))
+            .chain(self.has_interface.iter().map(|i| i as &dyn Identified))
+            .collect()
+    }
 }
+
+impl_toplevel!(Component);
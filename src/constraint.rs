@@ -0,0 +1,58 @@
+use url::Url;
+
+use crate::identified::{child_uri, impl_identified};
+use crate::ontologies::Restriction;
+use crate::{Annotation, SbolError};
+
+/// Restricts the relative, sequence-based positioning or identity of two `Feature` objects of the
+/// same `Component`.
+///
+/// For example, a `Constraint` can state that a promoter `SubComponent` precedes a CDS
+/// `SubComponent`, or that a `ComponentReference` replaces a `SubComponent`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Constraint {
+    pub identity: Url,
+    pub display_id: Option<String>,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub derived_from: Vec<Url>,
+    pub generated_by: Vec<Url>,
+    pub has_measure: Vec<Url>,
+    pub annotations: Vec<Annotation>,
+
+    /// Relationship which holds between `subject` and `object`
+    pub restriction: Restriction,
+
+    /// `Feature` on the left-hand side of the `restriction`
+    pub subject: Url,
+
+    /// `Feature` on the right-hand side of the `restriction`
+    pub object: Url,
+}
+
+impl Constraint {
+    /// Create a `Constraint` whose URI is `parent/display_id`
+    pub fn new(
+        parent: &Url,
+        display_id: &str,
+        restriction: Restriction,
+        subject: Url,
+        object: Url,
+    ) -> Result<Self, SbolError> {
+        Ok(Self {
+            identity: child_uri(parent, display_id)?,
+            display_id: Some(display_id.to_string()),
+            name: None,
+            description: None,
+            derived_from: vec![],
+            generated_by: vec![],
+            has_measure: vec![],
+            annotations: vec![],
+            restriction,
+            subject,
+            object,
+        })
+    }
+}
+
+impl_identified!(Constraint);
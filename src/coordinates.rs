@@ -0,0 +1,514 @@
+//! Positions and regions of linear and circular sequences.
+//!
+//! The ends of the `Sequence` of a circular `Component` are arbitrary (see
+//! [`Component::type`](crate::Component#structfield.type)), so a region may span the origin: it runs from
+//! `start` to the last element and continues from the first element to `end`. Such a [`Span`] has
+//! `start > end`. As a `Range` must have `end >= start`, a feature which spans the origin is stored
+//! as two `Range` locations, which [`Coordinates::span`] joins back together.
+
+use url::Url;
+
+use crate::identified::child_uri;
+use crate::ontologies::Orientation;
+use crate::sequence::nucleic_acid;
+use crate::uris::child_display_id;
+use crate::{Component, Document, Location, SbolError};
+
+/// Region between two inclusive, 1-based positions.
+///
+/// On a circular sequence, `start > end` denotes a region which spans the origin.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Self {
+        Self { start, end }
+    }
+
+    /// Whether the region spans the origin of a circular sequence
+    pub fn wraps(&self) -> bool {
+        self.start > self.end
+    }
+}
+
+impl From<&crate::Range> for Span {
+    fn from(range: &crate::Range) -> Self {
+        Self::new(range.start, range.end)
+    }
+}
+
+/// Coordinate system of a sequence of `length` elements
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Coordinates {
+    pub length: usize,
+    pub circular: bool,
+}
+
+impl Coordinates {
+    pub fn new(length: usize, circular: bool) -> Self {
+        Self { length, circular }
+    }
+
+    /// Coordinates of the first nucleic acid `Sequence` of `component`
+    pub fn of(document: &Document, component: &Component) -> Result<Self, SbolError> {
+        let sequence = document.nucleic_acid_sequence(component).ok_or_else(|| {
+            SbolError::Validation(format!(
+                "{} has no nucleic acid Sequence",
+                component.identity
+            ))
+        })?;
+        Ok(Self::new(
+            sequence.nucleic_acid_elements()?.len(),
+            component.is_circular(),
+        ))
+    }
+
+    /// Linear regions covered by `span`, in the order that they are read
+    fn intervals(&self, span: Span) -> Result<Vec<(usize, usize)>, SbolError> {
+        let within = |position| (1..=self.length).contains(&position);
+        if !within(span.start) || !within(span.end) {
+            return Err(SbolError::Validation(format!(
+                "{}..{} is outside of a sequence of {} elements",
+                span.start, span.end, self.length
+            )));
+        }
+        match (span.wraps(), self.circular) {
+            (false, _) => Ok(vec![(span.start, span.end)]),
+            (true, true) => Ok(vec![(span.start, self.length), (1, span.end)]),
+            (true, false) => Err(SbolError::Validation(format!(
+                "{}..{} spans the origin of a linear sequence",
+                span.start, span.end
+            ))),
+        }
+    }
+
+    /// Number of elements within `span`
+    pub fn len(&self, span: Span) -> Result<usize, SbolError> {
+        Ok(self
+            .intervals(span)?
+            .iter()
+            .map(|(start, end)| end + 1 - start)
+            .sum())
+    }
+
+    /// Whether `position` is within `span`
+    pub fn contains_position(&self, span: Span, position: usize) -> Result<bool, SbolError> {
+        Ok(self
+            .intervals(span)?
+            .iter()
+            .any(|(start, end)| (*start..=*end).contains(&position)))
+    }
+
+    /// Whether every element of `inner` is within `outer`
+    pub fn contains(&self, outer: Span, inner: Span) -> Result<bool, SbolError> {
+        let outer = self.intervals(outer)?;
+        Ok(self.intervals(inner)?.iter().all(|(start, end)| {
+            outer
+                .iter()
+                .any(|(outer_start, outer_end)| outer_start <= start && end <= outer_end)
+        }))
+    }
+
+    /// Number of elements shared by `a` and `b`
+    pub fn overlap(&self, a: Span, b: Span) -> Result<usize, SbolError> {
+        let b = self.intervals(b)?;
+        Ok(self
+            .intervals(a)?
+            .iter()
+            .flat_map(|a| b.iter().map(move |b| (a.0.max(b.0), a.1.min(b.1))))
+            .map(|(start, end)| (end + 1).saturating_sub(start))
+            .sum())
+    }
+
+    /// Whether `a` and `b` share any element
+    pub fn overlaps(&self, a: Span, b: Span) -> Result<bool, SbolError> {
+        Ok(self.overlap(a, b)? > 0)
+    }
+
+    /// Elements within `span`, read from `start` (across the origin if it wraps) to `end`.
+    ///
+    /// The region is not reverse complemented: use
+    /// [`nucleic_acid::orient`](crate::nucleic_acid::orient) for locations with a reverse
+    /// complement orientation.
+    pub fn extract(&self, elements: &str, span: Span) -> Result<String, SbolError> {
+        if elements.len() != self.length {
+            return Err(SbolError::Validation(format!(
+                "Expected {} elements, found {}",
+                self.length,
+                elements.len()
+            )));
+        }
+        self.intervals(span)?
+            .iter()
+            .map(|(start, end)| {
+                elements.get(start - 1..*end).ok_or_else(|| {
+                    SbolError::Validation(format!("{start}..{end} does not split elements"))
+                })
+            })
+            .collect()
+    }
+
+    /// Region covered by the `Range` objects of `locations`, if they form a single region.
+    ///
+    /// On a circular sequence, a `Range` which ends at the last element and one which starts at
+    /// the first element are joined into a `Span` which wraps. Other kinds of `Location` are
+    /// ignored, and `None` is returned if there is no `Range` or if the ranges leave a gap.
+    pub fn span(&self, locations: &[Location]) -> Result<Option<Span>, SbolError> {
+        let mut intervals = vec![];
+        for location in locations {
+            if let Location::Range(range) = location {
+                intervals.extend(self.intervals(Span::from(range))?);
+            }
+        }
+        intervals.sort();
+        let mut merged: Vec<(usize, usize)> = vec![];
+        for (start, end) in intervals {
+            match merged.last_mut() {
+                Some(last) if start <= last.1 + 1 => last.1 = last.1.max(end),
+                _ => merged.push((start, end)),
+            }
+        }
+        Ok(match merged[..] {
+            [(start, end)] => Some(Span::new(start, end)),
+            [(1, end), (start, last)] if self.circular && last == self.length => {
+                Some(Span::new(start, end))
+            }
+            _ => None,
+        })
+    }
+
+    /// Position of the element at `position` once the element at `origin` becomes the first
+    fn rotate_position(&self, position: usize, origin: usize) -> usize {
+        (position + self.length - origin) % self.length + 1
+    }
+
+    /// `span` once the element at `origin` becomes the first element of a circular sequence
+    pub fn rotate(&self, span: Span, origin: usize) -> Result<Span, SbolError> {
+        self.check_origin(origin)?;
+        self.intervals(span)?;
+        Ok(Span::new(
+            self.rotate_position(span.start, origin),
+            self.rotate_position(span.end, origin),
+        ))
+    }
+
+    fn check_origin(&self, origin: usize) -> Result<(), SbolError> {
+        if !self.circular {
+            return Err(SbolError::Validation(
+                "Only the origin of a circular sequence can be moved".to_string(),
+            ));
+        }
+        if !(1..=self.length).contains(&origin) {
+            return Err(SbolError::Validation(format!(
+                "Origin {origin} is outside of a sequence of {} elements",
+                self.length
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Make the element at `origin` the first element of the nucleic acid `Sequence` of the circular
+/// `component`.
+///
+/// Every `Range` and `Cut` of the document which refers to the rotated `Sequence`, including the
+/// `source_location` of `SubComponent` objects of other `Component` objects, is shifted to keep
+/// referring to the same elements. A `Range` which spans the new origin is split in two, the new
+/// `Range` being placed after it in reading order and left without an `order`. Conversely, two
+/// `Range` locations of the same `Feature` which were joined across the old origin are merged.
+pub fn rotate_origin(
+    document: &mut Document,
+    component: &Url,
+    origin: usize,
+) -> Result<(), SbolError> {
+    let target = document
+        .component(component)
+        .ok_or_else(|| SbolError::UnresolvedReference(component.clone()))?;
+    let sequence = document
+        .nucleic_acid_sequence(target)
+        .ok_or_else(|| SbolError::UnresolvedReference(component.clone()))?;
+    nucleic_acid::validate(sequence.nucleic_acid_elements()?)?;
+    let sequence = sequence.identity.clone();
+    let coordinates = Coordinates::of(document, target)?;
+    coordinates.check_origin(origin)?;
+    if origin == 1 {
+        return Ok(());
+    }
+
+    if let Some(elements) = document
+        .sequence_mut(&sequence)
+        .and_then(|sequence| sequence.elements.as_mut())
+    {
+        *elements = format!("{}{}", &elements[origin - 1..], &elements[..origin - 1]);
+    }
+    let rotation = Rotation {
+        coordinates,
+        sequence,
+        origin,
+    };
+    for component in &mut document.components {
+        for sub_component in &mut component.sub_components {
+            let parent = &sub_component.identity;
+            rotation.apply(parent, &mut sub_component.has_location)?;
+            rotation.apply(parent, &mut sub_component.source_location)?;
+        }
+        for sequence_feature in &mut component.sequence_features {
+            rotation.apply(
+                &sequence_feature.identity,
+                &mut sequence_feature.has_location,
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Shift of the locations on `sequence` by [`rotate_origin`]
+struct Rotation {
+    coordinates: Coordinates,
+    sequence: Url,
+    origin: usize,
+}
+
+impl Rotation {
+    /// Rotate the `locations` of the feature identified by `parent`
+    fn apply(&self, parent: &Url, locations: &mut Vec<Location>) -> Result<(), SbolError> {
+        let length = self.coordinates.length;
+        // Position which follows the last element of the sequence before rotation
+        let old_origin = self.coordinates.rotate_position(1, self.origin);
+        let siblings = locations.clone();
+        let mut rotated: Vec<Location> = vec![];
+        for location in std::mem::take(locations) {
+            let mut range = match location {
+                Location::Range(range) if range.has_sequence == self.sequence => range,
+                Location::Cut(mut cut) if cut.has_sequence == self.sequence => {
+                    cut.at = (cut.at + length + 1 - self.origin) % length;
+                    rotated.push(Location::Cut(cut));
+                    continue;
+                }
+                location => {
+                    rotated.push(location);
+                    continue;
+                }
+            };
+            let span = self.coordinates.rotate(Span::from(&range), self.origin)?;
+
+            let joined = rotated.iter_mut().find_map(|location| match location {
+                Location::Range(other)
+                    if other.has_sequence == range.has_sequence
+                        && other.orientation == range.orientation
+                        && ((other.end + 1, span.start) == (old_origin, old_origin)
+                            || (span.end + 1, other.start) == (old_origin, old_origin)) =>
+                {
+                    Some(other)
+                }
+                _ => None,
+            });
+            if let (Some(other), false) = (joined, span.wraps()) {
+                other.start = other.start.min(span.start);
+                other.end = other.end.max(span.end);
+                continue;
+            }
+
+            if !span.wraps() {
+                range.start = span.start;
+                range.end = span.end;
+                rotated.push(Location::Range(range));
+                continue;
+            }
+            let display_id = child_display_id("Range", siblings.iter().chain(&rotated));
+            let mut tail = range.clone();
+            tail.identity = child_uri(parent, &display_id)?;
+            tail.display_id = Some(display_id.clone());
+            tail.order = None;
+            tail.start = 1;
+            tail.end = span.end;
+            range.start = span.start;
+            range.end = length;
+            match range.orientation {
+                Some(Orientation::ReverseComplement | Orientation::ReverseComplementAlt) => {
+                    rotated.extend([Location::Range(tail), Location::Range(range)])
+                }
+                _ => rotated.extend([Location::Range(range), Location::Range(tail)]),
+            }
+        }
+        *locations = rotated;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ontologies::{ComponentTypeOntology, TopologyOntology};
+    use crate::test_utils::{dna_sequence, uri};
+    use crate::validation::hierarchy::sequence_mismatches;
+    use crate::{ComponentType, Range, SequenceFeature, SubComponent};
+
+    #[test]
+    fn test_span_arithmetic() {
+        let circular = Coordinates::new(10, true);
+        let wrapping = Span::new(8, 3);
+        assert_eq!(circular.len(wrapping).unwrap(), 6);
+        assert!(circular.contains_position(wrapping, 10).unwrap());
+        assert!(circular.contains_position(wrapping, 1).unwrap());
+        assert!(!circular.contains_position(wrapping, 5).unwrap());
+        assert!(circular.contains(wrapping, Span::new(9, 2)).unwrap());
+        assert!(circular.contains(wrapping, Span::new(1, 3)).unwrap());
+        assert!(!circular.contains(wrapping, Span::new(3, 4)).unwrap());
+        assert_eq!(circular.overlap(wrapping, Span::new(2, 9)).unwrap(), 4);
+        assert_eq!(circular.overlap(wrapping, Span::new(10, 9)).unwrap(), 6);
+        assert!(!circular.overlaps(wrapping, Span::new(4, 7)).unwrap());
+        assert_eq!(circular.extract("abcdefghij", wrapping).unwrap(), "hijabc");
+
+        let linear = Coordinates::new(10, false);
+        assert!(linear.len(wrapping).is_err());
+        assert!(linear.len(Span::new(3, 11)).is_err());
+        assert_eq!(linear.len(Span::new(3, 8)).unwrap(), 6);
+        assert!(linear.rotate(Span::new(3, 8), 2).is_err());
+        assert_eq!(
+            circular.rotate(Span::new(3, 8), 5).unwrap(),
+            Span::new(9, 4)
+        );
+    }
+
+    #[test]
+    fn test_span_of_locations() {
+        let sequence = uri("https://example.org/seq");
+        let parent = uri("https://example.org/plasmid/feature");
+        let range = |display_id, start, end| {
+            Location::Range(Range::new(&parent, display_id, sequence.clone(), start, end).unwrap())
+        };
+        let circular = Coordinates::new(10, true);
+        let locations = [range("range", 8, 10), range("range2", 1, 3)];
+        assert_eq!(circular.span(&locations).unwrap(), Some(Span::new(8, 3)));
+        assert_eq!(Coordinates::new(10, false).span(&locations).unwrap(), None);
+        let locations = [range("range", 2, 4), range("range2", 5, 6)];
+        assert_eq!(circular.span(&locations).unwrap(), Some(Span::new(2, 6)));
+        assert_eq!(circular.span(&[]).unwrap(), None);
+    }
+
+    /// Circular `gattacagc` with a sequence feature at `start..end`, joined across the origin when
+    /// `start > end`, and a sub-component which is an instance of `acag` at 5..8
+    fn circular_plasmid(start: usize, end: usize, orientation: Orientation) -> Document {
+        let namespace = uri("https://example.org");
+        let mut document = Document::new();
+        for (display_id, elements) in [("plasmid_seq", "gattacagc"), ("part_seq", "acag")] {
+            document
+                .sequences
+                .push(dna_sequence(&namespace, display_id, elements));
+        }
+        let plasmid_seq = uri("https://example.org/plasmid_seq");
+
+        let mut part = Component::new(namespace.clone(), "part").unwrap();
+        part.has_sequence.push(uri("https://example.org/part_seq"));
+        let mut plasmid = Component::new(namespace, "plasmid").unwrap();
+        plasmid.r#type = vec![
+            ComponentType::Type(ComponentTypeOntology::DNA),
+            ComponentType::Topology(TopologyOntology::Circular),
+        ];
+        plasmid.has_sequence.push(plasmid_seq.clone());
+
+        let mut feature = SequenceFeature::new(&plasmid.identity, "feature").unwrap();
+        let mut ranges = if start > end {
+            vec![(start, 9), (1, end)]
+        } else {
+            vec![(start, end)]
+        };
+        if orientation == Orientation::ReverseComplement {
+            ranges.reverse();
+        }
+        for (i, (start, end)) in ranges.into_iter().enumerate() {
+            let display_id = format!("range{}", i + 1);
+            let mut range = Range::new(
+                &feature.identity,
+                &display_id,
+                plasmid_seq.clone(),
+                start,
+                end,
+            )
+            .unwrap();
+            range.orientation = Some(orientation.clone());
+            feature.has_location.push(Location::Range(range));
+        }
+        let mut sub_component =
+            SubComponent::new(&plasmid.identity, "part", part.identity.clone()).unwrap();
+        let range = Range::new(&sub_component.identity, "range", plasmid_seq, 5, 8).unwrap();
+        sub_component.has_location.push(Location::Range(range));
+        plasmid.sequence_features.push(feature);
+        plasmid.sub_components.push(sub_component);
+        document.components.push(part);
+        document.components.push(plasmid);
+        document
+    }
+
+    /// Elements of the feature of [`circular_plasmid`], read in its orientation
+    fn feature_elements(document: &Document) -> String {
+        let plasmid = document
+            .component(&uri("https://example.org/plasmid"))
+            .unwrap();
+        let elements = document
+            .nucleic_acid_sequence(plasmid)
+            .unwrap()
+            .nucleic_acid_elements()
+            .unwrap();
+        plasmid.sequence_features[0]
+            .has_location
+            .iter()
+            .map(|location| match location {
+                Location::Range(range) => crate::nucleic_acid::orient(
+                    &elements[range.start - 1..range.end],
+                    range.orientation.as_ref().unwrap(),
+                )
+                .unwrap(),
+                _ => panic!("Expected a Range, found {location:?}"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_rotate_origin() {
+        let plasmid_uri = uri("https://example.org/plasmid");
+        for orientation in [Orientation::Inline, Orientation::ReverseComplement] {
+            // Feature split by the new origin
+            let mut document = circular_plasmid(2, 4, orientation.clone());
+            let expected = feature_elements(&document);
+            rotate_origin(&mut document, &plasmid_uri, 3).unwrap();
+            let plasmid = document.component(&plasmid_uri).unwrap();
+            assert_eq!(
+                document
+                    .sequence(&plasmid.has_sequence[0])
+                    .unwrap()
+                    .elements,
+                Some("ttacagcga".to_string())
+            );
+            assert_eq!(plasmid.sequence_features[0].has_location.len(), 2);
+            assert_eq!(feature_elements(&document), expected);
+            assert!(sequence_mismatches(&document).is_empty());
+
+            // Feature joined across the old origin
+            let mut document = circular_plasmid(8, 2, orientation.clone());
+            let expected = feature_elements(&document);
+            rotate_origin(&mut document, &plasmid_uri, 4).unwrap();
+            let plasmid = document.component(&plasmid_uri).unwrap();
+            let [Location::Range(range)] = &plasmid.sequence_features[0].has_location[..] else {
+                panic!("Expected a single range");
+            };
+            assert_eq!((range.start, range.end), (5, 8));
+            assert_eq!(feature_elements(&document), expected);
+            assert!(sequence_mismatches(&document).is_empty());
+        }
+
+        let mut document = circular_plasmid(2, 4, Orientation::Inline);
+        assert!(rotate_origin(&mut document, &plasmid_uri, 10).is_err());
+        let part = uri("https://example.org/part");
+        assert!(rotate_origin(&mut document, &part, 2).is_err());
+
+        // Elements which are not nucleotides are rejected rather than sliced
+        document.sequences[0].elements = Some("aéTGCATGC".to_string());
+        let result = rotate_origin(&mut document, &plasmid_uri, 3);
+        assert!(matches!(result, Err(SbolError::Validation(_))));
+    }
+}
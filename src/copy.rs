@@ -0,0 +1,430 @@
+//! Copies and renames of `TopLevel` objects, within or across `Document` objects.
+//!
+//! The URIs of child objects are formed from the URI of their parent, so that a `TopLevel` under a
+//! new URI also gets new children. Every reference to a renamed object or to one of its children
+//! (ie: the `subject` of a `Constraint`, or the `has_sequence` of a `Location`) is rewritten.
+
+use url::Url;
+
+use crate::identified::child_uri;
+use crate::{
+    is_valid_display_id, Activity, AnnotationValue, Component, ComponentReference, Constraint,
+    Document, Interaction, Interface, Location, Participation, SbolError, Sequence,
+    SequenceFeature, SubComponent, TopLevel, Usage,
+};
+
+/// Parameters of a copy
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CopyOptions {
+    /// Add the original of each copy to its `derived_from`
+    pub derived_from: bool,
+}
+
+/// Copy the `TopLevel` objects `uris` of `source` into the namespace `namespace` of `target`.
+/// Returns the URIs of the copies.
+///
+/// Copies keep the display id and local path of their original within its namespace. References
+/// between the copied objects are rewritten to the copies, while other references are kept.
+pub fn copy_to_namespace(
+    source: &Document,
+    target: &mut Document,
+    uris: &[Url],
+    namespace: &Url,
+    options: &CopyOptions,
+) -> Result<Vec<Url>, SbolError> {
+    let mut renames = vec![];
+    for uri in uris {
+        let original = top_level(source, uri)?;
+        let prefix = original
+            .has_namespace()
+            .as_str()
+            .trim_end_matches('/')
+            .to_string();
+        let path = uri.as_str().strip_prefix(&prefix).unwrap_or_default();
+        let copy = match (path.starts_with('/'), original.display_id()) {
+            (true, _) => Url::parse(&format!(
+                "{}{path}",
+                namespace.as_str().trim_end_matches('/')
+            ))?,
+            (false, Some(display_id)) => child_uri(namespace, &display_id)?,
+            (false, None) => {
+                return Err(SbolError::Validation(format!(
+                    "{uri} is outside of its namespace and has no display id"
+                )))
+            }
+        };
+        renames.push(Rename {
+            original: uri.clone(),
+            copy,
+            namespace: namespace.clone(),
+            display_id: original.display_id(),
+        });
+    }
+    copy(source, target, &renames, options)
+}
+
+/// Copy the `TopLevel` `uri` of `source` into `target`, under the display id `display_id` within
+/// the same namespace and local path. Returns the URI of the copy.
+pub fn copy_as(
+    source: &Document,
+    target: &mut Document,
+    uri: &Url,
+    display_id: &str,
+    options: &CopyOptions,
+) -> Result<Url, SbolError> {
+    let rename = renamed(source, uri, display_id)?;
+    let copies = copy(source, target, &[rename], options)?;
+    Ok(copies[0].clone())
+}
+
+/// Give the `TopLevel` `uri` of `document` the display id `display_id`, within the same namespace
+/// and local path, and rewrite every reference to it or its children throughout `document`.
+/// Returns the new URI.
+pub fn rename(document: &mut Document, uri: &Url, display_id: &str) -> Result<Url, SbolError> {
+    let rename = renamed(document, uri, display_id)?;
+    if document.contains(&rename.copy) {
+        return Err(SbolError::Validation(format!(
+            "{} is already in the document",
+            rename.copy
+        )));
+    }
+    let renames = [rename];
+    let map = |uri: &Url| map_uri(uri, &renames);
+    for component in &mut document.components {
+        component.rewrite(&map);
+    }
+    for sequence in &mut document.sequences {
+        sequence.rewrite(&map);
+    }
+    for activity in &mut document.activities {
+        activity.rewrite(&map);
+    }
+    let [rename] = renames;
+    set_display_id(document, &rename.copy, display_id);
+    Ok(rename.copy)
+}
+
+/// New identity of a `TopLevel`
+struct Rename {
+    original: Url,
+    copy: Url,
+    namespace: Url,
+    display_id: Option<String>,
+}
+
+fn top_level<'a>(document: &'a Document, uri: &Url) -> Result<&'a dyn TopLevel, SbolError> {
+    document
+        .top_levels()
+        .find(|top_level| &top_level.identity() == uri)
+        .ok_or_else(|| SbolError::UnresolvedReference(uri.clone()))
+}
+
+/// `Rename` of `uri` to the display id `display_id`, whose last segment is replaced
+fn renamed(document: &Document, uri: &Url, display_id: &str) -> Result<Rename, SbolError> {
+    if !is_valid_display_id(display_id) {
+        return Err(SbolError::Validation(format!(
+            "Invalid display id: {display_id:?}"
+        )));
+    }
+    let original = top_level(document, uri)?;
+    let path = uri.as_str().trim_end_matches('/');
+    let parent = &path[..path.rfind('/').unwrap_or(path.len())];
+    Ok(Rename {
+        original: uri.clone(),
+        copy: child_uri(&Url::parse(parent)?, display_id)?,
+        namespace: original.has_namespace(),
+        display_id: Some(display_id.to_string()),
+    })
+}
+
+/// `uri`, or its new URI when it identifies one of the renamed objects or one of their children
+fn map_uri(uri: &Url, renames: &[Rename]) -> Url {
+    for rename in renames {
+        if uri == &rename.original {
+            return rename.copy.clone();
+        }
+        let child = uri.as_str().strip_prefix(rename.original.as_str());
+        if let Some(path) = child.filter(|path| path.starts_with('/')) {
+            if let Ok(uri) = Url::parse(&format!("{}{path}", rename.copy)) {
+                return uri;
+            }
+        }
+    }
+    uri.clone()
+}
+
+fn copy(
+    source: &Document,
+    target: &mut Document,
+    renames: &[Rename],
+    options: &CopyOptions,
+) -> Result<Vec<Url>, SbolError> {
+    for rename in renames {
+        if target.contains(&rename.copy) {
+            return Err(SbolError::Validation(format!(
+                "{} is already in the target document",
+                rename.copy
+            )));
+        }
+    }
+    let originals = |uri: &Url| renames.iter().any(|rename| &rename.original == uri);
+    let mut copies = Document {
+        components: source
+            .components
+            .iter()
+            .filter(|component| originals(&component.identity))
+            .cloned()
+            .collect(),
+        sequences: source
+            .sequences
+            .iter()
+            .filter(|sequence| originals(&sequence.identity))
+            .cloned()
+            .collect(),
+        activities: source
+            .activities
+            .iter()
+            .filter(|activity| originals(&activity.identity))
+            .cloned()
+            .collect(),
+    };
+    let map = |uri: &Url| map_uri(uri, renames);
+    for component in &mut copies.components {
+        component.rewrite(&map);
+    }
+    for sequence in &mut copies.sequences {
+        sequence.rewrite(&map);
+    }
+    for activity in &mut copies.activities {
+        activity.rewrite(&map);
+    }
+
+    for rename in renames {
+        let display_id = rename.display_id.as_deref();
+        let namespace = &rename.namespace;
+        let original = rename.original.clone();
+        let copy = &rename.copy;
+        let set = |derived_from: &mut Vec<Url>, target_namespace: &mut Url| {
+            *target_namespace = namespace.clone();
+            if options.derived_from {
+                derived_from.push(original.clone());
+            }
+        };
+        if let Some(component) = copies.component_mut(copy) {
+            set(&mut component.derived_from, &mut component.namespace);
+        } else if let Some(sequence) = copies.sequence_mut(copy) {
+            set(&mut sequence.derived_from, &mut sequence.namespace);
+        } else if let Some(activity) = copies.activity_mut(copy) {
+            set(&mut activity.derived_from, &mut activity.namespace);
+        }
+        if let Some(display_id) = display_id {
+            set_display_id(&mut copies, copy, display_id);
+        }
+    }
+    target.extend(copies);
+    Ok(renames.iter().map(|rename| rename.copy.clone()).collect())
+}
+
+fn set_display_id(document: &mut Document, uri: &Url, display_id: &str) {
+    let display_id = Some(display_id.to_string());
+    if let Some(component) = document.component_mut(uri) {
+        component.display_id = display_id;
+    } else if let Some(sequence) = document.sequence_mut(uri) {
+        sequence.display_id = display_id;
+    } else if let Some(activity) = document.activity_mut(uri) {
+        activity.display_id = display_id;
+    }
+}
+
+/// Objects whose URIs, and the URIs of their children, can be rewritten
+trait Rewrite {
+    /// Replace every URI held by this object and its children with its image by `map`
+    fn rewrite(&mut self, map: &impl Fn(&Url) -> Url);
+}
+
+fn rewrite_all(uris: &mut [Url], map: &impl Fn(&Url) -> Url) {
+    for uri in uris {
+        *uri = map(uri);
+    }
+}
+
+/// Rewrite the properties shared by every `Identified` object, and then each of `$field`
+macro_rules! impl_rewrite {
+    ($ty:ty $(, $field:ident)*) => {
+        impl Rewrite for $ty {
+            fn rewrite(&mut self, map: &impl Fn(&Url) -> Url) {
+                self.identity = map(&self.identity);
+                rewrite_all(&mut self.derived_from, map);
+                rewrite_all(&mut self.generated_by, map);
+                rewrite_all(&mut self.has_measure, map);
+                for annotation in &mut self.annotations {
+                    if let AnnotationValue::Uri(uri) = &mut annotation.value {
+                        *uri = map(uri);
+                    }
+                }
+                $(self.$field.rewrite(map);)*
+            }
+        }
+    };
+}
+
+impl Rewrite for Url {
+    fn rewrite(&mut self, map: &impl Fn(&Url) -> Url) {
+        *self = map(self);
+    }
+}
+
+impl<T: Rewrite> Rewrite for Vec<T> {
+    fn rewrite(&mut self, map: &impl Fn(&Url) -> Url) {
+        for item in self {
+            item.rewrite(map);
+        }
+    }
+}
+
+impl<T: Rewrite> Rewrite for Option<T> {
+    fn rewrite(&mut self, map: &impl Fn(&Url) -> Url) {
+        if let Some(item) = self {
+            item.rewrite(map);
+        }
+    }
+}
+
+impl Rewrite for Location {
+    fn rewrite(&mut self, map: &impl Fn(&Url) -> Url) {
+        match self {
+            Location::Range(range) => range.rewrite(map),
+            Location::Cut(cut) => cut.rewrite(map),
+            Location::EntireSequence(entire) => entire.rewrite(map),
+        }
+    }
+}
+
+impl_rewrite!(
+    Component,
+    has_attachment,
+    has_sequence,
+    sub_components,
+    sequence_features,
+    component_references,
+    has_constraint,
+    has_interaction,
+    has_interface,
+    has_model
+);
+impl_rewrite!(SubComponent, instance_of, has_location, source_location);
+impl_rewrite!(SequenceFeature, has_location);
+impl_rewrite!(ComponentReference, in_child_of, refers_to);
+impl_rewrite!(Constraint, subject, object);
+impl_rewrite!(Interaction, has_participation);
+impl_rewrite!(Participation, participant);
+impl_rewrite!(Interface, input, output, nondirectional);
+impl_rewrite!(crate::Range, has_sequence);
+impl_rewrite!(crate::Cut, has_sequence);
+impl_rewrite!(crate::EntireSequence, has_sequence);
+impl_rewrite!(Sequence, has_attachment);
+impl_rewrite!(Activity, has_attachment, has_usage, informed_by);
+impl_rewrite!(Usage, entity);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ontologies::Restriction;
+    use crate::test_utils::uri;
+    use crate::{Identified, Range};
+
+    /// Document with a promoter, which has a sequence, and a device made of the promoter
+    fn library() -> Document {
+        let namespace = uri("https://parts.org");
+        let sequence = Sequence::new(namespace.clone(), "pTet_seq").unwrap();
+        let mut promoter = Component::new(namespace.clone(), "pTet").unwrap();
+        promoter.has_sequence.push(sequence.identity.clone());
+        let mut feature = SequenceFeature::new(&promoter.identity, "tetO").unwrap();
+        let range = Range::new(&feature.identity, "range", sequence.identity.clone(), 1, 4);
+        feature.has_location.push(Location::Range(range.unwrap()));
+        promoter.sequence_features.push(feature);
+
+        // Two copies of the promoter in a row
+        let mut device = Component::new(namespace, "device").unwrap();
+        for display_id in ["first", "second"] {
+            let sub_component =
+                SubComponent::new(&device.identity, display_id, promoter.identity.clone());
+            device.sub_components.push(sub_component.unwrap());
+        }
+        let constraint = Constraint::new(
+            &device.identity,
+            "constraint",
+            Restriction::Precedes,
+            device.sub_components[0].identity.clone(),
+            device.sub_components[1].identity.clone(),
+        );
+        device.has_constraint.push(constraint.unwrap());
+        Document {
+            components: vec![promoter, device],
+            sequences: vec![sequence],
+            activities: vec![],
+        }
+    }
+
+    #[test]
+    fn test_copy_to_namespace() {
+        let source = library();
+        let mut target = Document::new();
+        let namespace = uri("https://lab.org");
+        let uris = [
+            uri("https://parts.org/pTet"),
+            uri("https://parts.org/pTet_seq"),
+        ];
+        let options = CopyOptions { derived_from: true };
+        let copies = copy_to_namespace(&source, &mut target, &uris, &namespace, &options).unwrap();
+        assert_eq!(copies[0].as_str(), "https://lab.org/pTet");
+
+        let promoter = target.component(&copies[0]).unwrap();
+        assert_eq!(promoter.namespace, namespace);
+        assert_eq!(promoter.derived_from, [uris[0].clone()]);
+        assert_eq!(promoter.has_sequence, [copies[1].clone()]);
+        let location = &promoter.sequence_features[0].has_location[0];
+        assert_eq!(
+            location.identity().as_str(),
+            "https://lab.org/pTet/tetO/range"
+        );
+        assert_eq!(location.has_sequence(), &copies[1]);
+        assert_eq!(target.sequences[0].namespace, namespace);
+
+        // The copies are already in the target
+        assert!(copy_to_namespace(&source, &mut target, &uris, &namespace, &options).is_err());
+    }
+
+    #[test]
+    fn test_rename() {
+        let mut document = library();
+        let promoter = uri("https://parts.org/pTet");
+        let uri = rename(&mut document, &promoter, "pTet_v2").unwrap();
+        assert_eq!(uri.as_str(), "https://parts.org/pTet_v2");
+        let component = document.component(&uri).unwrap();
+        assert_eq!(component.display_id.as_deref(), Some("pTet_v2"));
+        assert_eq!(
+            component.sequence_features[0].identity.as_str(),
+            "https://parts.org/pTet_v2/tetO"
+        );
+        let device = &document.components[1];
+        assert_eq!(device.sub_components[1].instance_of, uri);
+        assert_eq!(
+            device.has_constraint[0].subject.as_str(),
+            "https://parts.org/device/first"
+        );
+        assert!(rename(&mut document, &uri, "device").is_err());
+        assert!(rename(&mut document, &uri, "not valid").is_err());
+
+        let copy = copy_as(
+            &library(),
+            &mut document,
+            &promoter,
+            "pTet_copy",
+            &Default::default(),
+        );
+        let copy = document.component(&copy.unwrap()).unwrap();
+        assert_eq!(copy.display_id.as_deref(), Some("pTet_copy"));
+        assert!(copy.derived_from.is_empty());
+    }
+}
@@ -0,0 +1,377 @@
+//! Semantic diff between two `Document` objects, ie: two revisions of a design file.
+//!
+//! Objects are matched by URI and compared property by property, as in [`merge`](crate::merge).
+//! Changes which matter to a reviewer, such as roles, sequence edits and moved features, are
+//! reported as such; every other property change is reported as a [`PropertyConflict`].
+
+use std::fmt;
+
+use url::Url;
+
+use crate::formats::{sbol3, top_level_graph};
+use crate::merge::{property_conflicts, PropertyConflict};
+use crate::ontologies::Orientation;
+use crate::{AnnotationValue, Component, Document, Identified, Location, SbolError};
+
+/// Region of `elements` which differs between two revisions of a `Sequence`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SequenceEdit {
+    /// Length of the left and right `elements`
+    pub lengths: (usize, usize),
+
+    /// 1-based position, in the left `elements`, of the first element which differs
+    pub position: usize,
+
+    /// Elements of the left revision replaced by `inserted`, empty for an insertion
+    pub deleted: String,
+
+    /// Elements of the right revision which replace `deleted`, empty for a deletion
+    pub inserted: String,
+}
+
+impl SequenceEdit {
+    /// Smallest region which differs between `left` and `right`, after their common prefix and
+    /// suffix
+    pub fn new(left: &str, right: &str) -> Self {
+        let left: Vec<char> = left.chars().collect();
+        let right: Vec<char> = right.chars().collect();
+        let prefix = left.iter().zip(&right).take_while(|(l, r)| l == r).count();
+        let suffix = left[prefix..]
+            .iter()
+            .rev()
+            .zip(right[prefix..].iter().rev())
+            .take_while(|(l, r)| l == r)
+            .count();
+        Self {
+            lengths: (left.len(), right.len()),
+            position: prefix + 1,
+            deleted: left[prefix..left.len() - suffix].iter().collect(),
+            inserted: right[prefix..right.len() - suffix].iter().collect(),
+        }
+    }
+}
+
+impl fmt::Display for SequenceEdit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let elements = |elements: &str| match elements.chars().count() {
+            0..=20 => format!("{elements:?}"),
+            length => format!("{length} elements"),
+        };
+        let (left, right) = self.lengths;
+        write!(f, "{left} -> {right} elements, ")?;
+        match (self.deleted.chars().count(), self.inserted.len()) {
+            (0, _) => write!(
+                f,
+                "{} inserted at {}",
+                elements(&self.inserted),
+                self.position
+            ),
+            (deleted, 0) => write!(
+                f,
+                "{} deleted at {}..{}",
+                elements(&self.deleted),
+                self.position,
+                self.position + deleted - 1
+            ),
+            (deleted, _) => write!(
+                f,
+                "{} replaced by {} at {}..{}",
+                elements(&self.deleted),
+                elements(&self.inserted),
+                self.position,
+                self.position + deleted - 1
+            ),
+        }
+    }
+}
+
+/// Difference between two `Document` objects
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    /// `TopLevel` of the right document only
+    Added(Url),
+
+    /// `TopLevel` of the left document only
+    Removed(Url),
+
+    /// Roles of a `Component` or of a feature
+    Roles {
+        object: Url,
+        added: Vec<Url>,
+        removed: Vec<Url>,
+    },
+
+    /// `elements` of a `Sequence`
+    Elements { sequence: Url, edit: SequenceEdit },
+
+    /// Locations of a `SubComponent` or `SequenceFeature`
+    Moved {
+        feature: Url,
+        from: Vec<Location>,
+        to: Vec<Location>,
+    },
+
+    /// Any other property of an object
+    Property(PropertyConflict),
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Added(uri) => write!(f, "+ {uri}"),
+            Self::Removed(uri) => write!(f, "- {uri}"),
+            Self::Roles {
+                object,
+                added,
+                removed,
+            } => {
+                write!(f, "~ {object} role:")?;
+                for role in added {
+                    write!(f, " +<{role}>")?;
+                }
+                for role in removed {
+                    write!(f, " -<{role}>")?;
+                }
+                Ok(())
+            }
+            Self::Elements { sequence, edit } => write!(f, "~ {sequence} elements: {edit}"),
+            Self::Moved { feature, from, to } => write!(
+                f,
+                "~ {feature} moved: {} -> {}",
+                positions(from),
+                positions(to)
+            ),
+            Self::Property(conflict) => write!(f, "~ {conflict}"),
+        }
+    }
+}
+
+/// Changes from a left to a right `Document`, printed as a report with one change per line
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Diff {
+    pub changes: Vec<Change>,
+}
+
+impl Diff {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+impl fmt::Display for Diff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for change in &self.changes {
+            writeln!(f, "{change}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Compare `left` and `right` object by object.
+///
+/// Removed `TopLevel` objects come first, then added ones, then the changes of the `TopLevel`
+/// objects of both documents, in the order of `left`.
+pub fn diff(left: &Document, right: &Document) -> Result<Diff, SbolError> {
+    let mut changes: Vec<Change> = left
+        .top_levels()
+        .map(|top_level| top_level.identity())
+        .filter(|uri| !right.contains(uri))
+        .map(Change::Removed)
+        .collect();
+    changes.extend(
+        right
+            .top_levels()
+            .map(|top_level| top_level.identity())
+            .filter(|uri| !left.contains(uri))
+            .map(Change::Added),
+    );
+
+    let role = sbol3("role");
+    let elements = sbol3("elements");
+    let has_location = sbol3("hasLocation");
+    for top_level in left.top_levels() {
+        let uri = top_level.identity();
+        let (Some(left_graph), Some(right_graph)) =
+            (top_level_graph(left, &uri)?, top_level_graph(right, &uri)?)
+        else {
+            continue;
+        };
+        let moves = match (left.component(&uri), right.component(&uri)) {
+            (Some(left), Some(right)) => moved_features(left, right),
+            _ => vec![],
+        };
+        // Locations of moved features are reported with the move
+        let moved = |object: &Url, property: &str| {
+            moves.iter().any(|(feature, from, to)| {
+                (feature == object && property == has_location)
+                    || from
+                        .iter()
+                        .chain(to)
+                        .any(|location| &location.identity() == object)
+            })
+        };
+        for conflict in property_conflicts(&left_graph, &right_graph) {
+            let property = conflict.property.as_str();
+            if property == role {
+                let uris = |values: &[AnnotationValue]| -> Vec<Url> {
+                    values.iter().filter_map(|v| v.as_uri()).cloned().collect()
+                };
+                let (before, after) = (uris(&conflict.left), uris(&conflict.right));
+                changes.push(Change::Roles {
+                    object: conflict.object,
+                    added: after
+                        .iter()
+                        .filter(|r| !before.contains(r))
+                        .cloned()
+                        .collect(),
+                    removed: before
+                        .iter()
+                        .filter(|r| !after.contains(r))
+                        .cloned()
+                        .collect(),
+                });
+            } else if property == elements {
+                let elements = |document: &Document| {
+                    document
+                        .sequence(&conflict.object)
+                        .and_then(|sequence| sequence.elements.clone())
+                        .unwrap_or_default()
+                };
+                changes.push(Change::Elements {
+                    edit: SequenceEdit::new(&elements(left), &elements(right)),
+                    sequence: conflict.object,
+                });
+            } else if moved(&conflict.object, property) {
+                continue;
+            } else {
+                changes.push(Change::Property(conflict));
+            }
+        }
+        changes.extend(moves.into_iter().map(|(feature, from, to)| Change::Moved {
+            feature,
+            from,
+            to,
+        }));
+    }
+    Ok(Diff { changes })
+}
+
+/// Features of both revisions of a `Component` whose locations differ, with their left and right
+/// locations
+fn moved_features(left: &Component, right: &Component) -> Vec<(Url, Vec<Location>, Vec<Location>)> {
+    let features = |component: &Component| -> Vec<(Url, Vec<Location>)> {
+        let sub_components = component
+            .sub_components
+            .iter()
+            .map(|f| (f.identity.clone(), f.has_location.clone()));
+        let sequence_features = component
+            .sequence_features
+            .iter()
+            .map(|f| (f.identity.clone(), f.has_location.clone()));
+        sub_components.chain(sequence_features).collect()
+    };
+    let right = features(right);
+    features(left)
+        .into_iter()
+        .filter_map(|(feature, from)| {
+            let (_, to) = right.iter().find(|(uri, _)| uri == &feature)?;
+            (positions(&from) != positions(to)).then(|| (feature, from, to.clone()))
+        })
+        .collect()
+}
+
+/// Positions of `locations`, ie: `3..10`, `^4` for a cut or `*` for an entire sequence, followed
+/// by `(-)` on the reverse complement
+fn positions(locations: &[Location]) -> String {
+    let positions: Vec<String> = locations
+        .iter()
+        .map(|location| {
+            let position = match location {
+                Location::Range(range) => format!("{}..{}", range.start, range.end),
+                Location::Cut(cut) => format!("^{}", cut.at),
+                Location::EntireSequence(_) => "*".to_string(),
+            };
+            match location.orientation() {
+                Some(Orientation::ReverseComplement) => format!("{position}(-)"),
+                _ => position,
+            }
+        })
+        .collect();
+    format!("[{}]", positions.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ontologies::ComponentRole;
+    use crate::test_utils::{dna_sequence, uri};
+    use crate::{Range, SequenceFeature};
+
+    fn design() -> Document {
+        let namespace = uri("https://example.org");
+        let sequence = dna_sequence(&namespace, "device_seq", "tccctatcagtgatagagattgaca");
+        let mut device = Component::new(namespace, "device").unwrap();
+        device.role = vec![ComponentRole::Promoter];
+        device.has_sequence.push(sequence.identity.clone());
+        let mut feature = SequenceFeature::new(&device.identity, "operator").unwrap();
+        let range = Range::new(&feature.identity, "range", sequence.identity.clone(), 2, 20);
+        feature.has_location.push(Location::Range(range.unwrap()));
+        device.sequence_features.push(feature);
+        Document {
+            components: vec![device],
+            sequences: vec![sequence],
+            activities: vec![],
+        }
+    }
+
+    #[test]
+    fn test_sequence_edit() {
+        let edit = SequenceEdit::new("acgtacgt", "acgGGtacgt");
+        assert_eq!(edit.position, 4);
+        assert_eq!((edit.deleted.as_str(), edit.inserted.as_str()), ("", "GG"));
+        assert_eq!(edit.to_string(), "8 -> 10 elements, \"GG\" inserted at 4");
+        let edit = SequenceEdit::new("acgtacgt", "acTTcgt");
+        assert_eq!(
+            edit.to_string(),
+            "8 -> 7 elements, \"gta\" replaced by \"TT\" at 3..5"
+        );
+        let edit = SequenceEdit::new("acgtacgt", "acgt");
+        assert_eq!(
+            edit.to_string(),
+            "8 -> 4 elements, \"acgt\" deleted at 5..8"
+        );
+    }
+
+    #[test]
+    fn test_diff() {
+        let left = design();
+        assert!(diff(&left, &left).unwrap().is_empty());
+
+        let mut right = design();
+        let namespace = uri("https://example.org");
+        right
+            .components
+            .push(Component::new(namespace, "terminator").unwrap());
+        let device = &mut right.components[0];
+        device.role = vec![ComponentRole::Operator];
+        device.name = Some("pTet".to_string());
+        if let Location::Range(range) = &mut device.sequence_features[0].has_location[0] {
+            range.start = 5;
+            range.end = 23;
+        }
+        right.sequences[0].elements = Some("tccctatcagtgatagagaTTGACA".to_string());
+
+        let diff = diff(&left, &right).unwrap();
+        assert_eq!(
+            diff.to_string(),
+            "+ https://example.org/terminator\n\
+             ~ https://example.org/device role: \
+             +<https://identifiers.org/SO:0000057> -<https://identifiers.org/SO:0000167>\n\
+             ~ https://example.org/device http://sbols.org/v3#name: [] != [\"pTet\"]\n\
+             ~ https://example.org/device/operator moved: [2..20] -> [5..23]\n\
+             ~ https://example.org/device_seq elements: \
+             25 -> 25 elements, \"ttgaca\" replaced by \"TTGACA\" at 20..25\n"
+        );
+        assert!(matches!(diff.changes[1], Change::Roles { .. }));
+    }
+}
@@ -0,0 +1,211 @@
+use url::Url;
+
+use crate::resolver::Resolver;
+use crate::validation::references::{dangling_references, ReferenceIssue};
+use crate::{Activity, Component, Identified, SbolError, Sequence, TopLevel};
+
+/// Collection of `TopLevel` objects, as stored in a single SBOL file.
+///
+/// Child objects (ie: `Feature` and `Location` objects) are stored within their parent
+/// `TopLevel`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Document {
+    pub components: Vec<Component>,
+    pub sequences: Vec<Sequence>,
+    pub activities: Vec<Activity>,
+}
+
+impl Document {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Find the `Component` identified by `uri`
+    pub fn component(&self, uri: &Url) -> Option<&Component> {
+        self.components.iter().find(|c| &c.identity == uri)
+    }
+
+    /// Find the `Component` identified by `uri`
+    pub fn component_mut(&mut self, uri: &Url) -> Option<&mut Component> {
+        self.components.iter_mut().find(|c| &c.identity == uri)
+    }
+
+    /// Find the `Sequence` identified by `uri`
+    pub fn sequence(&self, uri: &Url) -> Option<&Sequence> {
+        self.sequences.iter().find(|s| &s.identity == uri)
+    }
+
+    /// Find the `Sequence` identified by `uri`
+    pub fn sequence_mut(&mut self, uri: &Url) -> Option<&mut Sequence> {
+        self.sequences.iter_mut().find(|s| &s.identity == uri)
+    }
+
+    /// Find the `Activity` identified by `uri`
+    pub fn activity(&self, uri: &Url) -> Option<&Activity> {
+        self.activities.iter().find(|a| &a.identity == uri)
+    }
+
+    /// Find the `Activity` identified by `uri`
+    pub fn activity_mut(&mut self, uri: &Url) -> Option<&mut Activity> {
+        self.activities.iter_mut().find(|a| &a.identity == uri)
+    }
+
+    /// First nucleic acid `Sequence` of `component` which is found in this document
+    pub fn nucleic_acid_sequence(&self, component: &Component) -> Option<&Sequence> {
+        component
+            .has_sequence
+            .iter()
+            .filter_map(|uri| self.sequence(uri))
+            .find(|sequence| sequence.nucleic_acid_elements().is_ok())
+    }
+
+    /// All `TopLevel` objects of the document
+    pub fn top_levels(&self) -> impl Iterator<Item = &dyn TopLevel> {
+        let components = self.components.iter().map(|c| c as &dyn TopLevel);
+        let sequences = self.sequences.iter().map(|s| s as &dyn TopLevel);
+        let activities = self.activities.iter().map(|a| a as &dyn TopLevel);
+        components.chain(sequences).chain(activities)
+    }
+
+    /// Every `Identified` object of the document, with the URI of its parent for child objects.
+    ///
+    /// Each `TopLevel` comes first, followed by its descendants, and parents come before their
+    /// children.
+    pub fn objects(&self) -> Vec<(Option<&Url>, &dyn Identified)> {
+        let mut objects: Vec<(Option<&Url>, &dyn Identified)> = vec![];
+        for component in &self.components {
+            objects.push((None, component));
+            for child in component.children() {
+                objects.push((Some(&component.identity), child));
+            }
+            for sub_component in &component.sub_components {
+                let locations = sub_component
+                    .has_location
+                    .iter()
+                    .chain(&sub_component.source_location);
+                for location in locations {
+                    objects.push((Some(&sub_component.identity), location));
+                }
+            }
+            for feature in &component.sequence_features {
+                for location in &feature.has_location {
+                    objects.push((Some(&feature.identity), location));
+                }
+            }
+            for interaction in &component.has_interaction {
+                for participation in &interaction.has_participation {
+                    objects.push((Some(&interaction.identity), participation));
+                }
+            }
+        }
+        for sequence in &self.sequences {
+            objects.push((None, sequence));
+        }
+        for activity in &self.activities {
+            objects.push((None, activity));
+            for usage in &activity.has_usage {
+                objects.push((Some(&activity.identity), usage));
+            }
+        }
+        objects
+    }
+
+    /// Whether a `TopLevel` object is identified by `uri`
+    pub fn contains(&self, uri: &Url) -> bool {
+        self.top_levels()
+            .any(|top_level| &top_level.identity() == uri)
+    }
+
+    /// Add all `TopLevel` objects of `other` to this document
+    pub fn extend(&mut self, other: Document) {
+        self.components.extend(other.components);
+        self.sequences.extend(other.sequences);
+        self.activities.extend(other.activities);
+    }
+
+    /// Whether the document has the object identified by `uri`, after adding the `TopLevel`
+    /// which defines it, if `resolver` finds it.
+    ///
+    /// Only the `TopLevel` which defines the object is added, not the rest of the document
+    /// returned by `resolver`. A `TopLevel` which the document already has is kept as is, so that
+    /// the object remains missing when the local version does not define it.
+    pub fn resolve(&mut self, uri: &Url, resolver: &dyn Resolver) -> Result<bool, SbolError> {
+        if self.defining_top_level(uri).is_some() {
+            return Ok(true);
+        }
+        let Some(found) = resolver.resolve(uri)? else {
+            return Ok(false);
+        };
+        let Some(top_level) = found.defining_top_level(uri) else {
+            return Ok(false);
+        };
+        if self.contains(&top_level) {
+            return Ok(false);
+        }
+        self.components.extend(
+            found
+                .components
+                .into_iter()
+                .filter(|c| c.identity == top_level),
+        );
+        self.sequences.extend(
+            found
+                .sequences
+                .into_iter()
+                .filter(|s| s.identity == top_level),
+        );
+        self.activities.extend(
+            found
+                .activities
+                .into_iter()
+                .filter(|a| a.identity == top_level),
+        );
+        Ok(true)
+    }
+
+    /// Add the `TopLevel` objects which define the targets of the references that do not resolve
+    /// within the document, and of the references of the added objects in turn.
+    ///
+    /// Returns the URIs of the targets which `resolver` found, in the order they were added.
+    pub fn resolve_references(&mut self, resolver: &dyn Resolver) -> Result<Vec<Url>, SbolError> {
+        let mut resolved = vec![];
+        let mut unresolved: Vec<Url> = vec![];
+        loop {
+            let targets: Vec<Url> = dangling_references(self, &[])
+                .into_iter()
+                .filter(|reference| {
+                    !matches!(reference.issue, ReferenceIssue::WrongType { .. })
+                        && !unresolved.contains(&reference.target)
+                })
+                .map(|reference| reference.target)
+                .collect();
+            if targets.is_empty() {
+                return Ok(resolved);
+            }
+            for target in targets {
+                if self.resolve(&target, resolver)? {
+                    if !resolved.contains(&target) {
+                        resolved.push(target);
+                    }
+                } else if !unresolved.contains(&target) {
+                    unresolved.push(target);
+                }
+            }
+        }
+    }
+
+    /// URI of the `TopLevel` which is, or has, the object identified by `uri`
+    pub(crate) fn defining_top_level(&self, uri: &Url) -> Option<Url> {
+        let mut top_level = None;
+        for (parent, object) in self.objects() {
+            let identity = object.identity();
+            if parent.is_none() {
+                top_level = Some(identity.clone());
+            }
+            if &identity == uri {
+                return top_level;
+            }
+        }
+        None
+    }
+}
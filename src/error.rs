@@ -0,0 +1,56 @@
+use std::fmt;
+use std::io;
+
+use url::Url;
+
+/// Errors produced by this crate.
+///
+/// Library code never panics on user input. Instead, every fallible operation returns one of these
+/// variants.
+#[derive(Debug)]
+pub enum SbolError {
+    /// Input could not be parsed (ie: a malformed URI, an unknown ontology term, or a malformed
+    /// file).
+    Parse(String),
+
+    /// An object or value violates a rule of the SBOL specification.
+    Validation(String),
+
+    /// Failure of the underlying reader or writer.
+    Io(io::Error),
+
+    /// A URI does not resolve to an object.
+    UnresolvedReference(Url),
+}
+
+impl fmt::Display for SbolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse(msg) => write!(f, "Parse error: {msg}"),
+            Self::Validation(msg) => write!(f, "Validation error: {msg}"),
+            Self::Io(err) => write!(f, "IO error: {err}"),
+            Self::UnresolvedReference(uri) => write!(f, "Unresolved reference: {uri}"),
+        }
+    }
+}
+
+impl std::error::Error for SbolError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for SbolError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<url::ParseError> for SbolError {
+    fn from(err: url::ParseError) -> Self {
+        Self::Parse(format!("Error parsing URI: {err}"))
+    }
+}
@@ -1,15 +1,20 @@
 use url::Url;
 
+use crate::identified::{child_uri, impl_identified};
+use crate::ontologies::{ComponentRole, Orientation, RoleIntegration};
+use crate::sequence::nucleic_acid;
+use crate::{Annotation, Identified, Location, Range, SbolError};
+
 /// Used to compose `Component` objects into a structural or functional hierarchy.
-pub trait Feature {
+pub trait Feature: Identified {
     /// Describes the purpose or potential function in the context of its parent `Component`.
     ///
     /// If the `role` for a `SubComponent` is left unspecified, then the `role` is determined by
     /// the `role` property of the `Component` that it is an `instance_of`.
     ///
-    fn role(&self) -> &Vec<Url>;
+    fn role(&self) -> &Vec<ComponentRole>;
 
-    fn orientation(&self) -> &Vec<Url>;
+    fn orientation(&self) -> Option<&Orientation>;
 }
 
 /// Subclass of the `Feature` class that can be used to specify structural hierarchy.
@@ -18,25 +23,216 @@ pub trait Feature {
 /// RMS, CDS, and terminator, each linked to the `Component` that provides the complete definition.
 /// In turn, the `Component` of the promoter `SubComponent` might itself contain `SubComponent`
 /// objects defining various operator sites, etc.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct SubComponent {
-    role: Vec<Url>,
-    orientation: Vec<Url>,
-}
+    pub identity: Url,
+    pub display_id: Option<String>,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub derived_from: Vec<Url>,
+    pub generated_by: Vec<Url>,
+    pub has_measure: Vec<Url>,
+    pub annotations: Vec<Annotation>,
+    pub role: Vec<ComponentRole>,
+    pub orientation: Option<Orientation>,
 
-impl SubComponent {
     /// Specifies the relationship between a `SubComponent` instance's own set of `role` properties
     /// and the set of `role` properties on the included `Component`.
-    pub fn role_integration(&self) -> &Vec<Url> {
-        todo!()
+    ///
+    /// Required if `role` is set.
+    pub role_integration: Option<RoleIntegration>,
+
+    /// `Component` that this `SubComponent` is an instance of
+    pub instance_of: Url,
+
+    /// Locations of this `SubComponent` on the `Sequence` of its parent `Component`
+    pub has_location: Vec<Location>,
+
+    /// Region of the `Sequence` of the `instance_of` `Component` which is included. If not set,
+    /// the entire `Sequence` is included.
+    pub source_location: Vec<Location>,
+}
+
+impl SubComponent {
+    /// Create a `SubComponent` whose URI is `parent/display_id`
+    pub fn new(parent: &Url, display_id: &str, instance_of: Url) -> Result<Self, SbolError> {
+        Ok(Self {
+            identity: child_uri(parent, display_id)?,
+            display_id: Some(display_id.to_string()),
+            name: None,
+            description: None,
+            derived_from: vec![],
+            generated_by: vec![],
+            has_measure: vec![],
+            annotations: vec![],
+            role: vec![],
+            orientation: None,
+            role_integration: None,
+            instance_of,
+            has_location: vec![],
+            source_location: vec![],
+        })
+    }
+
+    /// Part of `elements`, the elements of the `Component` that this `SubComponent` is an
+    /// instance of, which is included.
+    ///
+    /// The `Range` objects of `source_location` are read in their `orientation` and concatenated
+    /// in order. All of `elements` is included when `source_location` has no `Range`.
+    pub fn source_elements(&self, elements: &str) -> Result<String, SbolError> {
+        let ranges: Vec<&Range> = self
+            .source_location
+            .iter()
+            .filter_map(|location| match location {
+                Location::Range(range) => Some(range),
+                _ => None,
+            })
+            .collect();
+        if ranges.is_empty() {
+            return Ok(elements.to_string());
+        }
+        let mut source = String::new();
+        for range in ranges {
+            if range.start == 0 {
+                return Err(SbolError::Validation(format!(
+                    "{} starts at 0, but positions are 1-based",
+                    range.identity
+                )));
+            }
+            let region = elements.get(range.start - 1..range.end).ok_or_else(|| {
+                SbolError::Validation(format!(
+                    "{} is outside of the {} elements of {}",
+                    range.identity,
+                    elements.len(),
+                    self.instance_of
+                ))
+            })?;
+            match &range.orientation {
+                Some(orientation) => source.push_str(&nucleic_acid::orient(region, orientation)?),
+                None => source.push_str(region),
+            }
+        }
+        Ok(source)
     }
 }
 
 impl Feature for SubComponent {
-    fn role(&self) -> &Vec<Url> {
+    fn role(&self) -> &Vec<ComponentRole> {
+        &self.role
+    }
+
+    fn orientation(&self) -> Option<&Orientation> {
+        self.orientation.as_ref()
+    }
+}
+
+/// Subclass of the `Feature` class that describes one or more regions of interest on the
+/// `Sequence` objects of its parent `Component`.
+///
+/// Unlike a `SubComponent`, a `SequenceFeature` is not linked to a `Component` which provides a
+/// complete definition, ie: an annotation imported from a GenBank file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SequenceFeature {
+    pub identity: Url,
+    pub display_id: Option<String>,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub derived_from: Vec<Url>,
+    pub generated_by: Vec<Url>,
+    pub has_measure: Vec<Url>,
+    pub annotations: Vec<Annotation>,
+    pub role: Vec<ComponentRole>,
+    pub orientation: Option<Orientation>,
+
+    /// One or more regions of interest. Must not be empty.
+    pub has_location: Vec<Location>,
+}
+
+impl SequenceFeature {
+    /// Create a `SequenceFeature` whose URI is `parent/display_id`
+    pub fn new(parent: &Url, display_id: &str) -> Result<Self, SbolError> {
+        Ok(Self {
+            identity: child_uri(parent, display_id)?,
+            display_id: Some(display_id.to_string()),
+            name: None,
+            description: None,
+            derived_from: vec![],
+            generated_by: vec![],
+            has_measure: vec![],
+            annotations: vec![],
+            role: vec![],
+            orientation: None,
+            has_location: vec![],
+        })
+    }
+}
+
+impl Feature for SequenceFeature {
+    fn role(&self) -> &Vec<ComponentRole> {
+        &self.role
+    }
+
+    fn orientation(&self) -> Option<&Orientation> {
+        self.orientation.as_ref()
+    }
+}
+
+/// Subclass of the `Feature` class that refers to a `Feature` of a `Component` included by a
+/// `SubComponent`, ie: to connect the output of a nested device with a `Constraint`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComponentReference {
+    pub identity: Url,
+    pub display_id: Option<String>,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub derived_from: Vec<Url>,
+    pub generated_by: Vec<Url>,
+    pub has_measure: Vec<Url>,
+    pub annotations: Vec<Annotation>,
+    pub role: Vec<ComponentRole>,
+    pub orientation: Option<Orientation>,
+
+    /// `SubComponent` (or `ComponentReference`) of the parent `Component` through which the
+    /// `Feature` is referred to
+    pub in_child_of: Url,
+
+    /// `Feature` of the `Component` that `in_child_of` is an instance of
+    pub refers_to: Url,
+}
+
+impl ComponentReference {
+    /// Create a `ComponentReference` whose URI is `parent/display_id`
+    pub fn new(
+        parent: &Url,
+        display_id: &str,
+        in_child_of: Url,
+        refers_to: Url,
+    ) -> Result<Self, SbolError> {
+        Ok(Self {
+            identity: child_uri(parent, display_id)?,
+            display_id: Some(display_id.to_string()),
+            name: None,
+            description: None,
+            derived_from: vec![],
+            generated_by: vec![],
+            has_measure: vec![],
+            annotations: vec![],
+            role: vec![],
+            orientation: None,
+            in_child_of,
+            refers_to,
+        })
+    }
+}
+
+impl Feature for ComponentReference {
+    fn role(&self) -> &Vec<ComponentRole> {
         &self.role
     }
 
-    fn orientation(&self) -> &Vec<Url> {
-        &self.orientation
+    fn orientation(&self) -> Option<&Orientation> {
+        self.orientation.as_ref()
     }
 }
+
+impl_identified!(SubComponent, SequenceFeature, ComponentReference);
@@ -0,0 +1,356 @@
+//! Conversion between FASTA files and SBOL.
+//!
+//! Each FASTA record becomes a `Sequence`, identified by the sanitized first word of its header.
+//! The `Encoding` of each `Sequence` is inferred from its alphabet. Optionally, each `Sequence` is
+//! wrapped by a DNA, RNA or protein `Component`.
+
+use std::collections::HashSet;
+use std::io::{Read, Write};
+
+use url::Url;
+
+use crate::alphabet::{self, IUPAC_AMINO_ACIDS};
+use crate::identified::unused_display_id;
+use crate::nucleic_acid::IUPAC_NUCLEOTIDES;
+use crate::ontologies::{ComponentTypeOntology, Encoding};
+use crate::{sanitize_display_id, Component, ComponentType, Document, SbolError, Sequence};
+
+/// Number of elements written per line
+const LINE_WIDTH: usize = 70;
+
+/// Minimum fraction of unambiguous nucleotides for elements to be considered nucleic acid
+const NUCLEOTIDE_FRACTION: f64 = 0.9;
+
+/// Parameters of FASTA import
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FastaOptions {
+    /// Wrap each `Sequence` in a `Component`, which is identified by the sanitized header and
+    /// typed by the inferred encoding. The `Sequence` is then identified by the same name suffixed
+    /// with `_sequence`, and a number if another record already uses that name.
+    pub components: bool,
+}
+
+/// Read every FASTA record of `reader` into a `Document`.
+///
+/// See [`parse`].
+pub fn read<R: Read>(
+    mut reader: R,
+    namespace: &Url,
+    options: &FastaOptions,
+) -> Result<Document, SbolError> {
+    let mut input = String::new();
+    reader.read_to_string(&mut input)?;
+    parse(&input, namespace, options)
+}
+
+/// Parse every FASTA record of `input` into a `Document`.
+///
+/// The first word of each header becomes the `name`, and the remainder the `description`.
+/// Identical sanitized headers are disambiguated by a numeric suffix.
+pub fn parse(input: &str, namespace: &Url, options: &FastaOptions) -> Result<Document, SbolError> {
+    let mut document = Document::new();
+    let mut display_ids = HashSet::new();
+    let mut record: Option<(usize, &str, String)> = None;
+
+    for (i, line) in input.lines().enumerate() {
+        let line = line.trim_end();
+        if let Some(header) = line.strip_prefix('>') {
+            if let Some(record) = record.take() {
+                parse_record(record, namespace, options, &mut display_ids, &mut document)?;
+            }
+            record = Some((i + 1, header, String::new()));
+        } else if line.starts_with(';') || line.is_empty() {
+            continue;
+        } else {
+            match record.as_mut() {
+                Some((_, _, elements)) => elements.push_str(line),
+                None => {
+                    return Err(SbolError::Parse(format!(
+                        "FASTA line {}: Sequence without header",
+                        i + 1
+                    )))
+                }
+            }
+        }
+    }
+    if let Some(record) = record {
+        parse_record(record, namespace, options, &mut display_ids, &mut document)?;
+    }
+    Ok(document)
+}
+
+fn parse_record(
+    (line, header, elements): (usize, &str, String),
+    namespace: &Url,
+    options: &FastaOptions,
+    display_ids: &mut HashSet<String>,
+    document: &mut Document,
+) -> Result<(), SbolError> {
+    let header = header.trim();
+    let (name, description) = match header.split_once(char::is_whitespace) {
+        Some((name, description)) => (name, Some(description.trim().to_string())),
+        None => (header, None),
+    };
+
+    let mut display_id = sanitize_display_id(name);
+    let mut suffix = 1;
+    while display_ids.contains(&display_id) {
+        suffix += 1;
+        display_id = format!("{}_{suffix}", sanitize_display_id(name));
+    }
+    display_ids.insert(display_id.clone());
+
+    let encoding = infer_encoding(&elements)
+        .map_err(|msg| SbolError::Parse(format!("FASTA line {line}: {msg}")))?;
+    let sequence_id = if options.components {
+        let sequence_id = unused_display_id(&format!("{display_id}_sequence"), |display_id| {
+            display_ids.contains(display_id)
+        });
+        display_ids.insert(sequence_id.clone());
+        sequence_id
+    } else {
+        display_id.clone()
+    };
+    let mut sequence = Sequence::new(namespace.clone(), &sequence_id)?;
+    sequence.elements = Some(alphabet::normalize(&elements, &encoding));
+    sequence.encoding = Some(encoding);
+
+    if options.components {
+        let mut component = Component::new(namespace.clone(), &display_id)?;
+        component.name = Some(name.to_string());
+        component.description = description;
+        component.r#type = vec![ComponentType::Type(component_type(&sequence))];
+        component.has_sequence = vec![sequence.identity.clone()];
+        document.components.push(component);
+    } else {
+        sequence.name = Some(name.to_string());
+        sequence.description = description;
+    }
+    document.sequences.push(sequence);
+    Ok(())
+}
+
+/// Infer the `Encoding` of `elements` from their alphabet.
+///
+/// Elements are nucleic acid if they only contain IUPAC nucleotide codes, mostly unambiguous
+/// ones. Otherwise they are protein if they only contain IUPAC amino acid codes. Whitespace and
+/// digits are ignored.
+pub fn infer_encoding(elements: &str) -> Result<Encoding, String> {
+    let elements: Vec<char> = elements
+        .chars()
+        .filter(|c| !c.is_whitespace() && !c.is_ascii_digit())
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+    let unambiguous = elements.iter().filter(|c| "ACGTUN-".contains(**c)).count();
+
+    if elements.iter().all(|c| IUPAC_NUCLEOTIDES.contains(*c))
+        && unambiguous as f64 >= NUCLEOTIDE_FRACTION * elements.len() as f64
+    {
+        return Ok(Encoding::NucleicAcid);
+    }
+    match elements
+        .iter()
+        .enumerate()
+        .find(|(_, c)| !IUPAC_AMINO_ACIDS.contains(**c))
+    {
+        Some((i, c)) => Err(format!(
+            "Unable to infer encoding, invalid element {c:?} at position {}",
+            i + 1
+        )),
+        None => Ok(Encoding::Protein),
+    }
+}
+
+/// `ComponentTypeOntology` that corresponds to the encoding of `sequence`
+fn component_type(sequence: &Sequence) -> ComponentTypeOntology {
+    let elements = sequence.elements.as_deref().unwrap_or_default();
+    match &sequence.encoding {
+        Some(Encoding::Protein) => ComponentTypeOntology::Protein,
+        _ if elements.contains('u') && !elements.contains('t') => ComponentTypeOntology::RNA,
+        _ => ComponentTypeOntology::DNA,
+    }
+}
+
+/// Write every `Sequence` of `document` with `elements` as a FASTA record.
+///
+/// See [`to_string`].
+pub fn write<W: Write>(document: &Document, mut writer: W) -> Result<(), SbolError> {
+    writer.write_all(to_string(document)?.as_bytes())?;
+    Ok(())
+}
+
+/// Serialize every `Sequence` of `document` with `elements` as a FASTA record.
+///
+/// The header is made of an identifier and the `description` of the `Component` which refers to
+/// the `Sequence`, or of the `Sequence` itself if no `Component` does. The identifier is the
+/// `name` if it is a single word which sanitizes into the `display_id`, so that [`parse`] restores
+/// both, and the `display_id` otherwise.
+pub fn to_string(document: &Document) -> Result<String, SbolError> {
+    let mut output = String::new();
+    for sequence in &document.sequences {
+        let Some(elements) = &sequence.elements else {
+            continue;
+        };
+        let component = document
+            .components
+            .iter()
+            .find(|c| c.has_sequence.contains(&sequence.identity));
+        let (display_id, name, description) = match component {
+            Some(c) => (&c.display_id, &c.name, &c.description),
+            None => (&sequence.display_id, &sequence.name, &sequence.description),
+        };
+        let display_id = display_id.clone().ok_or_else(|| {
+            SbolError::Validation(format!("{} has no display_id", sequence.identity))
+        })?;
+
+        let identifier = name
+            .as_ref()
+            .filter(|name| {
+                !name.contains(char::is_whitespace) && sanitize_display_id(name) == display_id
+            })
+            .unwrap_or(&display_id);
+        output.push('>');
+        output.push_str(identifier);
+        if let Some(description) = description {
+            output.push(' ');
+            output.push_str(description);
+        }
+        output.push('\n');
+        let chars: Vec<char> = elements.chars().collect();
+        for line in chars.chunks(LINE_WIDTH) {
+            output.extend(line);
+            output.push('\n');
+        }
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use url::Url;
+
+    use crate::ontologies::{ComponentTypeOntology, Encoding};
+    use crate::ComponentType;
+
+    use super::*;
+    use crate::test_utils::uri;
+
+    const OLIGOS: &str = ">oligo-1 forward primer
+ACGTACGTAC
+GTACGT
+; comment
+>oligo-1 duplicate header
+acgu
+>GFP|P42212
+MSKGEELFTGVVPILVELDGDVNGHKFSVSGEGEGDATYG
+";
+
+    fn namespace() -> Url {
+        uri("https://test.org/lab")
+    }
+
+    #[test]
+    fn test_parse_sequences() {
+        let document = parse(OLIGOS, &namespace(), &FastaOptions::default()).unwrap();
+        assert!(document.components.is_empty());
+
+        let sequences = &document.sequences;
+        assert_eq!(sequences.len(), 3);
+        assert_eq!(sequences[0].display_id.as_deref(), Some("oligo_1"));
+        assert_eq!(sequences[0].name.as_deref(), Some("oligo-1"));
+        assert_eq!(sequences[0].description.as_deref(), Some("forward primer"));
+        assert_eq!(sequences[0].elements.as_deref(), Some("acgtacgtacgtacgt"));
+        assert_eq!(sequences[0].encoding, Some(Encoding::NucleicAcid));
+        assert_eq!(sequences[1].display_id.as_deref(), Some("oligo_1_2"));
+        assert_eq!(sequences[2].display_id.as_deref(), Some("GFP_P42212"));
+        assert_eq!(sequences[2].encoding, Some(Encoding::Protein));
+    }
+
+    #[test]
+    fn test_parse_components() {
+        let options = FastaOptions { components: true };
+        let document = parse(OLIGOS, &namespace(), &options).unwrap();
+        let types: Vec<_> = document.components.iter().map(|c| &c.r#type[0]).collect();
+        assert_eq!(
+            types,
+            vec![
+                &ComponentType::Type(ComponentTypeOntology::DNA),
+                &ComponentType::Type(ComponentTypeOntology::RNA),
+                &ComponentType::Type(ComponentTypeOntology::Protein),
+            ]
+        );
+        let component = &document.components[0];
+        assert_eq!(component.identity.as_str(), "https://test.org/lab/oligo_1");
+        assert_eq!(
+            component.has_sequence[0].as_str(),
+            "https://test.org/lab/oligo_1_sequence"
+        );
+    }
+
+    #[test]
+    fn test_sequence_display_id_collision() {
+        let options = FastaOptions { components: true };
+        let input = ">a_sequence\nACGT\n>a\nACGT\n>a_sequence\nACGT\n";
+        let document = parse(input, &namespace(), &options).unwrap();
+        let components: Vec<_> = document
+            .components
+            .iter()
+            .filter_map(|c| c.display_id.as_deref())
+            .collect();
+        let sequences: Vec<_> = document
+            .sequences
+            .iter()
+            .filter_map(|s| s.display_id.as_deref())
+            .collect();
+        assert_eq!(components, ["a_sequence", "a", "a_sequence_2"]);
+        assert_eq!(
+            sequences,
+            [
+                "a_sequence_sequence",
+                "a_sequence1",
+                "a_sequence_2_sequence"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_header() {
+        let input = ">GFP GFP from Aequorea\nMSKGEELFTG\n";
+        let document = parse(input, &namespace(), &FastaOptions::default()).unwrap();
+        let sequence = &document.sequences[0];
+        assert_eq!(sequence.name.as_deref(), Some("GFP"));
+        assert_eq!(sequence.description.as_deref(), Some("GFP from Aequorea"));
+        assert_eq!(to_string(&document).unwrap(), input);
+    }
+
+    #[test]
+    fn test_infer_encoding() {
+        assert_eq!(infer_encoding("ACGTN").unwrap(), Encoding::NucleicAcid);
+        // Only made of nucleotide ambiguity codes, which are also amino acids
+        assert_eq!(infer_encoding("MKVDHRSW").unwrap(), Encoding::Protein);
+        assert!(infer_encoding("ACGT#").is_err());
+        assert!(parse("ACGT\n", &namespace(), &FastaOptions::default()).is_err());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for options in [FastaOptions::default(), FastaOptions { components: true }] {
+            let document = parse(OLIGOS, &namespace(), &options).unwrap();
+            let output = to_string(&document).unwrap();
+            assert!(output.starts_with(">oligo-1 forward primer\nacgtacgtacgtacgt\n"));
+            assert!(output.contains(">oligo_1_2 duplicate header\n"));
+            assert!(output.contains(">GFP|P42212\n"));
+
+            // Only the display_id of the duplicate is written, as it is not its name sanitized
+            let mut document = document;
+            let name = Some("oligo_1_2".to_string());
+            if options.components {
+                document.components[1].name = name;
+            } else {
+                document.sequences[1].name = name;
+            }
+            let restored = parse(&output, &namespace(), &options).unwrap();
+            assert_eq!(restored, document);
+        }
+    }
+}
@@ -0,0 +1,1207 @@
+//! Conversion between GenBank flat files and SBOL.
+//!
+//! Each GenBank record becomes a `Component` with a single `Sequence`. The LOCUS line determines
+//! the `ComponentTypeOntology` and `TopologyOntology` of the `Component`, and every entry of the
+//! feature table becomes a `SequenceFeature` whose role is mapped from the feature key.
+//!
+//! Export performs the reverse conversion. Properties which GenBank cannot express (ie: roles
+//! without a feature key, or the `instance_of` property of a `SubComponent`) are written to
+//! `sbol_*` qualifiers and comments, which are restored on import.
+
+use std::io::{Read, Write};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use url::Url;
+
+use crate::alphabet;
+use crate::ontologies::{
+    ComponentRole, ComponentTypeOntology, Encoding, Ontology, Orientation, TopologyOntology, SO_NS,
+};
+use crate::uris::child_display_id;
+use crate::{
+    sanitize_display_id, Component, ComponentType, Cut, Document, Feature, Location, Range,
+    SbolError, Sequence, SequenceFeature, SubComponent,
+};
+
+/// Mapping between GenBank feature keys and terms of the Sequence Ontology
+pub const FEATURE_KEYS: [(&str, &str); 39] = [
+    ("promoter", "0000167"),
+    ("RBS", "0000139"),
+    ("CDS", "0000316"),
+    ("terminator", "0000141"),
+    ("gene", "0000704"),
+    ("mRNA", "0000234"),
+    ("misc_feature", "0000001"),
+    ("protein_bind", "0000410"),
+    ("rep_origin", "0000296"),
+    ("primer_bind", "0005850"),
+    ("5'UTR", "0000204"),
+    ("3'UTR", "0000205"),
+    ("polyA_signal", "0000551"),
+    ("polyA_site", "0000553"),
+    ("sig_peptide", "0000418"),
+    ("mat_peptide", "0000419"),
+    ("transit_peptide", "0000725"),
+    ("propeptide", "0001062"),
+    ("misc_RNA", "0000673"),
+    ("ncRNA", "0000655"),
+    ("rRNA", "0000252"),
+    ("tRNA", "0000253"),
+    ("precursor_RNA", "0000185"),
+    ("exon", "0000147"),
+    ("intron", "0000188"),
+    ("enhancer", "0000165"),
+    ("regulatory", "0005836"),
+    ("repeat_region", "0000657"),
+    ("LTR", "0000286"),
+    ("misc_binding", "0000409"),
+    ("stem_loop", "0000313"),
+    ("oriT", "0000724"),
+    ("mobile_element", "0001037"),
+    ("misc_recomb", "0000298"),
+    ("variation", "0001060"),
+    ("misc_difference", "0000413"),
+    ("STS", "0000331"),
+    ("gap", "0000730"),
+    ("operon", "0000178"),
+];
+
+/// Feature key used for roles which have no corresponding key
+const DEFAULT_FEATURE_KEY: &str = "misc_feature";
+
+/// Column at which locations and qualifiers of the feature table begin
+const FEATURE_INDENT: usize = 21;
+
+/// Column at which the values of header keywords begin
+const HEADER_INDENT: usize = 12;
+
+/// Maximum width of a line
+const LINE_WIDTH: usize = 79;
+
+/// Maximum length of the name of the LOCUS line, whose fields are at fixed columns
+const LOCUS_NAME_WIDTH: usize = 16;
+
+/// Months of the dates of LOCUS lines
+const MONTHS: [&str; 12] = [
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+];
+
+/// Qualifier holding the `display_id` of a `Feature`
+const SBOL_DISPLAY_ID: &str = "sbol_display_id";
+
+/// Qualifier holding the `instance_of` property of a `SubComponent`
+const SBOL_INSTANCE_OF: &str = "sbol_instance_of";
+
+/// Qualifier holding the `orientation` of a `Feature`
+const SBOL_ORIENTATION: &str = "sbol_orientation";
+
+/// Qualifier or comment holding a `role` which cannot be expressed by a feature key
+const SBOL_ROLE: &str = "sbol_role";
+
+/// Comment holding the `name` of a `Component`
+const SBOL_NAME: &str = "sbol_name";
+
+/// Comment holding a `type` of a `Component` which cannot be expressed by the LOCUS line
+const SBOL_TYPE: &str = "sbol_type";
+
+/// Sequence Ontology role of a GenBank feature key.
+///
+/// Unknown keys are mapped to the role of `misc_feature` (SO:0000001, region).
+pub fn feature_role(key: &str) -> Result<ComponentRole, SbolError> {
+    let id = |key: &str| {
+        FEATURE_KEYS
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, id)| *id)
+    };
+    let id = id(key)
+        .or_else(|| id(DEFAULT_FEATURE_KEY))
+        .unwrap_or_default();
+    Ok(Url::parse(&format!("{SO_NS}{id}"))?.into())
+}
+
+/// GenBank feature key of a Sequence Ontology role, if there is one.
+pub fn feature_key(role: &ComponentRole) -> Option<&'static str> {
+    let uri = role.uri().ok()?;
+    FEATURE_KEYS
+        .iter()
+        .find(|(_, id)| uri.as_str() == format!("{SO_NS}{id}"))
+        .map(|(key, _)| *key)
+}
+
+/// Read every GenBank record of `reader` into a `Document`.
+///
+/// See [`parse`].
+pub fn read<R: Read>(mut reader: R, namespace: &Url) -> Result<Document, SbolError> {
+    let mut input = String::new();
+    reader.read_to_string(&mut input)?;
+    parse(&input, namespace)
+}
+
+/// Parse every GenBank record of `input` into a `Document`.
+///
+/// Objects are created within `namespace`. Each `Component` is identified by the sanitized LOCUS
+/// name, and its `Sequence` by the same name suffixed with `_sequence`.
+pub fn parse(input: &str, namespace: &Url) -> Result<Document, SbolError> {
+    let mut document = Document::new();
+    let mut record = vec![];
+    for (i, line) in input.lines().enumerate() {
+        if line.starts_with("//") {
+            parse_record(&record, namespace, &mut document)?;
+            record.clear();
+        } else if !line.trim().is_empty() || !record.is_empty() {
+            record.push((i + 1, line));
+        }
+    }
+    if record.iter().any(|(_, line)| !line.trim().is_empty()) {
+        parse_record(&record, namespace, &mut document)?;
+    }
+    Ok(document)
+}
+
+/// Entry of the feature table, prior to conversion
+struct RawFeature {
+    line: usize,
+    key: String,
+    location: String,
+    qualifiers: Vec<(String, Option<String>)>,
+}
+
+impl RawFeature {
+    fn qualifier(&self, name: &str) -> Option<&str> {
+        self.qualifiers
+            .iter()
+            .find(|(n, _)| n == name)
+            .and_then(|(_, value)| value.as_deref())
+    }
+}
+
+/// Section of a record which is being parsed
+#[derive(PartialEq)]
+enum Section {
+    Header,
+    Comment,
+    Features,
+    Origin,
+}
+
+fn parse_error(line: usize, msg: impl std::fmt::Display) -> SbolError {
+    SbolError::Parse(format!("GenBank line {line}: {msg}"))
+}
+
+fn parse_record(
+    lines: &[(usize, &str)],
+    namespace: &Url,
+    document: &mut Document,
+) -> Result<(), SbolError> {
+    let (first_line, locus) = lines
+        .first()
+        .ok_or_else(|| SbolError::Parse("Empty GenBank record".to_string()))?;
+    if !locus.starts_with("LOCUS") {
+        return Err(parse_error(*first_line, "Expected LOCUS line"));
+    }
+    let tokens: Vec<&str> = locus.split_whitespace().collect();
+    let locus_name = tokens
+        .get(1)
+        .ok_or_else(|| parse_error(*first_line, "Missing LOCUS name"))?;
+    let unit = tokens.iter().position(|t| *t == "bp" || *t == "aa");
+    let protein = unit.is_some_and(|i| tokens[i] == "aa");
+    let molecule = unit.and_then(|i| tokens.get(i + 1)).copied();
+    let topology = tokens
+        .iter()
+        .find(|t| t.eq_ignore_ascii_case("linear") || t.eq_ignore_ascii_case("circular"));
+
+    let display_id = sanitize_display_id(locus_name);
+    let mut component = Component::new(namespace.clone(), &display_id)?;
+    component.name = Some(locus_name.to_string());
+    component.r#type = component_types(protein, molecule, topology.copied());
+
+    let mut sequence = Sequence::new(namespace.clone(), &format!("{display_id}_sequence"))?;
+    if document.contains(&component.identity) || document.contains(&sequence.identity) {
+        return Err(parse_error(
+            *first_line,
+            format!("Duplicate LOCUS name {locus_name}, which is identified as {display_id}"),
+        ));
+    }
+    sequence.encoding = Some(if protein {
+        Encoding::Protein
+    } else {
+        Encoding::NucleicAcid
+    });
+
+    let mut section = Section::Header;
+    let mut definition = String::new();
+    let mut in_definition = false;
+    let mut comments: Vec<&str> = vec![];
+    let mut features: Vec<RawFeature> = vec![];
+    let mut elements = String::new();
+
+    for (number, line) in &lines[1..] {
+        let keyword = !line.starts_with(' ') && !line.is_empty();
+        if keyword {
+            in_definition = line.starts_with("DEFINITION");
+            if in_definition {
+                definition.push_str(line["DEFINITION".len()..].trim());
+            }
+            section = match line.split_whitespace().next() {
+                Some("COMMENT") => Section::Comment,
+                Some("FEATURES") => Section::Features,
+                Some("ORIGIN") => Section::Origin,
+                _ => Section::Header,
+            };
+            if section == Section::Comment {
+                comments.push(line["COMMENT".len()..].trim());
+            }
+            continue;
+        }
+
+        match section {
+            Section::Header => {
+                if in_definition {
+                    definition.push(' ');
+                    definition.push_str(line.trim());
+                }
+            }
+            Section::Comment => comments.push(line.trim()),
+            Section::Origin => elements.push_str(line),
+            Section::Features => parse_feature_line(*number, line, &mut features)?,
+        }
+    }
+
+    if !definition.is_empty() {
+        component.description = Some(definition);
+    }
+    for comment in comments {
+        match comment.split_once(": ") {
+            Some((SBOL_NAME, name)) => component.name = Some(name.to_string()),
+            Some((SBOL_ROLE, role)) => component.role.push(role.parse()?),
+            Some((SBOL_TYPE, r#type)) => component.r#type.push(Url::parse(r#type)?.into()),
+            _ => (),
+        }
+    }
+    sequence.elements = Some(alphabet::normalize(
+        &elements,
+        sequence.encoding.as_ref().unwrap_or(&Encoding::NucleicAcid),
+    ));
+
+    for feature in features.iter().filter(|f| f.key != "source") {
+        convert_feature(feature, &mut component, &sequence)?;
+    }
+
+    component.has_sequence.push(sequence.identity.clone());
+    document.components.push(component);
+    document.sequences.push(sequence);
+    Ok(())
+}
+
+/// `type` properties of a `Component` from the fields of the LOCUS line
+fn component_types(
+    protein: bool,
+    molecule: Option<&str>,
+    topology: Option<&str>,
+) -> Vec<ComponentType> {
+    let molecule = molecule.unwrap_or("DNA");
+    let mut types = vec![ComponentType::Type(if protein {
+        ComponentTypeOntology::Protein
+    } else if molecule.to_ascii_uppercase().contains("RNA") {
+        ComponentTypeOntology::RNA
+    } else {
+        ComponentTypeOntology::DNA
+    })];
+    if protein {
+        return types;
+    }
+    match topology.map(|t| t.to_ascii_lowercase()).as_deref() {
+        Some("circular") => types.push(ComponentType::Topology(TopologyOntology::Circular)),
+        Some("linear") => types.push(ComponentType::Topology(TopologyOntology::Linear)),
+        _ => (),
+    }
+    match molecule.get(..3).map(|m| m.to_ascii_lowercase()).as_deref() {
+        Some("ss-") => types.push(ComponentType::Topology(TopologyOntology::SingleStranded)),
+        Some("ds-") => types.push(ComponentType::Topology(TopologyOntology::DoubleStranded)),
+        _ => (),
+    }
+    types
+}
+
+/// Add a line of the feature table to `features`
+fn parse_feature_line(
+    number: usize,
+    line: &str,
+    features: &mut Vec<RawFeature>,
+) -> Result<(), SbolError> {
+    let indent = line.len() - line.trim_start().len();
+    let content = line.trim();
+    if indent < FEATURE_INDENT {
+        let (key, location) = content
+            .split_once(char::is_whitespace)
+            .ok_or_else(|| parse_error(number, "Feature without location"))?;
+        features.push(RawFeature {
+            line: number,
+            key: key.to_string(),
+            location: location.trim().to_string(),
+            qualifiers: vec![],
+        });
+        return Ok(());
+    }
+
+    let feature = features
+        .last_mut()
+        .ok_or_else(|| parse_error(number, "Qualifier outside of a feature"))?;
+    if let Some(qualifier) = content.strip_prefix('/') {
+        let (name, value) = match qualifier.split_once('=') {
+            Some((name, value)) => (name, Some(value.to_string())),
+            None => (qualifier, None),
+        };
+        feature.qualifiers.push((name.to_string(), value));
+    } else if let Some((name, Some(value))) = feature.qualifiers.last_mut() {
+        // Continuation of a qualifier value, which was wrapped on whitespace unless it has none
+        if !unspaced(name) {
+            value.push(' ');
+        }
+        value.push_str(content);
+    } else if feature.qualifiers.is_empty() {
+        feature.location.push_str(content);
+    } else {
+        return Err(parse_error(number, "Unexpected continuation line"));
+    }
+    Ok(())
+}
+
+/// Value of a qualifier, without quotes
+fn unquote(value: &str) -> String {
+    let value = value.trim();
+    let value = value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .unwrap_or(value);
+    value.replace("\"\"", "\"")
+}
+
+/// Convert an entry of the feature table into a `SequenceFeature`, or a `SubComponent` when it
+/// was exported from one, and add it to `component`.
+fn convert_feature(
+    feature: &RawFeature,
+    component: &mut Component,
+    sequence: &Sequence,
+) -> Result<(), SbolError> {
+    let instance_of = feature
+        .qualifier(SBOL_INSTANCE_OF)
+        .map(|uri| Url::parse(&unquote(uri)))
+        .transpose()?;
+    let display_id = match (feature.qualifier(SBOL_DISPLAY_ID), &instance_of) {
+        (Some(display_id), _) => unquote(display_id),
+        (None, Some(_)) => child_display_id("SubComponent", component.children()),
+        (None, None) => child_display_id("SequenceFeature", component.children()),
+    };
+
+    let mut sequence_feature = SequenceFeature::new(&component.identity, &display_id)?;
+    let roles: Vec<ComponentRole> = feature
+        .qualifiers
+        .iter()
+        .filter(|(name, _)| name == SBOL_ROLE)
+        .filter_map(|(_, value)| value.as_deref())
+        .map(|role| unquote(role).parse())
+        .collect::<Result<_, _>>()?;
+    sequence_feature.role = if roles.is_empty() {
+        vec![feature_role(&feature.key)?]
+    } else {
+        roles
+    };
+    sequence_feature.orientation = feature
+        .qualifier(SBOL_ORIENTATION)
+        .map(|orientation| unquote(orientation).parse())
+        .transpose()?;
+    sequence_feature.name = ["label", "gene", "product", "locus_tag"]
+        .iter()
+        .find_map(|name| feature.qualifier(name))
+        .map(unquote);
+    sequence_feature.description = feature.qualifier("note").map(unquote);
+
+    let segments = parse_location(&feature.location)
+        .map_err(|msg| parse_error(feature.line, format!("{msg} in {}", feature.location)))?;
+    let ordered = segments.len() > 1 && feature.location.contains("join(");
+    let mut ranges = 0;
+    let mut cuts = 0;
+    for (i, segment) in segments.into_iter().enumerate() {
+        let mut location = if segment.cut {
+            cuts += 1;
+            Location::Cut(Cut::new(
+                &sequence_feature.identity,
+                &format!("Cut{cuts}"),
+                sequence.identity.clone(),
+                segment.start,
+            )?)
+        } else {
+            ranges += 1;
+            Location::Range(Range::new(
+                &sequence_feature.identity,
+                &format!("Range{ranges}"),
+                sequence.identity.clone(),
+                segment.start,
+                segment.end,
+            )?)
+        };
+        let order = ordered.then_some(i as i64 + 1);
+        match &mut location {
+            Location::Range(range) => {
+                range.orientation = Some(segment.orientation);
+                range.order = order;
+            }
+            Location::Cut(cut) => {
+                cut.orientation = Some(segment.orientation);
+                cut.order = order;
+            }
+            Location::EntireSequence(_) => (),
+        }
+        sequence_feature.has_location.push(location);
+    }
+
+    match instance_of {
+        Some(instance_of) => {
+            let mut sub_component =
+                SubComponent::new(&component.identity, &display_id, instance_of)?;
+            sub_component.name = sequence_feature.name;
+            sub_component.description = sequence_feature.description;
+            sub_component.role = sequence_feature.role;
+            sub_component.orientation = sequence_feature.orientation;
+            sub_component.has_location = sequence_feature.has_location;
+            component.sub_components.push(sub_component);
+        }
+        None => component.sequence_features.push(sequence_feature),
+    }
+    Ok(())
+}
+
+/// Contiguous part of a GenBank location
+#[derive(Debug, PartialEq)]
+struct Segment {
+    start: usize,
+    end: usize,
+    orientation: Orientation,
+    /// Site between two bases (`start^end`), which is represented by a `Cut` after `start`
+    cut: bool,
+}
+
+/// Parse a GenBank location, ie: `complement(join(1..10,20..>30))`.
+///
+/// Segments are returned in biological order: the segments of a complemented join are reversed.
+/// Partial-location markers (`<`, `>`) are ignored. References to other records are rejected.
+fn parse_location(location: &str) -> Result<Vec<Segment>, String> {
+    let location: String = location.chars().filter(|c| !c.is_whitespace()).collect();
+    let location = location.as_str();
+
+    for operator in ["complement", "join", "order"] {
+        if let Some(inner) = location
+            .strip_prefix(operator)
+            .and_then(|l| l.strip_prefix('('))
+            .and_then(|l| l.strip_suffix(')'))
+        {
+            if operator == "complement" {
+                let mut segments = parse_location(inner)?;
+                segments.reverse();
+                for segment in segments.iter_mut() {
+                    segment.orientation = match segment.orientation {
+                        Orientation::ReverseComplement => Orientation::Inline,
+                        _ => Orientation::ReverseComplement,
+                    };
+                }
+                return Ok(segments);
+            }
+            let mut segments = vec![];
+            for part in split_top_level(inner)? {
+                segments.extend(parse_location(part)?);
+            }
+            return Ok(segments);
+        }
+    }
+
+    if location.contains(':') {
+        return Err("Unsupported reference to another record".to_string());
+    }
+    let position = |p: &str| {
+        p.trim_start_matches(['<', '>'])
+            .parse::<usize>()
+            .map_err(|_| format!("Invalid position '{p}'"))
+    };
+    let segment = |start, end, cut| Segment {
+        start,
+        end,
+        orientation: Orientation::Inline,
+        cut,
+    };
+    if let Some((start, end)) = location.split_once("..") {
+        Ok(vec![segment(position(start)?, position(end)?, false)])
+    } else if let Some((before, after)) = location.split_once('^') {
+        Ok(vec![segment(position(before)?, position(after)?, true)])
+    } else {
+        let base = position(location)?;
+        Ok(vec![segment(base, base, false)])
+    }
+}
+
+/// Split the arguments of a location operator on commas which are not nested
+fn split_top_level(arguments: &str) -> Result<Vec<&str>, String> {
+    let mut parts = vec![];
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (i, c) in arguments.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth = depth
+                    .checked_sub(1)
+                    .ok_or_else(|| "Unbalanced parentheses".to_string())?
+            }
+            ',' if depth == 0 => {
+                parts.push(&arguments[start..i]);
+                start = i + 1;
+            }
+            _ => (),
+        }
+    }
+    parts.push(&arguments[start..]);
+    Ok(parts)
+}
+
+/// Write every `Component` of `document` that has a nucleic acid `Sequence` as a GenBank record.
+pub fn write<W: Write>(document: &Document, mut writer: W) -> Result<(), SbolError> {
+    writer.write_all(to_string(document)?.as_bytes())?;
+    Ok(())
+}
+
+/// Serialize every `Component` of `document` that has a nucleic acid `Sequence` as GenBank.
+pub fn to_string(document: &Document) -> Result<String, SbolError> {
+    let mut output = String::new();
+    for component in &document.components {
+        if document.nucleic_acid_sequence(component).is_some() {
+            output.push_str(&serialize_record(component, document)?);
+        }
+    }
+    Ok(output)
+}
+
+/// Serialize `component` and its nucleic acid `Sequence` as a single GenBank record.
+///
+/// `SubComponent` and `SequenceFeature` objects with a location are exported together, in order of
+/// their first position on the `Sequence`. The LOCUS line holds the sanitized `display_id`, which
+/// must not be longer than 16 characters, and the current date.
+pub fn serialize_record(component: &Component, document: &Document) -> Result<String, SbolError> {
+    let sequence = document.nucleic_acid_sequence(component).ok_or_else(|| {
+        SbolError::Validation(format!(
+            "{} has no nucleic acid sequence",
+            component.identity
+        ))
+    })?;
+    let elements = sequence.nucleic_acid_elements()?;
+    let locus_name = sanitize_display_id(&component.display_id.clone().unwrap_or_default());
+    if locus_name.chars().count() > LOCUS_NAME_WIDTH {
+        return Err(SbolError::Validation(format!(
+            "{} cannot be exported as GenBank, its LOCUS name {locus_name} is longer than \
+             {LOCUS_NAME_WIDTH} characters",
+            component.identity
+        )));
+    }
+
+    let topology = |topology| {
+        component
+            .r#type
+            .contains(&ComponentType::Topology(topology))
+    };
+    let rna = component
+        .r#type
+        .contains(&ComponentType::Type(ComponentTypeOntology::RNA));
+    let strand = match (
+        topology(TopologyOntology::SingleStranded),
+        topology(TopologyOntology::DoubleStranded),
+    ) {
+        (true, _) => "ss-",
+        (_, true) => "ds-",
+        _ => "",
+    };
+    let molecule = if rna { "RNA" } else { "DNA" };
+    let circular = topology(TopologyOntology::Circular);
+
+    let mut record = format!(
+        "LOCUS       {locus_name:<LOCUS_NAME_WIDTH$} {:>11} bp {strand:<3}{molecule:<6}  {:<8} SYN {}\n",
+        elements.len(),
+        if circular { "circular" } else { "linear" },
+        today(),
+    );
+    let definition = component
+        .description
+        .clone()
+        .or_else(|| component.name.clone())
+        .unwrap_or_else(|| ".".to_string());
+    record.push_str(&header("DEFINITION", &definition));
+    record.push_str(&header("ACCESSION", &locus_name));
+    record.push_str(&header("KEYWORDS", "."));
+
+    let implied_types = component_types(
+        false,
+        Some(&format!("{strand}{molecule}")),
+        Some(if circular { "circular" } else { "linear" }),
+    );
+    let mut comments = vec![];
+    if component.name.is_some() && component.name.as_ref() != Some(&locus_name) {
+        comments.push(format!(
+            "{SBOL_NAME}: {}",
+            component.name.clone().unwrap_or_default()
+        ));
+    }
+    for role in &component.role {
+        comments.push(format!("{SBOL_ROLE}: {}", role.uri()?));
+    }
+    for r#type in component
+        .r#type
+        .iter()
+        .filter(|t| !implied_types.contains(t))
+    {
+        let uri = match r#type {
+            ComponentType::Type(r#type) => r#type.uri()?,
+            ComponentType::Topology(topology) => topology.uri()?,
+        };
+        comments.push(format!("{SBOL_TYPE}: {uri}"));
+    }
+    for (i, comment) in comments.iter().enumerate() {
+        match i {
+            0 => record.push_str(&format!("{:<HEADER_INDENT$}{comment}\n", "COMMENT")),
+            _ => record.push_str(&format!("{:HEADER_INDENT$}{comment}\n", "")),
+        }
+    }
+
+    record.push_str("FEATURES             Location/Qualifiers\n");
+    let mut features: Vec<(&dyn Feature, &Vec<Location>, Option<&Url>)> = component
+        .sub_components
+        .iter()
+        .map(|f| (f as &dyn Feature, &f.has_location, Some(&f.instance_of)))
+        .chain(
+            component
+                .sequence_features
+                .iter()
+                .map(|f| (f as &dyn Feature, &f.has_location, None)),
+        )
+        .filter(|(_, locations, _)| !locations.is_empty())
+        .collect();
+    features.sort_by_key(|(_, locations, _)| {
+        locations
+            .iter()
+            .map(|location| match location {
+                Location::Range(range) => range.start,
+                Location::Cut(cut) => cut.at,
+                Location::EntireSequence(_) => 1,
+            })
+            .min()
+    });
+    for (feature, locations, instance_of) in features {
+        record.push_str(&serialize_feature(
+            feature,
+            locations,
+            instance_of,
+            elements.len(),
+        )?);
+    }
+
+    record.push_str("ORIGIN\n");
+    let chars: Vec<char> = elements.chars().collect();
+    for (i, line) in chars.chunks(60).enumerate() {
+        record.push_str(&format!("{:>9}", i * 60 + 1));
+        for block in line.chunks(10) {
+            record.push(' ');
+            record.extend(block.iter().map(|c| c.to_ascii_lowercase()));
+        }
+        record.push('\n');
+    }
+    record.push_str("//\n");
+    Ok(record)
+}
+
+/// Current UTC date, ie: `29-FEB-2024`
+fn today() -> String {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default();
+    date(seconds / 86_400)
+}
+
+/// Date which is `days` after 1970-01-01, ie: `01-JAN-1970`.
+///
+/// Converts days into a proleptic Gregorian date, counting 400 year eras of 146097 days from
+/// 0000-03-01 so that leap days fall at the end of each year.
+fn date(days: u64) -> String {
+    let days = days + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month + 2) / 5 + 1;
+    let month = if month < 10 { month + 2 } else { month - 10 };
+    let year = era * 400 + year_of_era + u64::from(month < 2);
+    format!("{day:02}-{}-{year}", MONTHS[month as usize])
+}
+
+/// Header keyword followed by its value, wrapped on whitespace
+fn header(keyword: &str, value: &str) -> String {
+    wrap(value, HEADER_INDENT, true)
+        .iter()
+        .enumerate()
+        .map(|(i, line)| match i {
+            0 => format!("{keyword:<HEADER_INDENT$}{line}\n"),
+            _ => format!("{:HEADER_INDENT$}{line}\n", ""),
+        })
+        .collect()
+}
+
+/// Split `text` into lines which fit after `indent`.
+///
+/// When `spaced`, lines end between words, so that they are joined again with a space on import.
+/// A word which is longer than a line is then kept whole. Otherwise the text has no whitespace,
+/// like a location or a URI, and is split anywhere.
+fn wrap(text: &str, indent: usize, spaced: bool) -> Vec<String> {
+    let width = LINE_WIDTH - indent;
+    if !spaced && !text.is_empty() {
+        let chars: Vec<char> = text.chars().collect();
+        return chars
+            .chunks(width)
+            .map(|line| line.iter().collect())
+            .collect();
+    }
+    let mut lines = vec![];
+    let mut line = String::new();
+    for word in text.split(' ') {
+        if !line.is_empty() && line.chars().count() + word.chars().count() + 1 > width {
+            lines.push(std::mem::take(&mut line));
+        } else if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    if !line.is_empty() || lines.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+/// Whether the values of qualifier `name` have no whitespace, so that their lines are joined
+/// without one
+fn unspaced(name: &str) -> bool {
+    name == "translation" || name.starts_with("sbol_")
+}
+
+/// Serialize a `Feature` as an entry of the feature table
+fn serialize_feature(
+    feature: &dyn Feature,
+    locations: &[Location],
+    instance_of: Option<&Url>,
+    length: usize,
+) -> Result<String, SbolError> {
+    let key = feature
+        .role()
+        .iter()
+        .find_map(feature_key)
+        .unwrap_or(DEFAULT_FEATURE_KEY);
+
+    let mut entry = String::new();
+    let location = serialize_location(locations, length);
+    for (i, line) in wrap(&location, FEATURE_INDENT, false).iter().enumerate() {
+        match i {
+            0 => entry.push_str(&format!("     {key:<15} {line}\n")),
+            _ => entry.push_str(&format!("{:FEATURE_INDENT$}{line}\n", "")),
+        }
+    }
+
+    let mut qualifiers = vec![];
+    if let Some(name) = feature.name() {
+        qualifiers.push(("label", name));
+    }
+    if let Some(description) = feature.description() {
+        qualifiers.push(("note", description));
+    }
+    if let Some(display_id) = feature.display_id() {
+        qualifiers.push((SBOL_DISPLAY_ID, display_id));
+    }
+    if let Some(instance_of) = instance_of {
+        qualifiers.push((SBOL_INSTANCE_OF, instance_of.to_string()));
+    }
+    if let Some(orientation) = feature.orientation() {
+        qualifiers.push((SBOL_ORIENTATION, orientation.uri()?.to_string()));
+    }
+    // Roles are only implied by the key if it was derived from the single role of the feature
+    let implied = feature.role().len() == 1 && feature_key(&feature.role()[0]) == Some(key);
+    if !implied {
+        for role in feature.role() {
+            qualifiers.push((SBOL_ROLE, role.uri()?.to_string()));
+        }
+    }
+
+    for (name, value) in qualifiers {
+        let qualifier = format!("/{name}=\"{}\"", value.replace('"', "\"\""));
+        for line in wrap(&qualifier, FEATURE_INDENT, !unspaced(name)) {
+            entry.push_str(&format!("{:FEATURE_INDENT$}{line}\n", ""));
+        }
+    }
+    Ok(entry)
+}
+
+/// Serialize `locations` as a GenBank location, ie: `complement(join(1..10,20..30))`
+fn serialize_location(locations: &[Location], length: usize) -> String {
+    let mut locations: Vec<&Location> = locations.iter().collect();
+    locations.sort_by_key(|location| location.order());
+
+    let reverse = |location: &Location| {
+        matches!(
+            location.orientation(),
+            Some(Orientation::ReverseComplement | Orientation::ReverseComplementAlt)
+        )
+    };
+    let position = |location: &Location| match location {
+        Location::Range(range) if range.start == range.end => range.start.to_string(),
+        Location::Range(range) => format!("{}..{}", range.start, range.end),
+        Location::Cut(cut) => format!("{}^{}", cut.at, cut.at + 1),
+        Location::EntireSequence(_) => format!("1..{length}"),
+    };
+
+    if locations.len() > 1 && locations.iter().all(|l| reverse(l)) {
+        let parts: Vec<String> = locations.iter().rev().map(|l| position(l)).collect();
+        return format!("complement(join({}))", parts.join(","));
+    }
+    let parts: Vec<String> = locations
+        .iter()
+        .map(|l| {
+            if reverse(l) {
+                format!("complement({})", position(l))
+            } else {
+                position(l)
+            }
+        })
+        .collect();
+    match parts.len() {
+        1 => parts[0].clone(),
+        _ => format!("join({})", parts.join(",")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use url::Url;
+
+    use crate::ontologies::{ComponentRole, ComponentTypeOntology, Orientation, TopologyOntology};
+    use crate::{ComponentType, Location};
+
+    use super::*;
+    use crate::test_utils::uri;
+
+    pub(crate) const PLASMID: &str = r#"LOCUS       pTest_1                   60 bp    DNA     circular SYN 01-JAN-2020
+DEFINITION  Test plasmid with a promoter
+            and a reverse CDS.
+ACCESSION   .
+FEATURES             Location/Qualifiers
+     source          1..60
+                     /organism="synthetic DNA construct"
+     promoter        1..10
+                     /label="pTest"
+                     /note="constitutive promoter, from the ""Anderson""
+                     collection"
+     CDS             complement(join(20..30,41..50))
+                     /gene="testA"
+                     /translation="MKT
+                     AYIA"
+     misc_feature    55^56
+ORIGIN
+        1 ttgacagcta gctcagtcct aggtataatg ctagcatgca tgcatgcatg catgcatgca
+//
+"#;
+
+    fn namespace() -> Url {
+        uri("https://test.org/lab")
+    }
+
+    #[test]
+    fn test_parse_record() {
+        let document = parse(PLASMID, &namespace()).unwrap();
+        let component = &document.components[0];
+        assert_eq!(component.identity.as_str(), "https://test.org/lab/pTest_1");
+        assert_eq!(
+            component.description.as_deref(),
+            Some("Test plasmid with a promoter and a reverse CDS.")
+        );
+        assert_eq!(
+            component.r#type,
+            vec![
+                ComponentType::Type(ComponentTypeOntology::DNA),
+                ComponentType::Topology(TopologyOntology::Circular),
+            ]
+        );
+
+        let sequence = &document.sequences[0];
+        assert_eq!(component.has_sequence, vec![sequence.identity.clone()]);
+        assert_eq!(sequence.elements.as_ref().unwrap().len(), 60);
+        assert!(sequence
+            .elements
+            .as_ref()
+            .unwrap()
+            .starts_with("ttgacagcta"));
+        assert_eq!(sequence.encoding, Some(Encoding::NucleicAcid));
+    }
+
+    #[test]
+    fn test_parse_features() {
+        let document = parse(PLASMID, &namespace()).unwrap();
+        let features = &document.components[0].sequence_features;
+        assert_eq!(features.len(), 3);
+
+        let promoter = &features[0];
+        assert_eq!(promoter.role, vec![ComponentRole::Promoter]);
+        assert_eq!(promoter.name.as_deref(), Some("pTest"));
+        assert_eq!(
+            promoter.description.as_deref(),
+            Some("constitutive promoter, from the \"Anderson\" collection")
+        );
+        match &promoter.has_location[..] {
+            [Location::Range(range)] => assert_eq!((range.start, range.end), (1, 10)),
+            _ => panic!("Expected a single range"),
+        }
+
+        let cds = &features[1];
+        assert_eq!(cds.role, vec![ComponentRole::CDS]);
+        assert_eq!(cds.name.as_deref(), Some("testA"));
+        let ranges: Vec<_> = cds
+            .has_location
+            .iter()
+            .map(|location| match location {
+                Location::Range(r) => (r.start, r.end, r.orientation.clone(), r.order),
+                _ => panic!("Expected ranges"),
+            })
+            .collect();
+        assert_eq!(
+            ranges,
+            vec![
+                (41, 50, Some(Orientation::ReverseComplement), Some(1)),
+                (20, 30, Some(Orientation::ReverseComplement), Some(2)),
+            ]
+        );
+
+        match &features[2].has_location[..] {
+            [Location::Cut(cut)] => assert_eq!(cut.at, 55),
+            _ => panic!("Expected a cut"),
+        }
+    }
+
+    #[test]
+    fn test_generated_display_ids() {
+        let input = PLASMID.replace(
+            "/label=\"pTest\"",
+            "/label=\"pTest\"\n                     /sbol_display_id=\"SequenceFeature2\"",
+        );
+        let document = parse(&input, &namespace()).unwrap();
+        let display_ids: Vec<_> = document.components[0]
+            .sequence_features
+            .iter()
+            .filter_map(|feature| feature.display_id.as_deref())
+            .collect();
+        assert_eq!(
+            display_ids,
+            ["SequenceFeature2", "SequenceFeature1", "SequenceFeature3"]
+        );
+    }
+
+    #[test]
+    fn test_parse_location() {
+        assert_eq!(
+            parse_location("join(complement(5..10),<1..>3)").unwrap(),
+            vec![
+                Segment {
+                    start: 5,
+                    end: 10,
+                    orientation: Orientation::ReverseComplement,
+                    cut: false
+                },
+                Segment {
+                    start: 1,
+                    end: 3,
+                    orientation: Orientation::Inline,
+                    cut: false
+                },
+            ]
+        );
+        assert!(parse_location("J00194.1:100..202").is_err());
+        assert!(parse_location("1..x").is_err());
+    }
+
+    #[test]
+    fn test_feature_roles() {
+        assert_eq!(feature_role("promoter").unwrap(), ComponentRole::Promoter);
+        assert_eq!(
+            feature_role("unknown_key").unwrap(),
+            feature_role("misc_feature").unwrap()
+        );
+        assert_eq!(feature_key(&ComponentRole::Terminator), Some("terminator"));
+        assert_eq!(feature_key(&ComponentRole::Effector), None);
+    }
+
+    #[test]
+    fn test_multiple_records() {
+        let input = format!("{PLASMID}{}", PLASMID.replace("pTest_1", "pTest_2"));
+        let document = parse(&input, &namespace()).unwrap();
+        assert_eq!(document.components.len(), 2);
+        assert_eq!(document.sequences.len(), 2);
+    }
+
+    #[test]
+    fn test_duplicate_locus_names() {
+        let input = format!("{PLASMID}{PLASMID}");
+        let error = parse(&input, &namespace()).unwrap_err().to_string();
+        assert!(error.contains("line 20: Duplicate LOCUS name pTest_1"));
+    }
+
+    #[test]
+    fn test_export_order_and_wrapping() {
+        let mut document = parse(PLASMID, &namespace()).unwrap();
+        let component = &mut document.components[0];
+        let long_word = "ACGT".repeat(40);
+        component.description = Some(format!("Plasmid {long_word} with a long word"));
+        let promoter = &mut component.sequence_features[0];
+        promoter.description = Some(format!("Promoter {long_word} {long_word}"));
+
+        // The CDS is exported between the promoter and the cut site
+        let cds = component.sequence_features.remove(1);
+        let mut sub_component = SubComponent::new(
+            &component.identity,
+            "CDS",
+            uri("https://test.org/lab/testA"),
+        )
+        .unwrap();
+        sub_component.role = cds.role;
+        sub_component.has_location = cds.has_location;
+        component.sub_components.push(sub_component);
+
+        let output = to_string(&document).unwrap();
+        let position = |key: &str| output.find(&format!("     {key:<16}")).unwrap();
+        assert!(position("promoter") < position("CDS"));
+        assert!(position("CDS") < position("misc_feature"));
+        // Only the lines holding a word which is longer than a line exceed the width
+        assert!(output
+            .lines()
+            .filter(|line| !line.contains(&long_word))
+            .all(|line| line.len() <= LINE_WIDTH));
+
+        let restored = parse(&output, &namespace()).unwrap();
+        let component = &restored.components[0];
+        assert_eq!(component.description, document.components[0].description);
+        assert_eq!(
+            component.sequence_features[0].description,
+            document.components[0].sequence_features[0].description
+        );
+    }
+
+    #[test]
+    fn test_full_width_lines() {
+        // Lines which fill the width, as written by NCBI, end between two words
+        let definition = format!("{} {}", "a".repeat(30), "b".repeat(36));
+        let word = "n".repeat(38);
+        let input = PLASMID
+            .replace("Test plasmid with a promoter", &definition)
+            .replace("constitutive promoter, from the", &word);
+        assert!(input.lines().any(|line| line.len() == LINE_WIDTH));
+        let document = parse(&input, &namespace()).unwrap();
+        let component = &document.components[0];
+        assert_eq!(
+            component.description,
+            Some(format!("{definition} and a reverse CDS."))
+        );
+        assert_eq!(
+            component.sequence_features[0].description,
+            Some(format!("{word} \"Anderson\" collection"))
+        );
+
+        // A word which fills the rest of a line is followed by a space on the next one
+        let mut document = document;
+        let description = format!("{} {} end", "a".repeat(10), "b".repeat(56));
+        document.components[0].description = Some(description.clone());
+        let output = to_string(&document).unwrap();
+        let restored = parse(&output, &namespace()).unwrap();
+        assert_eq!(restored.components[0].description, Some(description));
+    }
+
+    #[test]
+    fn test_serialize_record() {
+        let document = parse(PLASMID, &namespace()).unwrap();
+        let output = to_string(&document).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(
+            lines[0],
+            format!(
+                "LOCUS       pTest_1                   60 bp    DNA     circular SYN {}",
+                today()
+            )
+        );
+        assert!(output.contains("     CDS             complement(join(20..30,41..50))\n"));
+        assert!(output.contains("     misc_feature    55^56\n"));
+        assert!(output.contains("                     /label=\"pTest\"\n"));
+        assert!(output.contains(
+            "        1 ttgacagcta gctcagtcct aggtataatg ctagcatgca tgcatgcatg catgcatgca\n"
+        ));
+        assert!(output.ends_with("//\n"));
+    }
+
+    #[test]
+    fn test_locus_line() {
+        assert_eq!(date(0), "01-JAN-1970");
+        assert_eq!(date(10_956), "31-DEC-1999");
+        assert_eq!(date(11_017), "01-MAR-2000");
+        assert_eq!(date(19_782), "29-FEB-2024");
+
+        // Longer names would shift the fixed columns of the LOCUS line
+        let mut document = parse(PLASMID, &namespace()).unwrap();
+        document.components[0].display_id = Some("pTest_1_with_a_long_name".to_string());
+        let error = to_string(&document).unwrap_err().to_string();
+        assert!(error.contains("longer than 16 characters"));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut document = parse(PLASMID, &namespace()).unwrap();
+        {
+            let component = &mut document.components[0];
+            component.name = Some("Test plasmid".to_string());
+            component.role = vec![ComponentRole::EngineeredRegion];
+            component
+                .r#type
+                .push(ComponentType::Topology(TopologyOntology::DoubleStranded));
+
+            // Feature with a role that has no GenBank key, linked to a part
+            let mut feature = component.sequence_features.remove(0);
+            feature.role = vec![ComponentRole::Effector, ComponentRole::Promoter];
+            let mut sub_component = SubComponent::new(
+                &component.identity,
+                "SubComponent1",
+                uri("https://test.org/lab/pTest"),
+            )
+            .unwrap();
+            sub_component.role = feature.role;
+            sub_component.name = feature.name;
+            sub_component.description = feature.description;
+            sub_component.orientation = Some(Orientation::Inline);
+            sub_component.has_location = feature
+                .has_location
+                .into_iter()
+                .map(|location| match location {
+                    Location::Range(mut range) => {
+                        range.identity =
+                            Url::parse(&format!("{}/Range1", sub_component.identity)).unwrap();
+                        Location::Range(range)
+                    }
+                    location => location,
+                })
+                .collect();
+            component.sub_components.push(sub_component);
+        }
+
+        let output = to_string(&document).unwrap();
+        let restored = parse(&output, &namespace()).unwrap();
+        assert_eq!(restored, document);
+    }
+}
@@ -0,0 +1,743 @@
+//! Conversion between GFF3 feature annotations and SBOL.
+//!
+//! Each GFF3 line becomes a `Range` of a `SequenceFeature`, which is attached to the existing
+//! `Component` whose `display_id` matches the seqid column. Lines sharing an `ID` attribute (ie:
+//! the exons of a CDS) become the `Range` objects of a single `SequenceFeature`, ordered as they
+//! are read along their strand. The type column is mapped to a role through the terms of the
+//! Sequence Ontology.
+//!
+//! The `Parent` attribute of a line becomes a [`PART_OF`] annotation of its feature, whose value is
+//! the URI of the parent feature.
+//!
+//! Export performs the reverse conversion. Roles which have no Sequence Ontology name and the
+//! `instance_of` property of a `SubComponent` are written to `sbol_*` attributes, which are
+//! restored on import.
+
+use std::collections::HashMap;
+use std::io::{Read, Write};
+
+use url::Url;
+
+use crate::ontologies::{so_accession, so_name, so_role, ComponentRole, Ontology, Orientation};
+use crate::{
+    sanitize_display_id, Annotation, AnnotationValue, Component, Document, Feature, Location,
+    Range, SbolError, SequenceFeature, SubComponent,
+};
+
+/// Type of features whose roles have no Sequence Ontology term
+const DEFAULT_TYPE: &str = "sequence_feature";
+
+/// Value of the source column of exported features
+const SOURCE: &str = "SBOL";
+
+/// Characters which are percent-encoded in columns and attribute values
+const RESERVED: &str = "\t\n\r%;=&,";
+
+/// Attribute holding the `instance_of` URI of a `SubComponent`
+const SBOL_INSTANCE_OF: &str = "sbol_instance_of";
+
+/// Attribute holding role URIs which are not implied by the type column
+const SBOL_ROLE: &str = "sbol_role";
+
+/// Property of the annotation which links a feature to its `Parent` feature: the `part_of`
+/// relation, which is the meaning of the attribute in GFF3
+pub const PART_OF: &str = "http://purl.obolibrary.org/obo/BFO_0000050";
+
+/// Read the GFF3 annotations of `reader` into the `Component` objects of `document`.
+///
+/// See [`parse`].
+pub fn read<R: Read>(mut reader: R, document: &mut Document) -> Result<(), SbolError> {
+    let mut input = String::new();
+    reader.read_to_string(&mut input)?;
+    parse(&input, document)
+}
+
+/// Parse the GFF3 annotations of `input` into the `Component` objects of `document`.
+///
+/// The seqid of each line must match the (sanitized) `display_id` of a `Component` with a nucleic
+/// acid `Sequence`, which the created `Range` objects refer to. Unknown types become a
+/// `sequence_feature`. The `Name` and `Note` attributes become the `name` and `description` of the
+/// feature. The `Parent` attribute must be the `ID` of a line of the same seqid. Directives,
+/// comments and the `##FASTA` section are ignored.
+///
+/// `document` is left unchanged if any line cannot be parsed.
+pub fn parse(input: &str, document: &mut Document) -> Result<(), SbolError> {
+    let mut parsed = document.clone();
+    // Display ids of the features created for each (Component index, ID attribute)
+    let mut features = HashMap::new();
+    // Line number, Component index, feature display id and Parent attribute of each child
+    let mut children = vec![];
+    for (i, line) in input.lines().enumerate() {
+        let line = line.trim_end_matches(['\r', '\n']);
+        if line.starts_with("##FASTA") {
+            break;
+        } else if line.starts_with('#') || line.trim().is_empty() {
+            continue;
+        }
+        let line = parse_line(i + 1, line)?;
+        let (index, display_id) = add_line(&line, &mut parsed, &mut features)?;
+        for parent in line.attribute("Parent").unwrap_or_default() {
+            children.push((line.number, index, display_id.clone(), parent.clone()));
+        }
+    }
+
+    let part_of = Url::parse(PART_OF)?;
+    for (number, index, display_id, parent) in children {
+        let component = &mut parsed.components[index];
+        let parent = features
+            .get(&(index, parent.clone()))
+            .and_then(|parent| feature_locations(component, parent))
+            .map(|(parent, _)| parent)
+            .ok_or_else(|| parse_error(number, format!("No feature with ID {parent}")))?;
+        let annotation = Annotation::uri(part_of.clone(), parent);
+        let annotations = component
+            .sub_components
+            .iter_mut()
+            .find(|f| f.display_id.as_deref() == Some(display_id.as_str()))
+            .map(|f| &mut f.annotations)
+            .or_else(|| {
+                component
+                    .sequence_features
+                    .iter_mut()
+                    .find(|f| f.display_id.as_deref() == Some(display_id.as_str()))
+                    .map(|f| &mut f.annotations)
+            })
+            .ok_or_else(|| parse_error(number, format!("Missing feature {display_id}")))?;
+        if !annotations.contains(&annotation) {
+            annotations.push(annotation);
+        }
+    }
+    *document = parsed;
+    Ok(())
+}
+
+fn parse_error(line: usize, msg: impl std::fmt::Display) -> SbolError {
+    SbolError::Parse(format!("GFF3 line {line}: {msg}"))
+}
+
+/// Feature line, prior to conversion
+struct Line {
+    number: usize,
+    seqid: String,
+    feature_type: String,
+    start: usize,
+    end: usize,
+    strand: Option<Orientation>,
+    attributes: Vec<(String, Vec<String>)>,
+}
+
+impl Line {
+    fn attribute(&self, tag: &str) -> Option<&[String]> {
+        self.attributes
+            .iter()
+            .find(|(t, _)| t == tag)
+            .map(|(_, values)| values.as_slice())
+    }
+}
+
+fn parse_line(number: usize, line: &str) -> Result<Line, SbolError> {
+    let columns: Vec<&str> = line.split('\t').collect();
+    if columns.len() != 9 {
+        return Err(parse_error(
+            number,
+            format!("Expected 9 tab separated columns, found {}", columns.len()),
+        ));
+    }
+    let position = |column: &str| {
+        column
+            .parse::<usize>()
+            .ok()
+            .filter(|position| *position > 0)
+            .ok_or_else(|| parse_error(number, format!("Invalid position {column:?}")))
+    };
+    let (start, end) = (position(columns[3])?, position(columns[4])?);
+    if start > end {
+        return Err(parse_error(
+            number,
+            format!("Start {start} is after end {end}"),
+        ));
+    }
+    let strand = match columns[6] {
+        "+" => Some(Orientation::Inline),
+        "-" => Some(Orientation::ReverseComplement),
+        "." | "?" => None,
+        strand => return Err(parse_error(number, format!("Invalid strand {strand:?}"))),
+    };
+
+    let mut attributes = vec![];
+    if columns[8] != "." {
+        for attribute in columns[8].split(';').filter(|a| !a.trim().is_empty()) {
+            let (tag, values) = attribute
+                .split_once('=')
+                .ok_or_else(|| parse_error(number, format!("Invalid attribute {attribute:?}")))?;
+            let values = values.split(',').map(decode).collect();
+            attributes.push((decode(tag.trim()), values));
+        }
+    }
+
+    Ok(Line {
+        number,
+        seqid: decode(columns[0]),
+        feature_type: decode(columns[2]),
+        start,
+        end,
+        strand,
+        attributes,
+    })
+}
+
+/// Add the `Range` described by `line` to its feature, which is created unless `features` already
+/// holds a feature with the same `ID` attribute. Returns the index of the `Component` and the
+/// `display_id` of the feature.
+fn add_line(
+    line: &Line,
+    document: &mut Document,
+    features: &mut HashMap<(usize, String), String>,
+) -> Result<(usize, String), SbolError> {
+    let index = document
+        .components
+        .iter()
+        .position(|c| c.display_id.as_deref() == Some(line.seqid.as_str()))
+        .or_else(|| {
+            let display_id = sanitize_display_id(&line.seqid);
+            document
+                .components
+                .iter()
+                .position(|c| c.display_id.as_deref() == Some(display_id.as_str()))
+        })
+        .ok_or_else(|| {
+            parse_error(
+                line.number,
+                format!("No Component with displayId {}", line.seqid),
+            )
+        })?;
+    let sequence = document
+        .nucleic_acid_sequence(&document.components[index])
+        .ok_or_else(|| {
+            parse_error(
+                line.number,
+                format!("Component {} has no nucleic acid Sequence", line.seqid),
+            )
+        })?;
+    let length = sequence.nucleic_acid_elements()?.chars().count();
+    if line.end > length {
+        return Err(parse_error(
+            line.number,
+            format!("End {} is beyond the sequence length {length}", line.end),
+        ));
+    }
+    let sequence = sequence.identity.clone();
+    let component = &mut document.components[index];
+
+    let id = line.attribute("ID").and_then(|id| id.first());
+    let existing = id.and_then(|id| features.get(&(index, id.clone())));
+    let display_id = match existing {
+        Some(display_id) => display_id.clone(),
+        None => {
+            let display_id = add_feature(line, component)?;
+            if let Some(id) = id {
+                features.insert((index, id.clone()), display_id.clone());
+            }
+            display_id
+        }
+    };
+
+    let (parent, locations) = feature_locations(component, &display_id)
+        .ok_or_else(|| parse_error(line.number, format!("Missing feature {display_id}")))?;
+    let mut range = Range::new(
+        &parent,
+        &format!("Range{}", locations.len() + 1),
+        sequence,
+        line.start,
+        line.end,
+    )?;
+    range.orientation = line.strand.clone();
+    locations.push(Location::Range(range));
+    if locations.len() > 1 {
+        // Segments are numbered in the order they are read, as GenBank locations are: lines list
+        // segments by ascending start, so those on the reverse strand are read backwards
+        let reverse = locations.iter().all(|location| {
+            matches!(
+                location.orientation(),
+                Some(Orientation::ReverseComplement | Orientation::ReverseComplementAlt)
+            )
+        });
+        let mut ranges: Vec<&mut Range> = locations
+            .iter_mut()
+            .filter_map(|location| match location {
+                Location::Range(range) => Some(range),
+                _ => None,
+            })
+            .collect();
+        if reverse {
+            ranges.sort_by_key(|range| std::cmp::Reverse(range.start));
+        }
+        for (i, range) in ranges.into_iter().enumerate() {
+            range.order = Some(i as i64 + 1);
+        }
+    }
+    Ok((index, display_id))
+}
+
+/// Create the feature described by `line`, without locations, and return its `display_id`.
+///
+/// The feature is a `SubComponent` when the line was exported from one, otherwise a
+/// `SequenceFeature`.
+fn add_feature(line: &Line, component: &mut Component) -> Result<String, SbolError> {
+    let instance_of = line
+        .attribute(SBOL_INSTANCE_OF)
+        .and_then(|values| values.first())
+        .map(|uri| Url::parse(uri))
+        .transpose()?;
+    let base = match (line.attribute("ID").and_then(|id| id.first()), &instance_of) {
+        (Some(id), _) => sanitize_display_id(id),
+        (None, Some(_)) => format!("SubComponent{}", component.sub_components.len() + 1),
+        (None, None) => format!("SequenceFeature{}", component.sequence_features.len() + 1),
+    };
+    let mut display_id = base.clone();
+    let mut suffix = 1;
+    while feature_locations(component, &display_id).is_some() {
+        suffix += 1;
+        display_id = format!("{base}_{suffix}");
+    }
+
+    let mut feature = SequenceFeature::new(&component.identity, &display_id)?;
+    let roles: Vec<ComponentRole> = line
+        .attribute(SBOL_ROLE)
+        .unwrap_or_default()
+        .iter()
+        .map(|role| role.parse())
+        .collect::<Result<_, _>>()?;
+    feature.role = if roles.is_empty() {
+        vec![so_role(&line.feature_type)
+            .or_else(|| so_role(DEFAULT_TYPE))
+            .ok_or_else(|| SbolError::Validation(format!("Unknown SO term {DEFAULT_TYPE}")))?]
+    } else {
+        roles
+    };
+    feature.name = line
+        .attribute("Name")
+        .and_then(|name| name.first())
+        .cloned();
+    feature.description = line.attribute("Note").map(|note| note.join(","));
+
+    match instance_of {
+        Some(instance_of) => {
+            let mut sub_component =
+                SubComponent::new(&component.identity, &display_id, instance_of)?;
+            sub_component.name = feature.name;
+            sub_component.description = feature.description;
+            sub_component.role = feature.role;
+            component.sub_components.push(sub_component);
+        }
+        None => component.sequence_features.push(feature),
+    }
+    Ok(display_id)
+}
+
+/// URI and locations of the feature of `component` identified by `display_id`
+fn feature_locations<'a>(
+    component: &'a mut Component,
+    display_id: &str,
+) -> Option<(Url, &'a mut Vec<Location>)> {
+    let matches = |id: &Option<String>| id.as_deref() == Some(display_id);
+    if let Some(sub_component) = component
+        .sub_components
+        .iter_mut()
+        .find(|f| matches(&f.display_id))
+    {
+        return Some((
+            sub_component.identity.clone(),
+            &mut sub_component.has_location,
+        ));
+    }
+    component
+        .sequence_features
+        .iter_mut()
+        .find(|f| matches(&f.display_id))
+        .map(|feature| (feature.identity.clone(), &mut feature.has_location))
+}
+
+/// `display_id` of the feature of `component` identified by `uri`
+fn feature_display_id(component: &Component, uri: &Url) -> Option<String> {
+    let sub_components = component.sub_components.iter().map(|f| f as &dyn Feature);
+    let features = component
+        .sequence_features
+        .iter()
+        .map(|f| f as &dyn Feature);
+    sub_components
+        .chain(features)
+        .find(|feature| &feature.identity() == uri)
+        .and_then(|feature| feature.display_id())
+}
+
+/// Decode the percent-encoded characters of `text`
+fn decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| text.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Percent-encode the characters of `text` which are reserved by GFF3
+fn encode(text: &str) -> String {
+    text.chars()
+        .map(|c| {
+            if RESERVED.contains(c) || c.is_control() {
+                format!("%{:02X}", c as u32)
+            } else {
+                c.to_string()
+            }
+        })
+        .collect()
+}
+
+/// Write the features of every `Component` of `document` with a nucleic acid `Sequence` as GFF3.
+///
+/// See [`to_string`].
+pub fn write<W: Write>(document: &Document, mut writer: W) -> Result<(), SbolError> {
+    writer.write_all(to_string(document)?.as_bytes())?;
+    Ok(())
+}
+
+/// Serialize the features of every `Component` of `document` with a nucleic acid `Sequence` as
+/// GFF3.
+///
+/// Each `Range` (or `EntireSequence`) becomes a line whose seqid is the `display_id` of the
+/// `Component`, and whose type is the Sequence Ontology name (or accession) of the first suitable
+/// role. `Cut` locations cannot be expressed in GFF3 and are skipped.
+pub fn to_string(document: &Document) -> Result<String, SbolError> {
+    let mut output = String::from("##gff-version 3\n");
+    for component in &document.components {
+        let Some(sequence) = document.nucleic_acid_sequence(component) else {
+            continue;
+        };
+        let seqid = component.display_id.as_deref().ok_or_else(|| {
+            SbolError::Validation(format!("{} has no display_id", component.identity))
+        })?;
+        let seqid = encode(seqid);
+        let length = sequence.nucleic_acid_elements()?.chars().count();
+        output.push_str(&format!("##sequence-region {seqid} 1 {length}\n"));
+
+        for sub_component in &component.sub_components {
+            output.push_str(&serialize_feature(
+                &seqid,
+                component,
+                sub_component,
+                &sub_component.has_location,
+                Some(&sub_component.instance_of),
+                length,
+            )?);
+        }
+        for feature in &component.sequence_features {
+            output.push_str(&serialize_feature(
+                &seqid,
+                component,
+                feature,
+                &feature.has_location,
+                None,
+                length,
+            )?);
+        }
+    }
+    Ok(output)
+}
+
+/// Serialize a `Feature` of `component` as one line per location
+fn serialize_feature(
+    seqid: &str,
+    component: &Component,
+    feature: &dyn Feature,
+    locations: &[Location],
+    instance_of: Option<&Url>,
+    length: usize,
+) -> Result<String, SbolError> {
+    let roles = feature.role();
+    let feature_type = roles
+        .iter()
+        .find_map(|role| so_name(role).map(str::to_string))
+        .or_else(|| {
+            roles
+                .iter()
+                .find_map(|role| so_accession(role).map(|id| format!("SO:{id}")))
+        })
+        .unwrap_or_else(|| DEFAULT_TYPE.to_string());
+
+    let mut attributes = vec![];
+    if let Some(display_id) = feature.display_id() {
+        attributes.push(format!("ID={}", encode(&display_id)));
+    }
+    if let Some(name) = feature.name() {
+        attributes.push(format!("Name={}", encode(&name)));
+    }
+    if let Some(description) = feature.description() {
+        attributes.push(format!("Note={}", encode(&description)));
+    }
+    let parents: Vec<String> = feature
+        .annotation_values(&Url::parse(PART_OF)?)
+        .iter()
+        .filter_map(AnnotationValue::as_uri)
+        .filter_map(|parent| feature_display_id(component, parent))
+        .map(|parent| encode(&parent))
+        .collect();
+    if !parents.is_empty() {
+        attributes.push(format!("Parent={}", parents.join(",")));
+    }
+    if let Some(instance_of) = instance_of {
+        attributes.push(format!(
+            "{SBOL_INSTANCE_OF}={}",
+            encode(instance_of.as_str())
+        ));
+    }
+    // Roles are only implied by the type if it was derived from the single role of the feature
+    let implied = roles.len() == 1 && so_role(&feature_type).as_ref() == Some(&roles[0]);
+    if !implied && !roles.is_empty() {
+        let roles: Vec<String> = roles
+            .iter()
+            .map(|role| Ok(encode(role.uri()?.as_str())))
+            .collect::<Result<_, SbolError>>()?;
+        attributes.push(format!("{SBOL_ROLE}={}", roles.join(",")));
+    }
+    let attributes = if attributes.is_empty() {
+        ".".to_string()
+    } else {
+        attributes.join(";")
+    };
+
+    // The phase is computed along the order in which segments are read, and lines are then
+    // listed by ascending start
+    let mut locations: Vec<&Location> = locations.iter().collect();
+    locations.sort_by_key(|location| location.order());
+    let mut lines = vec![];
+    // Number of bases of the preceding segments of a CDS, which determines the phase
+    let mut coding = 0;
+    for location in locations {
+        let (start, end) = match location {
+            Location::Range(range) => (range.start, range.end),
+            Location::EntireSequence(_) => (1, length),
+            Location::Cut(_) => continue,
+        };
+        let strand = match location.orientation().or(feature.orientation()) {
+            Some(Orientation::Inline | Orientation::InlineAlt) => "+",
+            Some(Orientation::ReverseComplement | Orientation::ReverseComplementAlt) => "-",
+            None => ".",
+        };
+        let phase = match feature_type.as_str() {
+            "CDS" => ((3 - coding % 3) % 3).to_string(),
+            _ => ".".to_string(),
+        };
+        coding += (end + 1).saturating_sub(start);
+        lines.push((
+            start,
+            format!(
+                "{seqid}\t{SOURCE}\t{}\t{start}\t{end}\t.\t{strand}\t{phase}\t{attributes}\n",
+                encode(&feature_type)
+            ),
+        ));
+    }
+    lines.sort_by_key(|(start, _)| *start);
+    Ok(lines.into_iter().map(|(_, line)| line).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use url::Url;
+
+    use crate::formats::fasta::{self, FastaOptions};
+    use crate::ontologies::{ComponentRole, Orientation};
+    use crate::translation::{translate_feature, TranslationOptions};
+    use crate::Location;
+
+    use super::*;
+    use crate::test_utils::uri;
+
+    const ANNOTATIONS: &str = "##gff-version 3
+##sequence-region pTest 1 60
+pTest\tpipeline\tpromoter\t1\t10\t.\t+\t.\tID=pTest_promoter;Name=pTest;Note=constitutive%3B strong
+pTest\tpipeline\tCDS\t20\t30\t.\t-\t2\tID=cds-1;Name=testA
+pTest\tpipeline\tCDS\t41\t50\t.\t-\t0\tID=cds-1;Name=testA
+pTest\tpipeline\tfive_prime_UTR\t11\t19\t.\t.\t.\tParent=cds-1
+pTest\tpipeline\tSO:0000296\t51\t60\t.\t?\t.\tID=ori
+pTest\tpipeline\tunknown_thing\t55\t56\t.\t+\t.\t.
+###
+##FASTA
+>pTest
+ttgacagcta
+";
+
+    fn document() -> Document {
+        let fasta = ">pTest\n\
+            ttgacagctagctcagtcctaggtataatgctagcatgcatgcatgcatgcatgcatgca\n";
+        let namespace = uri("https://test.org/lab");
+        fasta::parse(fasta, &namespace, &FastaOptions { components: true }).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let mut document = document();
+        parse(ANNOTATIONS, &mut document).unwrap();
+        let features = &document.components[0].sequence_features;
+        let display_ids: Vec<_> = features
+            .iter()
+            .map(|f| f.display_id.as_deref().unwrap())
+            .collect();
+        assert_eq!(
+            display_ids,
+            vec![
+                "pTest_promoter",
+                "cds_1",
+                "SequenceFeature3",
+                "ori",
+                "SequenceFeature5"
+            ]
+        );
+
+        let promoter = &features[0];
+        assert_eq!(promoter.role, vec![ComponentRole::Promoter]);
+        assert_eq!(promoter.name.as_deref(), Some("pTest"));
+        assert_eq!(
+            promoter.description.as_deref(),
+            Some("constitutive; strong")
+        );
+
+        let cds = &features[1];
+        assert_eq!(cds.role, vec![ComponentRole::CDS]);
+        assert_eq!(cds.has_location.len(), 2);
+        let Location::Range(range) = &cds.has_location[1] else {
+            panic!("Expected a Range, found {:?}", cds.has_location[1]);
+        };
+        assert_eq!(
+            range.identity.as_str(),
+            "https://test.org/lab/pTest/cds_1/Range2"
+        );
+        assert_eq!(
+            range.has_sequence.as_str(),
+            "https://test.org/lab/pTest_sequence"
+        );
+        assert_eq!((range.start, range.end), (41, 50));
+        assert_eq!(range.orientation, Some(Orientation::ReverseComplement));
+        // The segments of the reverse strand are read from the last one
+        assert_eq!(range.order, Some(1));
+
+        assert_eq!(so_name(&features[2].role[0]), Some("five_prime_UTR"));
+        assert_eq!(
+            features[2].annotations,
+            vec![Annotation::uri(uri(PART_OF), cds.identity.clone())]
+        );
+        assert_eq!(so_name(&features[3].role[0]), Some("origin_of_replication"));
+        assert_eq!(features[3].has_location[0].orientation(), None);
+        assert_eq!(so_name(&features[4].role[0]), Some(DEFAULT_TYPE));
+    }
+
+    #[test]
+    fn test_reverse_strand_order() {
+        // complement(join(3..11,16..21)), which reads ATG AAA TTT GGT TAA
+        let fasta = ">pCds\nggttaaccaaacccctttcatgg\n";
+        let namespace = uri("https://test.org/lab");
+        let mut document =
+            fasta::parse(fasta, &namespace, &FastaOptions { components: true }).unwrap();
+        let annotations = "pCds\t.\tCDS\t3\t11\t.\t-\t0\tID=cds\n\
+                           pCds\t.\tCDS\t16\t21\t.\t-\t0\tID=cds\n";
+        parse(annotations, &mut document).unwrap();
+        let component = document.components[0].identity.clone();
+        let cds = document.components[0].sequence_features[0].identity.clone();
+        let options = TranslationOptions::default();
+        let protein =
+            translate_feature(&mut document, &component, &cds, "protein", &options).unwrap();
+        let protein = document.component(&protein).unwrap();
+        let sequence = document.sequence(&protein.has_sequence[0]).unwrap();
+        assert_eq!(sequence.elements.as_deref(), Some("MKFG*"));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let mut document = document();
+        let unknown = "chr1\t.\tgene\t1\t10\t.\t+\t.\tID=a\n";
+        assert!(parse(unknown, &mut document).is_err());
+        let beyond = "pTest\t.\tgene\t1\t100\t.\t+\t.\tID=a\n";
+        assert!(parse(beyond, &mut document).is_err());
+        let columns = "pTest\t.\tgene\t1\t10\n";
+        assert!(parse(columns, &mut document).is_err());
+        let parent = "pTest\t.\tgene\t1\t10\t.\t+\t.\tID=a;Parent=b\n";
+        assert!(parse(parent, &mut document).is_err());
+        // The valid first line is not added either
+        let partial = format!("pTest\t.\tgene\t1\t10\t.\t+\t.\tID=a\n{beyond}");
+        assert!(parse(&partial, &mut document).is_err());
+        assert!(document.components[0].sequence_features.is_empty());
+    }
+
+    #[test]
+    fn test_serialize() {
+        let mut document = document();
+        parse(ANNOTATIONS, &mut document).unwrap();
+        let feature = &mut document.components[0].sequence_features[0];
+        feature.role.push(ComponentRole::Effector);
+
+        let output = to_string(&document).unwrap();
+        let lines: Vec<_> = output.lines().collect();
+        assert_eq!(lines[0], "##gff-version 3");
+        assert_eq!(lines[1], "##sequence-region pTest 1 60");
+        assert_eq!(
+            lines[2],
+            "pTest\tSBOL\tpromoter\t1\t10\t.\t+\t.\tID=pTest_promoter;Name=pTest;\
+             Note=constitutive%3B strong;sbol_role=https://identifiers.org/SO:0000167,\
+             https://identifiers.org/CHEBI:35224"
+        );
+        assert_eq!(
+            lines[4],
+            "pTest\tSBOL\tCDS\t41\t50\t.\t-\t0\tID=cds_1;Name=testA"
+        );
+        assert_eq!(
+            lines[3],
+            "pTest\tSBOL\tCDS\t20\t30\t.\t-\t2\tID=cds_1;Name=testA"
+        );
+        assert_eq!(
+            lines[5],
+            "pTest\tSBOL\tfive_prime_UTR\t11\t19\t.\t.\t.\tID=SequenceFeature3;Parent=cds_1"
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut document = document();
+        parse(ANNOTATIONS, &mut document).unwrap();
+        let component = &mut document.components[0];
+        let instance_of = uri("https://test.org/lab/pTet");
+        let mut sub_component =
+            SubComponent::new(&component.identity, "SubComponent1", instance_of).unwrap();
+        sub_component.role = vec![ComponentRole::Promoter];
+        sub_component.has_location = component.sequence_features[0].has_location.clone();
+        for location in &mut sub_component.has_location {
+            if let Location::Range(range) = location {
+                range.identity = Url::parse(&format!(
+                    "{}/{}",
+                    sub_component.identity,
+                    range.display_id.as_deref().unwrap()
+                ))
+                .unwrap();
+            }
+        }
+        component.sub_components.push(sub_component);
+
+        let output = to_string(&document).unwrap();
+        let mut restored = self::document();
+        parse(&output, &mut restored).unwrap();
+        assert_eq!(restored, document);
+    }
+}
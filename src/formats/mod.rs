@@ -0,0 +1,48 @@
+//! Conversion between SBOL and other file formats.
+
+use url::Url;
+
+use crate::Annotation;
+
+/// Properties shared by every `Identified` object, as read from an RDF graph
+struct Common {
+    identity: Url,
+    display_id: Option<String>,
+    name: Option<String>,
+    description: Option<String>,
+    derived_from: Vec<Url>,
+    generated_by: Vec<Url>,
+    has_measure: Vec<Url>,
+    annotations: Vec<Annotation>,
+}
+
+impl Common {
+    fn display_id(&self) -> &str {
+        self.display_id.as_deref().unwrap_or_default()
+    }
+}
+
+/// Copy the `Common` properties into an `Identified` struct
+macro_rules! set_common {
+    ($object:expr, $common:expr) => {{
+        let common: $crate::formats::Common = $common;
+        $object.identity = common.identity;
+        $object.display_id = common.display_id;
+        $object.name = common.name;
+        $object.description = common.description;
+        $object.derived_from = common.derived_from;
+        $object.generated_by = common.generated_by;
+        $object.has_measure = common.has_measure;
+        $object.annotations = common.annotations;
+    }};
+}
+
+pub mod fasta;
+pub mod genbank;
+pub mod gff3;
+mod rdf_xml;
+pub mod sbol2;
+mod sbol3;
+
+pub(crate) use rdf_xml::Graph;
+pub(crate) use sbol3::{sbol3, top_level_graph};
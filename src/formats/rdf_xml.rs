@@ -0,0 +1,873 @@
+//! Minimal RDF/XML support, as used by SBOL files.
+//!
+//! The RDF/XML syntax is supported, but triples are kept in a [`Graph`] whose nodes are URIs:
+//! blank nodes are given URIs with the [`BLANK_NODE_SCHEME`] scheme, which are written back as
+//! `rdf:nodeID` attributes. Literals keep their lexical form, without datatype or language, and
+//! `rdf:parseType="Literal"` values are kept as their XML text.
+
+use std::collections::{HashMap, HashSet};
+
+use url::Url;
+use xml::attribute::OwnedAttribute;
+use xml::name::OwnedName;
+use xml::reader::{EventReader, XmlEvent};
+
+use crate::{AnnotationValue, SbolError};
+
+pub(crate) const RDF_NS: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#";
+pub(crate) const RDF_TYPE: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#type";
+pub(crate) const PROV_DERIVED_FROM: &str = "http://www.w3.org/ns/prov#wasDerivedFrom";
+pub(crate) const PROV_GENERATED_BY: &str = "http://www.w3.org/ns/prov#wasGeneratedBy";
+
+/// Scheme of the URIs given to blank nodes, whose path is the node ID, ie: `genid:genid1`
+const BLANK_NODE_SCHEME: &str = "genid";
+
+/// Namespace of attributes which are not RDF properties (ie: `xml:lang`)
+const XML_NS: &str = "http://www.w3.org/XML/1998/namespace";
+
+/// Attributes of the RDF namespace which are syntax rather than properties
+const RDF_SYNTAX: [&str; 7] = [
+    "about",
+    "ID",
+    "nodeID",
+    "resource",
+    "datatype",
+    "parseType",
+    "bagID",
+];
+
+/// Set of triples, grouped by subject
+#[derive(Debug, Default)]
+pub(crate) struct Graph {
+    /// Subjects in order of first appearance
+    subjects: Vec<Url>,
+    properties: HashMap<Url, Vec<(Url, AnnotationValue)>>,
+}
+
+/// Element being parsed
+enum Frame {
+    /// `rdf:RDF`
+    Root,
+
+    /// Node element, or property element with `rdf:parseType="Resource"`. `items` counts the
+    /// `rdf:li` properties.
+    Node { subject: Url, items: usize },
+
+    Property {
+        subject: Url,
+        predicate: Url,
+        text: String,
+        object: Option<Url>,
+        /// Statement of the `rdf:ID` attribute, which reifies the triple
+        statement: Option<Url>,
+    },
+
+    /// Property element with `rdf:parseType="Literal"`, whose content is kept as XML text. `depth`
+    /// counts the open elements of the content.
+    Literal {
+        subject: Url,
+        predicate: Url,
+        xml: String,
+        depth: usize,
+    },
+
+    /// Property element with `rdf:parseType="Collection"`, whose node elements form a list
+    Collection {
+        subject: Url,
+        predicate: Url,
+        items: Vec<Url>,
+    },
+}
+
+fn parse_error(msg: impl std::fmt::Display) -> SbolError {
+    SbolError::Parse(format!("RDF/XML: {msg}"))
+}
+
+fn rdf(local: &str) -> Result<Url, SbolError> {
+    Ok(Url::parse(&format!("{RDF_NS}{local}"))?)
+}
+
+fn expanded(name: &OwnedName) -> String {
+    format!(
+        "{}{}",
+        name.namespace.as_deref().unwrap_or_default(),
+        name.local_name
+    )
+}
+
+/// Whether `uri` identifies a blank node
+fn is_blank(uri: &Url) -> bool {
+    uri.scheme() == BLANK_NODE_SCHEME
+}
+
+/// State of [`Graph::parse`]
+struct Parser {
+    graph: Graph,
+    stack: Vec<Frame>,
+    /// `xml:base` in scope of each element of `stack`
+    bases: Vec<Option<Url>>,
+    /// Node IDs of the document, which generated blank nodes must not reuse
+    node_ids: HashSet<String>,
+    generated: usize,
+}
+
+impl Parser {
+    fn base(&self) -> Option<&Url> {
+        self.bases.last().and_then(Option::as_ref)
+    }
+
+    /// Resolve `uri`, from the `attribute` of an element, against `xml:base`
+    fn resolve(&self, attribute: &str, uri: &str) -> Result<Url, SbolError> {
+        match self.base() {
+            Some(base) => base.join(uri),
+            None => Url::parse(uri),
+        }
+        .map_err(|e| parse_error(format!("Invalid rdf:{attribute} {uri:?}: {e}")))
+    }
+
+    /// URI of `rdf:ID` `id`, relative to `xml:base`
+    fn id(&self, id: &str) -> Result<Url, SbolError> {
+        let base = self
+            .base()
+            .ok_or_else(|| parse_error(format!("rdf:ID {id:?} requires an xml:base")))?;
+        let mut uri = base.clone();
+        uri.set_fragment(Some(id));
+        Ok(uri)
+    }
+
+    fn blank_node(&self, node_id: &str) -> Result<Url, SbolError> {
+        Url::parse(&format!("{BLANK_NODE_SCHEME}:{node_id}"))
+            .map_err(|e| parse_error(format!("Invalid rdf:nodeID {node_id:?}: {e}")))
+    }
+
+    /// Blank node with a generated ID
+    fn new_blank_node(&mut self) -> Result<Url, SbolError> {
+        loop {
+            self.generated += 1;
+            let node_id = format!("genid{}", self.generated);
+            if !self.node_ids.contains(&node_id) {
+                return self.blank_node(&node_id);
+            }
+        }
+    }
+
+    /// Subject of a node element, or object of an empty property element, from its `attribute`
+    fn node(&mut self, attribute: impl Fn(&str) -> Option<String>) -> Result<Url, SbolError> {
+        if let Some(about) = attribute("about") {
+            self.resolve("about", &about)
+        } else if let Some(resource) = attribute("resource") {
+            self.resolve("resource", &resource)
+        } else if let Some(id) = attribute("ID") {
+            self.id(&id)
+        } else if let Some(node_id) = attribute("nodeID") {
+            self.blank_node(&node_id)
+        } else {
+            self.new_blank_node()
+        }
+    }
+
+    /// Add the property attributes of an element, and its `rdf:type` attribute, to `subject`
+    fn property_attributes(
+        &mut self,
+        subject: &Url,
+        attributes: &[OwnedAttribute],
+    ) -> Result<(), SbolError> {
+        for a in attributes {
+            let namespace = a.name.namespace.as_deref().unwrap_or_default();
+            if namespace.is_empty() || namespace == XML_NS {
+                continue;
+            } else if namespace == RDF_NS && a.name.local_name == "type" {
+                let class = self.resolve("type", &a.value)?;
+                self.graph
+                    .insert(subject.clone(), rdf("type")?, AnnotationValue::Uri(class));
+            } else if namespace != RDF_NS || !RDF_SYNTAX.contains(&a.name.local_name.as_str()) {
+                let predicate = Url::parse(&expanded(&a.name))?;
+                let object = AnnotationValue::Literal(a.value.clone());
+                self.graph.insert(subject.clone(), predicate, object);
+            }
+        }
+        Ok(())
+    }
+
+    /// Add a triple, and its reification if it has a `statement` URI
+    fn insert(
+        &mut self,
+        subject: Url,
+        predicate: Url,
+        object: AnnotationValue,
+        statement: Option<Url>,
+    ) -> Result<(), SbolError> {
+        if let Some(statement) = statement {
+            let uri = |uri: &Url| AnnotationValue::Uri(uri.clone());
+            let class = AnnotationValue::Uri(rdf("Statement")?);
+            self.graph.insert(statement.clone(), rdf("type")?, class);
+            self.graph
+                .insert(statement.clone(), rdf("subject")?, uri(&subject));
+            self.graph
+                .insert(statement.clone(), rdf("predicate")?, uri(&predicate));
+            self.graph.insert(statement, rdf("object")?, object.clone());
+        }
+        self.graph.insert(subject, predicate, object);
+        Ok(())
+    }
+
+    fn start_element(
+        &mut self,
+        name: &OwnedName,
+        attributes: &[OwnedAttribute],
+    ) -> Result<(), SbolError> {
+        let element = expanded(name);
+        if let Some(Frame::Literal { xml, depth, .. }) = self.stack.last_mut() {
+            xml.push('<');
+            xml.push_str(&name.to_string());
+            for a in attributes {
+                xml.push_str(&format!(" {}=\"{}\"", a.name, escape(&a.value)));
+            }
+            xml.push('>');
+            *depth += 1;
+            return Ok(());
+        }
+
+        let base = attributes
+            .iter()
+            .find(|a| a.name.namespace.as_deref() == Some(XML_NS) && a.name.local_name == "base")
+            .map(|a| self.resolve("base", &a.value))
+            .transpose()?
+            .or_else(|| self.base().cloned());
+        self.bases.push(base);
+        let attribute = |local: &str| {
+            attributes
+                .iter()
+                .find(|a| a.name.namespace.as_deref() == Some(RDF_NS) && a.name.local_name == local)
+                .map(|a| a.value.clone())
+        };
+
+        let frame = match self.stack.last_mut() {
+            None if element == format!("{RDF_NS}RDF") => Frame::Root,
+            None | Some(Frame::Root | Frame::Property { .. } | Frame::Collection { .. }) => {
+                if attribute("resource").is_some() {
+                    return Err(parse_error(format!(
+                        "rdf:resource on node element {element}"
+                    )));
+                }
+                let subject = self.node(attribute)?;
+                match self.stack.last_mut() {
+                    Some(Frame::Property { object, .. }) => *object = Some(subject.clone()),
+                    Some(Frame::Collection { items, .. }) => items.push(subject.clone()),
+                    _ => (),
+                }
+                self.graph.touch(&subject);
+                if element != format!("{RDF_NS}Description") {
+                    let class = AnnotationValue::Uri(Url::parse(&element)?);
+                    self.graph.insert(subject.clone(), rdf("type")?, class);
+                }
+                self.property_attributes(&subject, attributes)?;
+                Frame::Node { subject, items: 0 }
+            }
+            Some(Frame::Node { subject, items }) => {
+                let subject = subject.clone();
+                let predicate = if element == format!("{RDF_NS}li") {
+                    *items += 1;
+                    rdf(&format!("_{items}"))?
+                } else {
+                    Url::parse(&element)?
+                };
+                let statement = attribute("ID").map(|id| self.id(&id)).transpose()?;
+                match attribute("parseType").as_deref() {
+                    Some("Resource") => {
+                        let object = self.new_blank_node()?;
+                        let value = AnnotationValue::Uri(object.clone());
+                        self.insert(subject, predicate, value, statement)?;
+                        self.graph.touch(&object);
+                        Frame::Node {
+                            subject: object,
+                            items: 0,
+                        }
+                    }
+                    Some("Collection") => Frame::Collection {
+                        subject,
+                        predicate,
+                        items: vec![],
+                    },
+                    Some(_) => Frame::Literal {
+                        subject,
+                        predicate,
+                        xml: String::new(),
+                        depth: 0,
+                    },
+                    None => {
+                        let properties = attributes.iter().any(|a| {
+                            let namespace = a.name.namespace.as_deref().unwrap_or_default();
+                            !namespace.is_empty()
+                                && namespace != XML_NS
+                                && (namespace != RDF_NS
+                                    || !RDF_SYNTAX.contains(&a.name.local_name.as_str()))
+                        });
+                        let object = if attribute("resource").is_some()
+                            || attribute("nodeID").is_some()
+                            || properties
+                        {
+                            let object = self.node(|local| match local {
+                                "resource" | "nodeID" => attribute(local),
+                                _ => None,
+                            })?;
+                            self.property_attributes(&object, attributes)?;
+                            Some(object)
+                        } else {
+                            None
+                        };
+                        Frame::Property {
+                            subject,
+                            predicate,
+                            text: String::new(),
+                            object,
+                            statement,
+                        }
+                    }
+                }
+            }
+            Some(Frame::Literal { .. }) => unreachable!("Literal content is handled above"),
+        };
+        self.stack.push(frame);
+        Ok(())
+    }
+
+    fn end_element(&mut self, name: &OwnedName) -> Result<(), SbolError> {
+        if let Some(Frame::Literal { xml, depth, .. }) = self.stack.last_mut() {
+            if *depth > 0 {
+                xml.push_str(&format!("</{name}>"));
+                *depth -= 1;
+                return Ok(());
+            }
+        }
+        self.bases.pop();
+        match self.stack.pop() {
+            Some(Frame::Property {
+                subject,
+                predicate,
+                text,
+                object,
+                statement,
+            }) => {
+                let object = match object {
+                    Some(uri) => AnnotationValue::Uri(uri),
+                    None => AnnotationValue::Literal(text),
+                };
+                self.insert(subject, predicate, object, statement)?;
+            }
+            Some(Frame::Literal {
+                subject,
+                predicate,
+                xml,
+                ..
+            }) => {
+                self.insert(subject, predicate, AnnotationValue::Literal(xml), None)?;
+            }
+            Some(Frame::Collection {
+                subject,
+                predicate,
+                items,
+            }) => {
+                let mut list = rdf("nil")?;
+                for item in items.into_iter().rev() {
+                    let node = self.new_blank_node()?;
+                    let first = AnnotationValue::Uri(item);
+                    self.graph.insert(node.clone(), rdf("first")?, first);
+                    self.graph
+                        .insert(node.clone(), rdf("rest")?, AnnotationValue::Uri(list));
+                    list = node;
+                }
+                self.insert(subject, predicate, AnnotationValue::Uri(list), None)?;
+            }
+            Some(Frame::Root | Frame::Node { .. }) | None => (),
+        }
+        Ok(())
+    }
+
+    fn characters(&mut self, characters: &str) {
+        match self.stack.last_mut() {
+            Some(Frame::Property { text, .. }) => text.push_str(characters),
+            Some(Frame::Literal { xml, .. }) => xml.push_str(&escape(characters)),
+            _ => (),
+        }
+    }
+}
+
+impl Graph {
+    /// Parse an RDF/XML document
+    pub(crate) fn parse(input: &str) -> Result<Self, SbolError> {
+        // Node IDs are collected first, so that generated blank nodes do not clash with them
+        let mut node_ids = HashSet::new();
+        for event in EventReader::new(input.as_bytes()) {
+            if let XmlEvent::StartElement { attributes, .. } = event.map_err(parse_error)? {
+                node_ids.extend(
+                    attributes
+                        .into_iter()
+                        .filter(|a| {
+                            a.name.namespace.as_deref() == Some(RDF_NS)
+                                && a.name.local_name == "nodeID"
+                        })
+                        .map(|a| a.value),
+                );
+            }
+        }
+
+        let mut parser = Parser {
+            graph: Self::default(),
+            stack: vec![],
+            bases: vec![],
+            node_ids,
+            generated: 0,
+        };
+        for event in EventReader::new(input.as_bytes()) {
+            match event.map_err(parse_error)? {
+                XmlEvent::StartElement {
+                    name, attributes, ..
+                } => parser.start_element(&name, &attributes)?,
+                XmlEvent::EndElement { name } => parser.end_element(&name)?,
+                XmlEvent::Characters(characters)
+                | XmlEvent::CData(characters)
+                | XmlEvent::Whitespace(characters) => parser.characters(&characters),
+                _ => (),
+            }
+        }
+        Ok(parser.graph)
+    }
+
+    fn touch(&mut self, subject: &Url) {
+        if !self.properties.contains_key(subject) {
+            self.subjects.push(subject.clone());
+            self.properties.insert(subject.clone(), vec![]);
+        }
+    }
+
+    pub(crate) fn insert(&mut self, subject: Url, predicate: Url, object: AnnotationValue) {
+        self.touch(&subject);
+        if let Some(properties) = self.properties.get_mut(&subject) {
+            properties.push((predicate, object));
+        }
+    }
+
+    /// Add a property whose value is a reference to `object`
+    pub(crate) fn insert_uri(
+        &mut self,
+        subject: &Url,
+        predicate: &str,
+        object: &Url,
+    ) -> Result<(), SbolError> {
+        let predicate = Url::parse(predicate)?;
+        self.insert(
+            subject.clone(),
+            predicate,
+            AnnotationValue::Uri(object.clone()),
+        );
+        Ok(())
+    }
+
+    /// Add a property whose value is a literal
+    pub(crate) fn insert_literal(
+        &mut self,
+        subject: &Url,
+        predicate: &str,
+        object: impl ToString,
+    ) -> Result<(), SbolError> {
+        let predicate = Url::parse(predicate)?;
+        let object = AnnotationValue::Literal(object.to_string());
+        self.insert(subject.clone(), predicate, object);
+        Ok(())
+    }
+
+    /// Serialize as RDF/XML, with one node element per subject.
+    ///
+    /// Namespaces are declared with the given `prefixes` where possible, other namespaces are
+    /// given generated prefixes. Fails if a property cannot be written as an XML element name.
+    pub(crate) fn to_rdf_xml(&self, prefixes: &[(&str, &str)]) -> Result<String, SbolError> {
+        let mut namespaces: Vec<(String, String)> = vec![("rdf".to_string(), RDF_NS.to_string())];
+        let mut qname = |uri: &Url| -> Option<String> {
+            let (namespace, local) = split_uri(uri)?;
+            let prefix = match namespaces.iter().find(|(_, ns)| ns == namespace) {
+                Some((prefix, _)) => prefix.clone(),
+                None => {
+                    let prefix = prefixes
+                        .iter()
+                        .find(|(_, ns)| *ns == namespace)
+                        .map(|(prefix, _)| prefix.to_string())
+                        .unwrap_or_else(|| format!("ns{}", namespaces.len()));
+                    namespaces.push((prefix.clone(), namespace.to_string()));
+                    prefix
+                }
+            };
+            Some(format!("{prefix}:{local}"))
+        };
+
+        let mut body = String::new();
+        for subject in &self.subjects {
+            let properties = self.properties(subject);
+            if properties.is_empty() {
+                continue;
+            }
+            // The first type which is a valid element name becomes the node element
+            let typed = properties.iter().position(|(predicate, value)| {
+                predicate.as_str() == RDF_TYPE
+                    && value.as_uri().and_then(|uri| split_uri(uri)).is_some()
+            });
+            let element = match typed.and_then(|i| properties[i].1.as_uri()) {
+                Some(class) => qname(class).unwrap_or_default(),
+                None => "rdf:Description".to_string(),
+            };
+            body.push_str(&format!(
+                "  <{element} {}>\n",
+                node_attribute("about", subject)
+            ));
+            for (i, (predicate, value)) in properties.iter().enumerate() {
+                if Some(i) == typed {
+                    continue;
+                }
+                let property = qname(predicate).ok_or_else(|| {
+                    SbolError::Validation(format!("{predicate} cannot be written as RDF/XML"))
+                })?;
+                match value {
+                    AnnotationValue::Uri(uri) => body.push_str(&format!(
+                        "    <{property} {}/>\n",
+                        node_attribute("resource", uri)
+                    )),
+                    AnnotationValue::Literal(literal) => body.push_str(&format!(
+                        "    <{property}>{}</{property}>\n",
+                        escape(literal)
+                    )),
+                }
+            }
+            body.push_str(&format!("  </{element}>\n"));
+        }
+
+        let mut output = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<rdf:RDF");
+        for (prefix, namespace) in &namespaces {
+            output.push_str(&format!("\n  xmlns:{prefix}=\"{}\"", escape(namespace)));
+        }
+        output.push_str(">\n");
+        output.push_str(&body);
+        output.push_str("</rdf:RDF>\n");
+        Ok(output)
+    }
+
+    /// Every subject, in order of appearance
+    pub(crate) fn subjects(&self) -> &[Url] {
+        &self.subjects
+    }
+
+    /// Every subject which has `rdf:type` `class`, in order of appearance
+    pub(crate) fn subjects_of_type(&self, class: &str) -> Vec<&Url> {
+        self.subjects
+            .iter()
+            .filter(|subject| self.has_type(subject, class))
+            .collect()
+    }
+
+    pub(crate) fn has_type(&self, subject: &Url, class: &str) -> bool {
+        self.uris(subject, RDF_TYPE)
+            .iter()
+            .any(|uri| uri.as_str() == class)
+    }
+
+    /// Every property of `subject`
+    pub(crate) fn properties(&self, subject: &Url) -> &[(Url, AnnotationValue)] {
+        self.properties
+            .get(subject)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Values of the `predicate` properties of `subject`
+    pub(crate) fn values<'a>(
+        &'a self,
+        subject: &Url,
+        predicate: &'a str,
+    ) -> impl Iterator<Item = &'a AnnotationValue> {
+        self.properties(subject)
+            .iter()
+            .filter(move |(p, _)| p.as_str() == predicate)
+            .map(|(_, value)| value)
+    }
+
+    /// URI values of the `predicate` properties of `subject`
+    pub(crate) fn uris(&self, subject: &Url, predicate: &str) -> Vec<Url> {
+        self.values(subject, predicate)
+            .filter_map(AnnotationValue::as_uri)
+            .cloned()
+            .collect()
+    }
+
+    /// First URI value of the `predicate` properties of `subject`
+    pub(crate) fn uri(&self, subject: &Url, predicate: &str) -> Option<Url> {
+        self.uris(subject, predicate).into_iter().next()
+    }
+
+    /// First value of the `predicate` properties of `subject`, as text
+    pub(crate) fn literal(&self, subject: &Url, predicate: &str) -> Option<String> {
+        self.values(subject, predicate)
+            .next()
+            .map(|value| value.as_str().to_string())
+    }
+}
+
+/// Split `uri` into a namespace and a local name which is a valid XML name, after the last `#` or
+/// `/`
+fn split_uri(uri: &Url) -> Option<(&str, &str)> {
+    let uri = uri.as_str();
+    let split = uri.rfind(['#', '/'])? + 1;
+    let (namespace, local) = uri.split_at(split);
+    let valid = local.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && local
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "_-.".contains(c));
+    valid.then_some((namespace, local))
+}
+
+/// `rdf:nodeID` attribute of a blank node, or `rdf:{attribute}` attribute of another node
+fn node_attribute(attribute: &str, uri: &Url) -> String {
+    if is_blank(uri) {
+        format!("rdf:nodeID=\"{}\"", escape(uri.path()))
+    } else {
+        format!("rdf:{attribute}=\"{}\"", escape(uri.as_str()))
+    }
+}
+
+/// Escape the characters of `text` which are reserved in XML
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::uri;
+
+    #[test]
+    fn test_parse() {
+        let input = r#"<?xml version="1.0" ?>
+<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#"
+         xmlns:ex="http://example.org/ns#">
+  <ex:Thing rdf:about="http://example.org/a" ex:label="A">
+    <ex:child>
+      <ex:Part rdf:about="http://example.org/a/b">
+        <ex:count>2</ex:count>
+      </ex:Part>
+    </ex:child>
+    <ex:link rdf:resource="http://example.org/c"/>
+  </ex:Thing>
+  <rdf:Description rdf:about="http://example.org/c"/>
+</rdf:RDF>"#;
+        let graph = Graph::parse(input).unwrap();
+        let a = uri("http://example.org/a");
+        let b = uri("http://example.org/a/b");
+        assert_eq!(graph.subjects.len(), 3);
+        assert!(graph.has_type(&a, "http://example.org/ns#Thing"));
+        assert_eq!(
+            graph.literal(&a, "http://example.org/ns#label").as_deref(),
+            Some("A")
+        );
+        assert_eq!(
+            graph.uri(&a, "http://example.org/ns#child"),
+            Some(b.clone())
+        );
+        assert_eq!(
+            graph.literal(&b, "http://example.org/ns#count").as_deref(),
+            Some("2")
+        );
+        assert_eq!(
+            graph.subjects_of_type("http://example.org/ns#Part"),
+            vec![&b]
+        );
+    }
+
+    #[test]
+    fn test_xml_base() {
+        let input = r#"<?xml version="1.0" ?>
+<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#"
+         xmlns:ex="http://example.org/ns#"
+         xml:base="http://example.org/doc">
+  <ex:Thing rdf:ID="a">
+    <ex:link rdf:resource="parts/b"/>
+  </ex:Thing>
+  <ex:Thing rdf:about="c" xml:base="http://other.org/lib/">
+    <ex:link rdf:resource="d"/>
+  </ex:Thing>
+</rdf:RDF>"#;
+        let graph = Graph::parse(input).unwrap();
+        let ex = |local: &str| format!("http://example.org/ns#{local}");
+        let a = uri("http://example.org/doc#a");
+        assert!(graph.has_type(&a, &ex("Thing")));
+        assert_eq!(
+            graph.uri(&a, &ex("link")),
+            Some(uri("http://example.org/parts/b"))
+        );
+        // A nested xml:base overrides the one of the document
+        let c = uri("http://other.org/lib/c");
+        assert_eq!(
+            graph.uri(&c, &ex("link")),
+            Some(uri("http://other.org/lib/d"))
+        );
+
+        let no_base = input.replace(r#"xml:base="http://example.org/doc""#, "");
+        assert!(Graph::parse(&no_base).is_err());
+    }
+
+    #[test]
+    fn test_node_id() {
+        let input = r#"<?xml version="1.0" ?>
+<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#"
+         xmlns:ex="http://example.org/ns#">
+  <ex:Thing rdf:about="http://example.org/a">
+    <ex:author rdf:nodeID="genid1"/>
+    <ex:editor>
+      <rdf:Description ex:name="Grace"/>
+    </ex:editor>
+  </ex:Thing>
+  <rdf:Description rdf:nodeID="genid1" ex:name="Ada"/>
+</rdf:RDF>"#;
+        let graph = Graph::parse(input).unwrap();
+        let ex = |local: &str| format!("http://example.org/ns#{local}");
+        let a = uri("http://example.org/a");
+        let author = graph.uri(&a, &ex("author")).unwrap();
+        assert!(is_blank(&author));
+        assert_eq!(graph.literal(&author, &ex("name")).as_deref(), Some("Ada"));
+        // Generated node IDs do not reuse those of the document
+        let editor = graph.uri(&a, &ex("editor")).unwrap();
+        assert!(is_blank(&editor));
+        assert_ne!(editor, author);
+        assert_eq!(
+            graph.literal(&editor, &ex("name")).as_deref(),
+            Some("Grace")
+        );
+    }
+
+    #[test]
+    fn test_parse_type() {
+        let input = r#"<?xml version="1.0" ?>
+<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#"
+         xmlns:ex="http://example.org/ns#">
+  <ex:Thing rdf:about="http://example.org/a">
+    <ex:address rdf:parseType="Resource">
+      <ex:city>Paris</ex:city>
+    </ex:address>
+    <ex:note rdf:parseType="Literal">a <b>bold</b> note</ex:note>
+    <ex:parts rdf:parseType="Collection">
+      <ex:Part rdf:about="http://example.org/p1"/>
+      <ex:Part rdf:about="http://example.org/p2"/>
+    </ex:parts>
+  </ex:Thing>
+</rdf:RDF>"#;
+        let graph = Graph::parse(input).unwrap();
+        let ex = |local: &str| format!("http://example.org/ns#{local}");
+        let a = uri("http://example.org/a");
+        let address = graph.uri(&a, &ex("address")).unwrap();
+        assert!(is_blank(&address));
+        assert_eq!(
+            graph.literal(&address, &ex("city")).as_deref(),
+            Some("Paris")
+        );
+        assert_eq!(
+            graph.literal(&a, &ex("note")).as_deref(),
+            Some("a <b>bold</b> note")
+        );
+
+        // Collections are lists of blank nodes
+        let list = graph.uri(&a, &ex("parts")).unwrap();
+        let first = format!("{RDF_NS}first");
+        let rest = format!("{RDF_NS}rest");
+        assert_eq!(graph.uri(&list, &first), Some(uri("http://example.org/p1")));
+        let list = graph.uri(&list, &rest).unwrap();
+        assert_eq!(graph.uri(&list, &first), Some(uri("http://example.org/p2")));
+        assert_eq!(graph.uri(&list, &rest), Some(uri(&format!("{RDF_NS}nil"))));
+        assert!(graph.has_type(&uri("http://example.org/p2"), &ex("Part")));
+    }
+
+    #[test]
+    fn test_parse_syntax() {
+        let input = r#"<?xml version="1.0" ?>
+<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#"
+         xmlns:ex="http://example.org/ns#"
+         xml:base="http://example.org/doc">
+  <ex:Thing rdf:ID="a">
+    <ex:size rdf:ID="claim">3</ex:size>
+    <ex:origin ex:country="FR"/>
+  </ex:Thing>
+  <rdf:Bag rdf:about="bag">
+    <rdf:li rdf:resource="p1"/>
+    <rdf:li rdf:resource="p2"/>
+  </rdf:Bag>
+</rdf:RDF>"#;
+        let graph = Graph::parse(input).unwrap();
+        let ex = |local: &str| format!("http://example.org/ns#{local}");
+        let a = uri("http://example.org/doc#a");
+        let origin = graph.uri(&a, &ex("origin")).unwrap();
+        assert_eq!(
+            graph.literal(&origin, &ex("country")).as_deref(),
+            Some("FR")
+        );
+
+        // rdf:ID on a property element reifies the triple
+        let claim = uri("http://example.org/doc#claim");
+        assert!(graph.has_type(&claim, &format!("{RDF_NS}Statement")));
+        assert_eq!(graph.uri(&claim, &format!("{RDF_NS}subject")), Some(a));
+        let bag = uri("http://example.org/bag");
+        assert_eq!(
+            graph.uri(&bag, &format!("{RDF_NS}_2")),
+            Some(uri("http://example.org/p2"))
+        );
+    }
+
+    #[test]
+    fn test_write() {
+        let mut graph = Graph::default();
+        let a = uri("http://example.org/a");
+        graph
+            .insert_uri(&a, RDF_TYPE, &uri("http://example.org/ns#Thing"))
+            .unwrap();
+        graph
+            .insert_literal(&a, "http://example.org/ns#label", "<A & B>")
+            .unwrap();
+        graph
+            .insert_uri(&a, "http://other.org/link", &uri("http://example.org/c"))
+            .unwrap();
+
+        let output = graph
+            .to_rdf_xml(&[("ex", "http://example.org/ns#")])
+            .unwrap();
+        assert!(output.contains("<ex:Thing rdf:about=\"http://example.org/a\">"));
+        assert!(output.contains("<ex:label>&lt;A &amp; B&gt;</ex:label>"));
+        assert!(output.contains("xmlns:ns2=\"http://other.org/\""));
+
+        let restored = Graph::parse(&output).unwrap();
+        assert_eq!(restored.subjects, graph.subjects);
+        assert_eq!(restored.properties, graph.properties);
+    }
+
+    #[test]
+    fn test_write_blank_nodes() {
+        let input = r#"<?xml version="1.0" ?>
+<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#"
+         xmlns:ex="http://example.org/ns#">
+  <ex:Thing rdf:about="http://example.org/a">
+    <ex:author rdf:nodeID="genid1"/>
+    <ex:address rdf:parseType="Resource">
+      <ex:city>Paris</ex:city>
+    </ex:address>
+  </ex:Thing>
+  <rdf:Description rdf:nodeID="genid1" ex:name="Ada"/>
+</rdf:RDF>"#;
+        let graph = Graph::parse(input).unwrap();
+        let output = graph
+            .to_rdf_xml(&[("ex", "http://example.org/ns#")])
+            .unwrap();
+        // Blank nodes are written back with their node IDs
+        assert!(output.contains("<ex:author rdf:nodeID=\"genid1\"/>"));
+        assert!(output.contains("<rdf:Description rdf:nodeID=\"genid1\">"));
+        let restored = Graph::parse(&output).unwrap();
+        assert_eq!(restored.subjects, graph.subjects);
+        assert_eq!(restored.properties, graph.properties);
+    }
+}
@@ -0,0 +1,2155 @@
+//! Conversion between SBOL2 RDF/XML documents and SBOL3.
+//!
+//! [`parse`] follows the rules of the SBOL3 specification (Appendix: SBOL 2 to SBOL 3):
+//!
+//! - `ComponentDefinition` and `ModuleDefinition` objects become `Component` objects, the latter
+//!   with a functional entity `type`. BioPAX types become SBO types.
+//! - `Component`, `FunctionalComponent` and `Module` objects become `SubComponent` objects. The
+//!   `direction` of a `FunctionalComponent` is recorded by the `Interface` of its `Component`.
+//! - A `SequenceAnnotation` becomes a `SequenceFeature`, unless it locates a `Component`, in which
+//!   case its `Location` objects are moved to the corresponding `SubComponent`.
+//! - A `SequenceConstraint` becomes a `Constraint`, and each `MapsTo` becomes a
+//!   `ComponentReference` along with a `Constraint` whose restriction follows its refinement.
+//! - The `persistentIdentity` of each object becomes its identity, and versions are dropped.
+//!
+//! Properties which SBOL3 cannot express are kept as `sbol2:` annotations: the `version` of
+//! `TopLevel` objects, the non-public `access` of sub-components, the `merge` refinement of
+//! `MapsTo` objects, and the `SequenceAnnotation` merged into a `SubComponent`. Application
+//! annotations are kept as is. `prov:Activity` objects are shared by both versions. Documents
+//! with other SBOL2 `TopLevel` classes (ie: `Collection` or `Model`) are rejected, so that no
+//! object is dropped.
+//!
+//! [`to_string`] performs the reverse conversion. A `Component` whose only type is functional
+//! entity becomes a `ModuleDefinition`, any other `Component` becomes a `ComponentDefinition`.
+//! Objects which SBOL2 cannot express, such as `Interface` objects, `Constraint` objects with an
+//! SBOL3 restriction or features of a `ModuleDefinition`, are embedded as SBOL3 triples referred
+//! to by `backport:` properties, as are SBOL3 properties without an SBOL2 equivalent (ie: the
+//! `orientation` of a feature or the `order` of a `Location`). Converting a document into SBOL2
+//! and back therefore gives the same document.
+
+use std::collections::{HashMap, HashSet};
+use std::io::{Read, Write};
+
+use url::Url;
+
+use super::rdf_xml::{Graph, PROV_DERIVED_FROM, PROV_GENERATED_BY, RDF_TYPE};
+use super::sbol3::{self, SBOL3_RDF_NS};
+use super::Common;
+use crate::identified::child_uri;
+use crate::ontologies::{
+    ComponentRole, ComponentTypeOntology, Encoding, InteractionType, Ontology, Orientation,
+    ParticipationRole, Restriction, RoleIntegration, SBOL3_NS,
+};
+use crate::{
+    sanitize_display_id, Activity, Annotation, Component, ComponentReference, ComponentType,
+    Constraint, Cut, Document, EntireSequence, Identified, Interaction, Interface, Location,
+    Participation, Range, SbolError, Sequence, SequenceFeature, SubComponent, Usage,
+};
+
+pub const SBOL2_NS: &str = "http://sbols.org/v2#";
+
+/// Namespace of the properties which embed SBOL3 data into SBOL2 documents
+pub const BACKPORT_NS: &str = "http://sboltools.org/backport#";
+
+const BIOPAX_NS: &str = "http://www.biopax.org/release/biopax-level3.owl#";
+const DCTERMS_NS: &str = "http://purl.org/dc/terms/";
+const DCTERMS_TITLE: &str = "http://purl.org/dc/terms/title";
+const DCTERMS_DESCRIPTION: &str = "http://purl.org/dc/terms/description";
+const PROV_NS: &str = "http://www.w3.org/ns/prov#";
+
+/// PROV-O properties of `Activity` and `Usage` objects, which are not kept as annotations
+const PROV_PROPERTIES: [&str; 6] = [
+    "startedAtTime",
+    "endedAtTime",
+    "qualifiedUsage",
+    "wasInformedBy",
+    "entity",
+    "hadRole",
+];
+
+/// Mapping between SBOL2 encodings and `Encoding` terms
+pub(crate) const ENCODINGS: [(&str, Encoding); 3] = [
+    (
+        "http://www.chem.qmul.ac.uk/iubmb/misc/naseq.html",
+        Encoding::NucleicAcid,
+    ),
+    (
+        "http://www.chem.qmul.ac.uk/iupac/AminoAcid/",
+        Encoding::Protein,
+    ),
+    (
+        "http://www.opensmiles.org/opensmiles.html",
+        Encoding::SMILES,
+    ),
+];
+
+/// Mapping between BioPAX types of SBOL2 and `ComponentTypeOntology` terms
+pub(crate) const BIOPAX_TYPES: [(&str, ComponentTypeOntology); 7] = [
+    ("DnaRegion", ComponentTypeOntology::DNA),
+    ("DnaMolecule", ComponentTypeOntology::DNA),
+    ("RnaRegion", ComponentTypeOntology::RNA),
+    ("RnaMolecule", ComponentTypeOntology::RNA),
+    ("Protein", ComponentTypeOntology::Protein),
+    ("SmallMolecule", ComponentTypeOntology::SimpleChemical),
+    ("Complex", ComponentTypeOntology::NonCovalentComplex),
+];
+
+/// Restrictions which an SBOL2 `SequenceConstraint` can express
+const SEQUENCE_RESTRICTIONS: [Restriction; 4] = [
+    Restriction::Precedes,
+    Restriction::SameOrientationAs,
+    Restriction::OppositeOrientationAs,
+    Restriction::DifferentFrom,
+];
+
+/// SBOL3 classes of `TopLevel` objects, which SBOL2 documents do not have
+const SBOL3_TOP_LEVELS: [&str; 9] = [
+    "Component",
+    "Sequence",
+    "Collection",
+    "CombinatorialDerivation",
+    "Implementation",
+    "Model",
+    "Attachment",
+    "Experiment",
+    "ExperimentalData",
+];
+
+/// SBOL2 classes of `TopLevel` objects which are not converted
+const UNSUPPORTED_TOP_LEVELS: [&str; 7] = [
+    "Collection",
+    "CombinatorialDerivation",
+    "Implementation",
+    "Model",
+    "Attachment",
+    "Experiment",
+    "ExperimentalData",
+];
+
+/// Read an SBOL2 RDF/XML document from `reader` and convert it into SBOL3.
+///
+/// See [`parse`].
+pub fn read<R: Read>(mut reader: R) -> Result<Document, SbolError> {
+    let mut input = String::new();
+    reader.read_to_string(&mut input)?;
+    parse(&input)
+}
+
+/// Parse an SBOL2 RDF/XML document and convert it into SBOL3.
+///
+/// Documents with SBOL3 `TopLevel` objects are rejected, rather than read as an empty document, as
+/// are documents with SBOL2 `TopLevel` objects which are not converted (ie: `Collection`), rather
+/// than dropping them.
+pub fn parse(input: &str) -> Result<Document, SbolError> {
+    let graph = Graph::parse(input)?;
+    if let Some(subject) = graph.subjects().iter().find(|subject| {
+        SBOL3_TOP_LEVELS
+            .iter()
+            .any(|class| graph.has_type(subject, &sbol3::sbol3(class)))
+    }) {
+        return Err(SbolError::Parse(format!(
+            "{subject} is an SBOL3 object, expected an SBOL2 document"
+        )));
+    }
+    let unsupported: Vec<String> = graph
+        .subjects()
+        .iter()
+        .filter(|subject| {
+            UNSUPPORTED_TOP_LEVELS
+                .iter()
+                .any(|class| graph.has_type(subject, &sbol2(class)))
+        })
+        .map(ToString::to_string)
+        .collect();
+    if !unsupported.is_empty() {
+        return Err(SbolError::Parse(format!(
+            "Unsupported SBOL2 TopLevel objects: {}",
+            unsupported.join(", ")
+        )));
+    }
+    let mut converter = Converter {
+        graph: &graph,
+        uris: HashMap::new(),
+    };
+    converter.map_uris()?;
+
+    let mut document = Document::new();
+    for sequence in graph.subjects_of_type(&sbol2("Sequence")) {
+        document.sequences.push(converter.sequence(sequence)?);
+    }
+    for subject in graph.subjects() {
+        if graph.has_type(subject, &prov("Activity")) {
+            document.activities.push(converter.activity(subject)?);
+        } else if graph.has_type(subject, &sbol2("ComponentDefinition")) {
+            document
+                .components
+                .push(converter.component_definition(subject)?);
+        } else if graph.has_type(subject, &sbol2("ModuleDefinition")) {
+            document
+                .components
+                .push(converter.module_definition(subject)?);
+        }
+    }
+    Ok(document)
+}
+
+/// Convert `document` into SBOL2 and write it as RDF/XML into `writer`.
+///
+/// See [`to_string`].
+pub fn write<W: Write>(document: &Document, mut writer: W) -> Result<(), SbolError> {
+    writer.write_all(to_string(document)?.as_bytes())?;
+    Ok(())
+}
+
+/// Convert `document` into SBOL2 and serialize it as RDF/XML.
+pub fn to_string(document: &Document) -> Result<String, SbolError> {
+    let mut exporter = Exporter::new(document)?;
+    for sequence in &document.sequences {
+        exporter.sequence(sequence)?;
+    }
+    for component in &document.components {
+        if is_module(component) {
+            exporter.module_definition(component)?;
+        } else {
+            exporter.component_definition(component)?;
+        }
+    }
+    for activity in &document.activities {
+        exporter.activity(activity)?;
+    }
+    exporter.graph.to_rdf_xml(&[
+        ("sbol", SBOL2_NS),
+        ("dcterms", DCTERMS_NS),
+        ("prov", PROV_NS),
+        ("backport", BACKPORT_NS),
+        ("sbol3", SBOL3_RDF_NS),
+    ])
+}
+
+/// URI of a term of the SBOL2 namespace
+pub(crate) fn sbol2(local: &str) -> String {
+    format!("{SBOL2_NS}{local}")
+}
+
+/// URI of a term of the backport namespace
+fn backport(local: &str) -> String {
+    format!("{BACKPORT_NS}{local}")
+}
+
+/// URI of a term of the PROV-O namespace
+pub(crate) fn prov(local: &str) -> String {
+    format!("{PROV_NS}{local}")
+}
+
+/// Part of `uri` after its namespace `ns`, ie: `inline` for `http://sbols.org/v2#inline`
+fn local_name<'a>(uri: &'a Url, ns: &str) -> Option<&'a str> {
+    uri.as_str().strip_prefix(ns)
+}
+
+/// SBOL3 term of the SBOL3 namespace with the same local name as an SBOL2 term
+fn sbol3_term<T: From<Url>>(uri: &Url) -> Result<T, SbolError> {
+    match local_name(uri, SBOL2_NS) {
+        Some(local) => Ok(Url::parse(&format!("{SBOL3_NS}{local}"))?.into()),
+        None => Ok(uri.clone().into()),
+    }
+}
+
+/// SBOL2 term with the same local name as a term of the SBOL3 namespace
+fn sbol2_term(term: &impl Ontology) -> Result<Url, SbolError> {
+    let uri = term.uri()?;
+    match local_name(&uri, SBOL3_NS) {
+        Some(local) => Ok(Url::parse(&sbol2(local))?),
+        None => Ok(uri),
+    }
+}
+
+/// Last segment of the path of `uri`
+fn last_segment(uri: &Url) -> &str {
+    uri.path_segments()
+        .and_then(|mut s| s.next_back())
+        .unwrap_or_default()
+}
+
+/// Namespace of a `TopLevel` identified by `uri` by default, ie: its URI without the last segment
+fn default_namespace(uri: &Url) -> Result<Url, SbolError> {
+    let (namespace, _) = uri
+        .as_str()
+        .trim_end_matches('/')
+        .rsplit_once('/')
+        .ok_or_else(|| SbolError::Validation(format!("{uri} has no namespace")))?;
+    Ok(Url::parse(namespace)?)
+}
+
+/// `common` without the PROV-O properties of `Activity` and `Usage` objects
+fn prov_common(mut common: Common) -> Common {
+    common.annotations.retain(|annotation| {
+        !PROV_PROPERTIES
+            .iter()
+            .any(|property| annotation.property.as_str() == prov(property))
+    });
+    common
+}
+
+/// Whether `component` is converted into a `ModuleDefinition`
+fn is_module(component: &Component) -> bool {
+    component.r#type == [ComponentType::Type(ComponentTypeOntology::FunctionalEntity)]
+}
+
+struct Converter<'a> {
+    graph: &'a Graph,
+    /// SBOL3 URI of each SBOL2 object which may be referred to
+    uris: HashMap<Url, Url>,
+}
+
+impl Converter<'_> {
+    fn uris(&self, subject: &Url, property: &str) -> Vec<Url> {
+        self.graph.uris(subject, &sbol2(property))
+    }
+
+    fn uri(&self, subject: &Url, property: &str) -> Option<Url> {
+        self.graph.uri(subject, &sbol2(property))
+    }
+
+    fn literal(&self, subject: &Url, property: &str) -> Option<String> {
+        self.graph.literal(subject, &sbol2(property))
+    }
+
+    fn required_uri(&self, subject: &Url, property: &str) -> Result<Url, SbolError> {
+        self.uri(subject, property).ok_or_else(|| {
+            SbolError::Validation(format!("{subject} has no sbol2:{property} property"))
+        })
+    }
+
+    fn display_id(&self, subject: &Url) -> String {
+        match self.literal(subject, "displayId") {
+            Some(display_id) => display_id,
+            None => sanitize_display_id(last_segment(&self.strip_version(subject))),
+        }
+    }
+
+    /// `subject` without its trailing version segment, ie: `https://example.org/part/1`
+    /// becomes `https://example.org/part`.
+    ///
+    /// For objects outside the document, any trailing segment which looks like a version is
+    /// removed.
+    fn strip_version(&self, subject: &Url) -> Url {
+        let version = match self.literal(subject, "version") {
+            Some(version) => Some(version),
+            None if self.graph.properties(subject).is_empty() => {
+                let last = last_segment(subject);
+                let looks_like_version = last.starts_with(|c: char| c.is_ascii_digit())
+                    && last.chars().all(|c| c.is_ascii_digit() || c == '.');
+                looks_like_version.then(|| last.to_string())
+            }
+            None => None,
+        };
+        version
+            .and_then(|version| subject.as_str().strip_suffix(&format!("/{version}")))
+            .and_then(|uri| Url::parse(uri).ok())
+            .unwrap_or_else(|| subject.clone())
+    }
+
+    /// SBOL3 URI of the SBOL2 object identified by `subject`
+    fn sbol3_uri(&self, subject: &Url) -> Url {
+        self.uris
+            .get(subject)
+            .cloned()
+            .unwrap_or_else(|| self.strip_version(subject))
+    }
+
+    /// SBOL3 URI of a child object of `parent`: its `persistentIdentity` if it is a direct child
+    /// of `parent`, otherwise `parent/display_id`.
+    fn child_identity(&self, subject: &Url, parent: &Url) -> Result<Url, SbolError> {
+        if let Some(uri) = self.uri(subject, "persistentIdentity") {
+            if default_namespace(&uri).is_ok_and(|namespace| &namespace == parent) {
+                return Ok(uri);
+            }
+        }
+        child_uri(parent, &self.display_id(subject))
+    }
+
+    /// Map each `TopLevel` to its `persistentIdentity`, and each child object to its SBOL3
+    /// child URI.
+    fn map_uris(&mut self) -> Result<(), SbolError> {
+        for class in ["Sequence", "ComponentDefinition", "ModuleDefinition"] {
+            for top_level in self.graph.subjects_of_type(&sbol2(class)) {
+                let uri = self
+                    .uri(top_level, "persistentIdentity")
+                    .unwrap_or_else(|| self.strip_version(top_level));
+                self.uris.insert(top_level.clone(), uri);
+            }
+        }
+        for activity in self.graph.subjects_of_type(&prov("Activity")) {
+            let uri = self
+                .uri(activity, "persistentIdentity")
+                .unwrap_or_else(|| self.strip_version(activity));
+            for usage in self.graph.uris(activity, &prov("qualifiedUsage")) {
+                let usage_uri = self.child_identity(&usage, &uri)?;
+                self.uris.insert(usage, usage_uri);
+            }
+            self.uris.insert(activity.clone(), uri);
+        }
+
+        let children = [
+            "component",
+            "sequenceAnnotation",
+            "sequenceConstraint",
+            "functionalComponent",
+            "module",
+            "interaction",
+        ];
+        for class in ["ComponentDefinition", "ModuleDefinition"] {
+            for definition in self.graph.subjects_of_type(&sbol2(class)) {
+                let parent = self.sbol3_uri(definition);
+                for property in children {
+                    for child in self.uris(definition, property) {
+                        let uri = self.child_identity(&child, &parent)?;
+                        for maps_to in self.uris(&child, "mapsTo") {
+                            let maps_to_uri = self.child_identity(&maps_to, &parent)?;
+                            self.uris.insert(maps_to, maps_to_uri);
+                        }
+                        for participation in self.uris(&child, "participation") {
+                            let participation_uri = self.child_identity(&participation, &uri)?;
+                            self.uris.insert(participation, participation_uri);
+                        }
+                        self.uris.insert(child, uri);
+                    }
+                }
+                // Annotations which locate a `Component` are merged into its `SubComponent`
+                for annotation in self.uris(definition, "sequenceAnnotation") {
+                    if let Some(component) = self.uri(&annotation, "component") {
+                        let uri = self.sbol3_uri(&component);
+                        self.uris.insert(annotation, uri);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn common(&self, subject: &Url) -> Common {
+        let known = [
+            RDF_TYPE,
+            DCTERMS_TITLE,
+            DCTERMS_DESCRIPTION,
+            PROV_DERIVED_FROM,
+            PROV_GENERATED_BY,
+        ];
+        let annotations = self
+            .graph
+            .properties(subject)
+            .iter()
+            .filter(|(property, _)| {
+                let property = property.as_str();
+                !property.starts_with(SBOL2_NS)
+                    && !property.starts_with(BACKPORT_NS)
+                    && !known.contains(&property)
+            })
+            .map(|(property, value)| Annotation {
+                property: property.clone(),
+                value: value.clone(),
+            })
+            .collect();
+        Common {
+            identity: self.sbol3_uri(subject),
+            display_id: Some(self.display_id(subject)),
+            name: self.graph.literal(subject, DCTERMS_TITLE),
+            description: self.graph.literal(subject, DCTERMS_DESCRIPTION),
+            derived_from: self
+                .graph
+                .uris(subject, PROV_DERIVED_FROM)
+                .iter()
+                .map(|uri| self.sbol3_uri(uri))
+                .collect(),
+            generated_by: self
+                .graph
+                .uris(subject, PROV_GENERATED_BY)
+                .iter()
+                .map(|uri| self.sbol3_uri(uri))
+                .collect(),
+            has_measure: self.graph.uris(subject, &backport("hasMeasure")),
+            annotations,
+        }
+    }
+
+    /// `Common` properties of a `TopLevel`, which also records its SBOL2 version
+    fn top_level_common(&self, subject: &Url) -> Result<Common, SbolError> {
+        let mut common = self.common(subject);
+        if let Some(version) = self.literal(subject, "version") {
+            common
+                .annotations
+                .push(Annotation::literal(Url::parse(&sbol2("version"))?, version));
+        }
+        Ok(common)
+    }
+
+    /// Namespace of a `TopLevel` whose SBOL3 URI is `identity`
+    fn namespace(&self, subject: &Url, identity: &Url) -> Result<Url, SbolError> {
+        match self.graph.uri(subject, &backport("hasNamespace")) {
+            Some(namespace) => Ok(namespace),
+            None => default_namespace(identity),
+        }
+    }
+
+    fn attachments(&self, subject: &Url) -> Vec<Url> {
+        self.uris(subject, "attachment")
+            .iter()
+            .map(|uri| self.sbol3_uri(uri))
+            .collect()
+    }
+
+    fn sequence(&self, subject: &Url) -> Result<Sequence, SbolError> {
+        let common = self.top_level_common(subject)?;
+        let namespace = self.namespace(subject, &common.identity)?;
+        let mut sequence = Sequence::new(namespace, common.display_id())?;
+        set_common!(sequence, common);
+        sequence.has_attachment = self.attachments(subject);
+        sequence.elements = self.literal(subject, "elements");
+        sequence.encoding = self.uri(subject, "encoding").map(|uri| {
+            ENCODINGS
+                .iter()
+                .find(|(sbol2, _)| *sbol2 == uri.as_str())
+                .map(|(_, encoding)| encoding.clone())
+                .unwrap_or_else(|| uri.into())
+        });
+        Ok(sequence)
+    }
+
+    fn activity(&self, subject: &Url) -> Result<Activity, SbolError> {
+        let common = prov_common(self.top_level_common(subject)?);
+        let namespace = self.namespace(subject, &common.identity)?;
+        let mut activity = Activity::new(namespace, common.display_id())?;
+        set_common!(activity, common);
+        activity.has_attachment = self.attachments(subject);
+        activity.r#type = self.uris(subject, "type");
+        activity.start_time = self.graph.literal(subject, &prov("startedAtTime"));
+        activity.end_time = self.graph.literal(subject, &prov("endedAtTime"));
+        for subject in self.graph.uris(subject, &prov("qualifiedUsage")) {
+            let common = prov_common(self.common(&subject));
+            let entity = self.graph.uri(&subject, &prov("entity")).ok_or_else(|| {
+                SbolError::Validation(format!("{subject} has no prov:entity property"))
+            })?;
+            let entity = self.sbol3_uri(&entity);
+            let mut usage = Usage::new(&activity.identity, common.display_id(), entity)?;
+            set_common!(usage, common);
+            usage.role = self.graph.uris(&subject, &prov("hadRole"));
+            activity.has_usage.push(usage);
+        }
+        activity.informed_by = self
+            .graph
+            .uris(subject, &prov("wasInformedBy"))
+            .iter()
+            .map(|uri| self.sbol3_uri(uri))
+            .collect();
+        Ok(activity)
+    }
+
+    fn new_component(&self, subject: &Url) -> Result<Component, SbolError> {
+        let common = self.top_level_common(subject)?;
+        let namespace = self.namespace(subject, &common.identity)?;
+        let mut component = Component::new(namespace, common.display_id())?;
+        set_common!(component, common);
+        component.has_attachment = self.attachments(subject);
+        component.role = self
+            .uris(subject, "role")
+            .into_iter()
+            .map(ComponentRole::from)
+            .collect();
+        Ok(component)
+    }
+
+    fn component_definition(&self, subject: &Url) -> Result<Component, SbolError> {
+        let mut component = self.new_component(subject)?;
+        component.r#type = self
+            .uris(subject, "type")
+            .into_iter()
+            .map(|uri| {
+                let biopax = local_name(&uri, BIOPAX_NS).and_then(|local| {
+                    BIOPAX_TYPES
+                        .iter()
+                        .find(|(name, _)| *name == local)
+                        .map(|(_, term)| ComponentType::Type(term.clone()))
+                });
+                biopax.unwrap_or_else(|| uri.into())
+            })
+            .collect();
+        component.has_sequence = self
+            .uris(subject, "sequence")
+            .iter()
+            .map(|uri| self.sbol3_uri(uri))
+            .collect();
+        let sequence = component.has_sequence.first().cloned();
+
+        for child in self.uris(subject, "component") {
+            let sub_component = self.sub_component(&child, &mut component)?;
+            component.sub_components.push(sub_component);
+        }
+        for annotation in self.uris(subject, "sequenceAnnotation") {
+            self.sequence_annotation(&annotation, sequence.as_ref(), &mut component)?;
+        }
+        for constraint in self.uris(subject, "sequenceConstraint") {
+            let common = self.common(&constraint);
+            let mut converted = Constraint::new(
+                &component.identity,
+                common.display_id(),
+                sbol3_term(&self.required_uri(&constraint, "restriction")?)?,
+                self.sbol3_uri(&self.required_uri(&constraint, "subject")?),
+                self.sbol3_uri(&self.required_uri(&constraint, "object")?),
+            )?;
+            set_common!(converted, common);
+            component.has_constraint.push(converted);
+        }
+        self.backported(subject, &mut component)?;
+        Ok(component)
+    }
+
+    fn module_definition(&self, subject: &Url) -> Result<Component, SbolError> {
+        let mut component = self.new_component(subject)?;
+        component.r#type = vec![ComponentType::Type(ComponentTypeOntology::FunctionalEntity)];
+        component.has_model = self
+            .uris(subject, "model")
+            .iter()
+            .map(|uri| self.sbol3_uri(uri))
+            .collect();
+
+        // Functional components and modules are converted in order of appearance
+        let children: Vec<(bool, Url)> = self
+            .graph
+            .properties(subject)
+            .iter()
+            .filter_map(|(property, value)| match local_name(property, SBOL2_NS) {
+                Some("functionalComponent") => Some((true, value.as_uri()?.clone())),
+                Some("module") => Some((false, value.as_uri()?.clone())),
+                _ => None,
+            })
+            .collect();
+        let mut interface = Interface::new(&component.identity, "interface")?;
+        for (functional, child) in children {
+            let sub_component = self.sub_component(&child, &mut component)?;
+            if functional {
+                let uri = sub_component.identity.clone();
+                let direction = self.uri(&child, "direction");
+                match direction.as_ref().and_then(|d| local_name(d, SBOL2_NS)) {
+                    Some("in") => interface.input.push(uri),
+                    Some("out") => interface.output.push(uri),
+                    Some("inout") => {
+                        interface.input.push(uri.clone());
+                        interface.output.push(uri);
+                    }
+                    _ => {
+                        let access = self.uri(&child, "access");
+                        if access.is_some_and(|access| access.as_str() == sbol2("public")) {
+                            interface.nondirectional.push(uri);
+                        }
+                    }
+                }
+            }
+            component.sub_components.push(sub_component);
+        }
+        let explicitly_none = self
+            .graph
+            .literal(subject, &backport("noInterface"))
+            .is_some_and(|value| value == "true");
+        if !(explicitly_none
+            || interface.input.is_empty()
+                && interface.output.is_empty()
+                && interface.nondirectional.is_empty())
+        {
+            component.has_interface = Some(interface);
+        }
+
+        for interaction in self.uris(subject, "interaction") {
+            let interaction = self.interaction(&interaction, &component.identity)?;
+            component.has_interaction.push(interaction);
+        }
+        self.backported(subject, &mut component)?;
+        Ok(component)
+    }
+
+    /// Add the SBOL3 objects embedded into `subject` by `backport:` properties to `component`
+    fn backported(&self, subject: &Url, component: &mut Component) -> Result<(), SbolError> {
+        for feature in self.graph.uris(subject, &backport("hasFeature")) {
+            sbol3::read_feature(self.graph, &feature, component)?;
+        }
+        for constraint in self.graph.uris(subject, &backport("hasConstraint")) {
+            let constraint = sbol3::read_constraint(self.graph, &constraint)?;
+            component.has_constraint.push(constraint);
+        }
+        for interaction in self.graph.uris(subject, &backport("hasInteraction")) {
+            let interaction = sbol3::read_interaction(self.graph, &interaction)?;
+            component.has_interaction.push(interaction);
+        }
+        if let Some(interface) = self.graph.uri(subject, &backport("hasInterface")) {
+            component.has_interface = Some(sbol3::read_interface(self.graph, &interface)?);
+        }
+        let sequences = self.graph.uris(subject, &backport("hasSequence"));
+        component.has_sequence.extend(sequences);
+        let models = self.graph.uris(subject, &backport("hasModel"));
+        component.has_model.extend(models);
+        Ok(())
+    }
+
+    /// Convert a `Component`, `FunctionalComponent` or `Module` into a `SubComponent` of
+    /// `parent`, along with the references and constraints of its `MapsTo` objects.
+    fn sub_component(
+        &self,
+        subject: &Url,
+        parent: &mut Component,
+    ) -> Result<SubComponent, SbolError> {
+        let common = self.common(subject);
+        let definition = self.required_uri(subject, "definition")?;
+        let instance_of = self.sbol3_uri(&definition);
+        let mut sub_component =
+            SubComponent::new(&parent.identity, common.display_id(), instance_of)?;
+        set_common!(sub_component, common);
+        sub_component.role = self
+            .uris(subject, "role")
+            .into_iter()
+            .chain(self.graph.uris(subject, &backport("role")))
+            .map(ComponentRole::from)
+            .collect();
+        sub_component.role_integration = match self.uri(subject, "roleIntegration") {
+            Some(uri) => Some(RoleIntegration::try_from(sbol3_term::<Url>(&uri)?)?),
+            None => self
+                .graph
+                .uri(subject, &backport("roleIntegration"))
+                .map(RoleIntegration::try_from)
+                .transpose()?,
+        };
+        sub_component.orientation = self
+            .graph
+            .uri(subject, &backport("orientation"))
+            .map(Orientation::try_from)
+            .transpose()?;
+        if let Some(access) = self.uri(subject, "access") {
+            if access.as_str() != sbol2("public") {
+                sub_component
+                    .annotations
+                    .push(Annotation::uri(Url::parse(&sbol2("access"))?, access));
+            }
+        }
+
+        // Source locations refer to the sequence of the definition
+        let source_sequence = self
+            .uri(&definition, "sequence")
+            .map(|sequence| self.sbol3_uri(&sequence));
+        for location in self.uris(subject, "sourceLocation") {
+            let location =
+                self.location(&location, &sub_component.identity, source_sequence.as_ref())?;
+            sub_component.source_location.push(location);
+        }
+        for location in self.graph.uris(subject, &backport("hasLocation")) {
+            let location = sbol3::read_location(self.graph, &location)?;
+            sub_component.has_location.push(location);
+        }
+        for location in self.graph.uris(subject, &backport("sourceLocation")) {
+            let location = sbol3::read_location(self.graph, &location)?;
+            sub_component.source_location.push(location);
+        }
+
+        for maps_to in self.uris(subject, "mapsTo") {
+            let common = self.common(&maps_to);
+            let display_id = format!("{}_constraint", common.display_id());
+            let local = self.sbol3_uri(&self.required_uri(&maps_to, "local")?);
+            let remote = self.sbol3_uri(&self.required_uri(&maps_to, "remote")?);
+            let mut reference = ComponentReference::new(
+                &parent.identity,
+                common.display_id(),
+                sub_component.identity.clone(),
+                remote,
+            )?;
+            set_common!(reference, common);
+
+            let refinement = self.uri(&maps_to, "refinement");
+            let refinement_name = refinement.as_ref().and_then(|r| local_name(r, SBOL2_NS));
+            let merge = refinement_name == Some("merge");
+            let (restriction, subject, object) = match refinement_name {
+                Some("useRemote") => (Restriction::Replaces, reference.identity.clone(), local),
+                Some("useLocal") => (Restriction::Replaces, local, reference.identity.clone()),
+                _ => (
+                    Restriction::VerifyIdentical,
+                    local,
+                    reference.identity.clone(),
+                ),
+            };
+            let mut constraint =
+                Constraint::new(&parent.identity, &display_id, restriction, subject, object)?;
+            // Merging has no SBOL3 equivalent
+            if let Some(refinement) = refinement.filter(|_| merge) {
+                constraint.annotations.push(Annotation::uri(
+                    Url::parse(&sbol2("refinement"))?,
+                    refinement,
+                ));
+            }
+            parent.component_references.push(reference);
+            parent.has_constraint.push(constraint);
+        }
+        Ok(sub_component)
+    }
+
+    /// Convert a `SequenceAnnotation` into a `SequenceFeature` of `component`, or merge it into
+    /// the `SubComponent` that it locates.
+    fn sequence_annotation(
+        &self,
+        subject: &Url,
+        sequence: Option<&Url>,
+        component: &mut Component,
+    ) -> Result<(), SbolError> {
+        let identity = self.sbol3_uri(subject);
+        let mut locations = vec![];
+        for location in self.uris(subject, "location") {
+            locations.push(self.location(&location, &identity, sequence)?);
+        }
+        let roles = self
+            .uris(subject, "role")
+            .into_iter()
+            .map(ComponentRole::from);
+
+        match self.uri(subject, "component") {
+            Some(located) => {
+                let generated = child_uri(
+                    &component.identity,
+                    &format!("{}_annotation", self.display_id(&located)),
+                )?;
+                let sub_component = component
+                    .sub_components
+                    .iter_mut()
+                    .find(|sub_component| sub_component.identity == identity)
+                    .ok_or_else(|| {
+                        SbolError::Validation(format!(
+                            "{subject} locates a Component which is not part of {}",
+                            component.identity
+                        ))
+                    })?;
+                sub_component.has_location.extend(locations);
+                sub_component.role.extend(roles);
+                if self.strip_version(subject) != generated {
+                    sub_component.annotations.push(Annotation::uri(
+                        Url::parse(&sbol2("sequenceAnnotation"))?,
+                        subject.clone(),
+                    ));
+                }
+            }
+            None => {
+                let common = self.common(subject);
+                let mut feature = SequenceFeature::new(&component.identity, common.display_id())?;
+                set_common!(feature, common);
+                feature.role = roles.collect();
+                feature.orientation = self
+                    .graph
+                    .uri(subject, &backport("orientation"))
+                    .map(Orientation::try_from)
+                    .transpose()?;
+                feature.has_location = locations;
+                component.sequence_features.push(feature);
+            }
+        }
+        Ok(())
+    }
+
+    fn location(
+        &self,
+        subject: &Url,
+        parent: &Url,
+        sequence: Option<&Url>,
+    ) -> Result<Location, SbolError> {
+        let mut common = self.common(subject);
+        common.identity = self.child_identity(subject, parent)?;
+        let has_sequence = match self.uri(subject, "sequence") {
+            Some(sequence) => self.sbol3_uri(&sequence),
+            None => sequence.cloned().ok_or_else(|| {
+                SbolError::Validation(format!("{subject} does not refer to a Sequence"))
+            })?,
+        };
+        let orientation = match (
+            self.graph.uri(subject, &backport("orientation")),
+            self.uri(subject, "orientation"),
+        ) {
+            (Some(uri), _) => Some(uri.try_into()?),
+            (None, Some(uri)) => Some(match local_name(&uri, SBOL2_NS) {
+                Some("inline") => Orientation::Inline,
+                Some("reverseComplement") => Orientation::ReverseComplement,
+                _ => uri.try_into()?,
+            }),
+            (None, None) => None,
+        };
+        let invalid = |property: &str, value: &str| {
+            SbolError::Parse(format!("{subject} has an invalid {property}: {value:?}"))
+        };
+        let position = |property: &str| {
+            let value = self.literal(subject, property).unwrap_or_default();
+            value
+                .trim()
+                .parse::<usize>()
+                .map_err(|_| invalid(property, &value))
+        };
+        let order = self
+            .graph
+            .literal(subject, &backport("order"))
+            .map(|order| order.trim().parse().map_err(|_| invalid("order", &order)))
+            .transpose()?;
+
+        let location = if self.graph.has_type(subject, &sbol2("Range")) {
+            let mut range = Range::new(
+                parent,
+                common.display_id(),
+                has_sequence,
+                position("start")?,
+                position("end")?,
+            )?;
+            range.orientation = orientation;
+            range.order = order;
+            set_common!(range, common);
+            Location::Range(range)
+        } else if self.graph.has_type(subject, &sbol2("Cut")) {
+            let mut cut = Cut::new(parent, common.display_id(), has_sequence, position("at")?)?;
+            cut.orientation = orientation;
+            cut.order = order;
+            set_common!(cut, common);
+            Location::Cut(cut)
+        } else if self.graph.has_type(subject, &sbol2("GenericLocation")) {
+            let mut entire = EntireSequence::new(parent, common.display_id(), has_sequence)?;
+            entire.orientation = orientation;
+            entire.order = order;
+            set_common!(entire, common);
+            Location::EntireSequence(entire)
+        } else {
+            return Err(SbolError::Parse(format!(
+                "{subject} is not a known Location"
+            )));
+        };
+        Ok(location)
+    }
+
+    fn interaction(&self, subject: &Url, parent: &Url) -> Result<Interaction, SbolError> {
+        let common = self.common(subject);
+        let mut interaction = Interaction::new(parent, common.display_id())?;
+        set_common!(interaction, common);
+        interaction.r#type = self
+            .uris(subject, "type")
+            .into_iter()
+            .map(InteractionType::from)
+            .collect();
+        for participation in self.uris(subject, "participation") {
+            let common = self.common(&participation);
+            let participant = self.sbol3_uri(&self.required_uri(&participation, "participant")?);
+            let mut converted =
+                Participation::new(&interaction.identity, common.display_id(), participant)?;
+            set_common!(converted, common);
+            converted.role = self
+                .uris(&participation, "role")
+                .into_iter()
+                .map(ParticipationRole::from)
+                .collect();
+            interaction.has_participation.push(converted);
+        }
+        Ok(interaction)
+    }
+}
+
+/// `ComponentReference` and `Constraint` pair which an SBOL2 `MapsTo` can express
+struct MapsTo<'a> {
+    reference: &'a ComponentReference,
+    constraint: &'a Constraint,
+    local: &'a Url,
+    refinement: Url,
+}
+
+impl<'a> MapsTo<'a> {
+    /// Every pair of `component` which matches the conversion of a `MapsTo`: a reference into a
+    /// `SubComponent`, and a plain constraint named after it which either replaces or is
+    /// identical to another `SubComponent`.
+    fn find_all(component: &'a Component) -> Vec<Self> {
+        let sub_components: HashSet<&Url> = component
+            .sub_components
+            .iter()
+            .map(|sub_component| &sub_component.identity)
+            .collect();
+        let refinement_property = sbol2("refinement");
+        component
+            .component_references
+            .iter()
+            .filter(|reference| {
+                sub_components.contains(&reference.in_child_of)
+                    && reference.role.is_empty()
+                    && reference.orientation.is_none()
+            })
+            .filter_map(|reference| {
+                let display_id = format!("{}_constraint", display_id(reference));
+                let identity = child_uri(&component.identity, &display_id).ok()?;
+                let constraint = component
+                    .has_constraint
+                    .iter()
+                    .find(|constraint| constraint.identity == identity)?;
+                let plain = constraint.display_id.as_deref() == Some(display_id.as_str())
+                    && constraint.name.is_none()
+                    && constraint.description.is_none()
+                    && constraint.derived_from.is_empty()
+                    && constraint.generated_by.is_empty()
+                    && constraint.has_measure.is_empty();
+                let merge = match constraint.annotations.as_slice() {
+                    [] => false,
+                    [annotation] if annotation.property.as_str() == refinement_property => {
+                        annotation.value.as_str() == sbol2("merge")
+                    }
+                    _ => return None,
+                };
+                let (local, refinement) = match constraint.restriction {
+                    Restriction::Replaces if constraint.subject == reference.identity => {
+                        (&constraint.object, "useRemote")
+                    }
+                    Restriction::Replaces if constraint.object == reference.identity => {
+                        (&constraint.subject, "useLocal")
+                    }
+                    Restriction::VerifyIdentical if constraint.object == reference.identity => {
+                        (&constraint.subject, "verifyIdentical")
+                    }
+                    _ => return None,
+                };
+                let refinement = if merge { "merge" } else { refinement };
+                let valid = plain
+                    && sub_components.contains(local)
+                    && (!merge || constraint.restriction == Restriction::VerifyIdentical);
+                if !valid {
+                    return None;
+                }
+                Some(MapsTo {
+                    reference,
+                    constraint,
+                    local,
+                    refinement: Url::parse(&sbol2(refinement)).ok()?,
+                })
+            })
+            .collect()
+    }
+}
+
+/// `display_id` of `object`, or the last segment of its URI
+fn display_id(object: &dyn Identified) -> String {
+    object
+        .display_id()
+        .unwrap_or_else(|| last_segment(&object.identity()).to_string())
+}
+
+/// SBOL2 version of `object`, as recorded by its `sbol2:version` annotation
+fn version(object: &dyn Identified) -> Option<String> {
+    object
+        .annotations()
+        .into_iter()
+        .find(|annotation| annotation.property.as_str() == sbol2("version"))
+        .map(|annotation| annotation.value.as_str().to_string())
+}
+
+/// URI of the SBOL2 `version` of an object identified by `uri`
+fn versioned(uri: &Url, version: Option<&str>) -> Result<Url, SbolError> {
+    match version {
+        Some(version) => child_uri(uri, version),
+        None => Ok(uri.clone()),
+    }
+}
+
+struct Exporter<'a> {
+    graph: Graph,
+    /// SBOL2 URI of each SBOL3 object of the document
+    uris: HashMap<Url, Url>,
+    /// `Component` objects which become `ModuleDefinition` objects
+    modules: HashSet<&'a Url>,
+}
+
+impl<'a> Exporter<'a> {
+    fn new(document: &'a Document) -> Result<Self, SbolError> {
+        let mut uris = HashMap::new();
+        for sequence in &document.sequences {
+            let version = version(sequence);
+            let uri = versioned(&sequence.identity, version.as_deref())?;
+            uris.insert(sequence.identity.clone(), uri);
+        }
+        for activity in &document.activities {
+            let version = version(activity);
+            let usages = activity.has_usage.iter().map(|usage| &usage.identity);
+            for object in usages.chain([&activity.identity]) {
+                let uri = versioned(object, version.as_deref())?;
+                uris.insert(object.clone(), uri);
+            }
+        }
+        for component in &document.components {
+            let mut objects = vec![component.identity.clone()];
+            for sub_component in &component.sub_components {
+                objects.push(sub_component.identity.clone());
+                let locations = sub_component
+                    .has_location
+                    .iter()
+                    .chain(&sub_component.source_location);
+                objects.extend(locations.map(Identified::identity));
+            }
+            for feature in &component.sequence_features {
+                objects.push(feature.identity.clone());
+                objects.extend(feature.has_location.iter().map(Identified::identity));
+            }
+            for reference in &component.component_references {
+                objects.push(reference.identity.clone());
+            }
+            for constraint in &component.has_constraint {
+                objects.push(constraint.identity.clone());
+            }
+            for interaction in &component.has_interaction {
+                objects.push(interaction.identity.clone());
+                let participations = interaction.has_participation.iter();
+                objects.extend(participations.map(|participation| participation.identity.clone()));
+            }
+            // Children share the version of their `TopLevel`
+            let version = version(component);
+            for object in objects {
+                let uri = versioned(&object, version.as_deref())?;
+                uris.insert(object, uri);
+            }
+        }
+        Ok(Self {
+            graph: Graph::default(),
+            uris,
+            modules: document
+                .components
+                .iter()
+                .filter(|component| is_module(component))
+                .map(|component| &component.identity)
+                .collect(),
+        })
+    }
+
+    /// SBOL2 URI of the SBOL3 object identified by `uri`
+    fn sbol2_uri(&self, uri: &Url) -> Url {
+        self.uris.get(uri).cloned().unwrap_or_else(|| uri.clone())
+    }
+
+    fn insert_uri(&mut self, subject: &Url, property: &str, object: &Url) -> Result<(), SbolError> {
+        let object = self.sbol2_uri(object);
+        self.graph.insert_uri(subject, &sbol2(property), &object)
+    }
+
+    fn insert_backport(
+        &mut self,
+        subject: &Url,
+        property: &str,
+        object: &Url,
+    ) -> Result<(), SbolError> {
+        self.graph.insert_uri(subject, &backport(property), object)
+    }
+
+    /// Write the properties shared by every SBOL2 object, returning its SBOL2 URI.
+    ///
+    /// Annotations of the SBOL2 namespace are skipped: they are written back as the SBOL2
+    /// properties they were converted from.
+    fn identified(
+        &mut self,
+        object: &dyn Identified,
+        class: &str,
+        version: Option<&str>,
+    ) -> Result<Url, SbolError> {
+        self.identified_as(object, &Url::parse(&sbol2(class))?, version)
+    }
+
+    /// Write the properties shared by every SBOL2 object whose `class` is not of the SBOL2
+    /// namespace, returning its SBOL2 URI.
+    fn identified_as(
+        &mut self,
+        object: &dyn Identified,
+        class: &Url,
+        version: Option<&str>,
+    ) -> Result<Url, SbolError> {
+        let identity = object.identity();
+        let subject = self.sbol2_uri(&identity);
+        self.graph.insert_uri(&subject, RDF_TYPE, class)?;
+        self.graph
+            .insert_uri(&subject, &sbol2("persistentIdentity"), &identity)?;
+        if let Some(display_id) = object.display_id() {
+            self.graph
+                .insert_literal(&subject, &sbol2("displayId"), display_id)?;
+        }
+        if let Some(version) = version {
+            self.graph
+                .insert_literal(&subject, &sbol2("version"), version)?;
+        }
+        if let Some(name) = object.name() {
+            self.graph.insert_literal(&subject, DCTERMS_TITLE, name)?;
+        }
+        if let Some(description) = object.description() {
+            self.graph
+                .insert_literal(&subject, DCTERMS_DESCRIPTION, description)?;
+        }
+        for uri in object.derived_from() {
+            let uri = self.sbol2_uri(&uri);
+            self.graph.insert_uri(&subject, PROV_DERIVED_FROM, &uri)?;
+        }
+        for uri in object.generated_by() {
+            let uri = self.sbol2_uri(&uri);
+            self.graph.insert_uri(&subject, PROV_GENERATED_BY, &uri)?;
+        }
+        for uri in object.has_measure() {
+            self.insert_backport(&subject, "hasMeasure", &uri)?;
+        }
+        for annotation in object.annotations() {
+            if !annotation.property.as_str().starts_with(SBOL2_NS) {
+                self.graph
+                    .insert(subject.clone(), annotation.property, annotation.value);
+            }
+        }
+        Ok(subject)
+    }
+
+    /// Write the properties shared by every SBOL2 `TopLevel`
+    fn top_level(
+        &mut self,
+        subject: &Url,
+        identity: &Url,
+        namespace: &Url,
+        attachments: &[Url],
+    ) -> Result<(), SbolError> {
+        if default_namespace(identity).ok().as_ref() != Some(namespace) {
+            self.insert_backport(subject, "hasNamespace", namespace)?;
+        }
+        for attachment in attachments {
+            self.insert_uri(subject, "attachment", attachment)?;
+        }
+        Ok(())
+    }
+
+    fn sequence(&mut self, sequence: &Sequence) -> Result<(), SbolError> {
+        let version = version(sequence);
+        let subject = self.identified(sequence, "Sequence", version.as_deref())?;
+        self.top_level(
+            &subject,
+            &sequence.identity,
+            &sequence.namespace,
+            &sequence.has_attachment,
+        )?;
+        if let Some(elements) = &sequence.elements {
+            self.graph
+                .insert_literal(&subject, &sbol2("elements"), elements)?;
+        }
+        if let Some(encoding) = &sequence.encoding {
+            let uri = match ENCODINGS.iter().find(|(_, term)| term == encoding) {
+                Some((sbol2, _)) => Url::parse(sbol2)?,
+                None => encoding.uri()?,
+            };
+            self.graph.insert_uri(&subject, &sbol2("encoding"), &uri)?;
+        }
+        Ok(())
+    }
+
+    fn activity(&mut self, activity: &Activity) -> Result<(), SbolError> {
+        let version = version(activity);
+        let class = Url::parse(&prov("Activity"))?;
+        let subject = self.identified_as(activity, &class, version.as_deref())?;
+        self.top_level(
+            &subject,
+            &activity.identity,
+            &activity.namespace,
+            &activity.has_attachment,
+        )?;
+        for r#type in &activity.r#type {
+            self.graph.insert_uri(&subject, &sbol2("type"), r#type)?;
+        }
+        if let Some(start_time) = &activity.start_time {
+            self.graph
+                .insert_literal(&subject, &prov("startedAtTime"), start_time)?;
+        }
+        if let Some(end_time) = &activity.end_time {
+            self.graph
+                .insert_literal(&subject, &prov("endedAtTime"), end_time)?;
+        }
+        let class = Url::parse(&prov("Usage"))?;
+        for usage in &activity.has_usage {
+            let child = self.identified_as(usage, &class, version.as_deref())?;
+            let entity = self.sbol2_uri(&usage.entity);
+            self.graph.insert_uri(&child, &prov("entity"), &entity)?;
+            for role in &usage.role {
+                self.graph.insert_uri(&child, &prov("hadRole"), role)?;
+            }
+            self.graph
+                .insert_uri(&subject, &prov("qualifiedUsage"), &child)?;
+        }
+        for informed_by in &activity.informed_by {
+            let uri = self.sbol2_uri(informed_by);
+            self.graph
+                .insert_uri(&subject, &prov("wasInformedBy"), &uri)?;
+        }
+        Ok(())
+    }
+
+    /// Write the properties shared by `ComponentDefinition` and `ModuleDefinition` objects
+    fn definition(&mut self, component: &Component, class: &str) -> Result<Url, SbolError> {
+        let version = version(component);
+        let subject = self.identified(component, class, version.as_deref())?;
+        self.top_level(
+            &subject,
+            &component.identity,
+            &component.namespace,
+            &component.has_attachment,
+        )?;
+        for role in &component.role {
+            self.graph
+                .insert_uri(&subject, &sbol2("role"), &role.uri()?)?;
+        }
+        Ok(subject)
+    }
+
+    fn component_definition(&mut self, component: &Component) -> Result<(), SbolError> {
+        let version = version(component);
+        let version = version.as_deref();
+        let subject = self.definition(component, "ComponentDefinition")?;
+        for r#type in &component.r#type {
+            let uri = match r#type {
+                ComponentType::Type(term) => {
+                    match BIOPAX_TYPES.iter().find(|(_, biopax)| biopax == term) {
+                        Some((name, _)) => Url::parse(&format!("{BIOPAX_NS}{name}"))?,
+                        None => term.uri()?,
+                    }
+                }
+                ComponentType::Topology(term) => term.uri()?,
+            };
+            self.graph.insert_uri(&subject, &sbol2("type"), &uri)?;
+        }
+        for sequence in &component.has_sequence {
+            self.insert_uri(&subject, "sequence", sequence)?;
+        }
+
+        let maps_to = MapsTo::find_all(component);
+        for sub_component in &component.sub_components {
+            let child = self.sub_component(&subject, sub_component, "component", version)?;
+            self.insert_uri(&child, "access", &access(sub_component)?)?;
+            for role in &sub_component.role {
+                self.graph
+                    .insert_uri(&child, &sbol2("role"), &role.uri()?)?;
+            }
+            if let Some(role_integration) = &sub_component.role_integration {
+                let uri = sbol2_term(role_integration)?;
+                self.graph
+                    .insert_uri(&child, &sbol2("roleIntegration"), &uri)?;
+            }
+            for location in &sub_component.source_location {
+                self.location(&child, "sourceLocation", location, version)?;
+            }
+            self.maps_to(&child, sub_component, &maps_to, version)?;
+        }
+        for sub_component in &component.sub_components {
+            if !sub_component.has_location.is_empty() {
+                self.located_sub_component(component, sub_component, version)?;
+            }
+        }
+        for feature in &component.sequence_features {
+            let child = self.identified(feature, "SequenceAnnotation", version)?;
+            self.graph
+                .insert_uri(&subject, &sbol2("sequenceAnnotation"), &child)?;
+            for role in &feature.role {
+                self.graph
+                    .insert_uri(&child, &sbol2("role"), &role.uri()?)?;
+            }
+            if let Some(orientation) = &feature.orientation {
+                self.insert_backport(&child, "orientation", &orientation.uri()?)?;
+            }
+            for location in &feature.has_location {
+                self.location(&child, "location", location, version)?;
+            }
+        }
+
+        let sub_components: HashSet<&Url> = component
+            .sub_components
+            .iter()
+            .map(|sub_component| &sub_component.identity)
+            .collect();
+        for constraint in &component.has_constraint {
+            let sequence_constraint = SEQUENCE_RESTRICTIONS.contains(&constraint.restriction)
+                && sub_components.contains(&constraint.subject)
+                && sub_components.contains(&constraint.object);
+            if sequence_constraint {
+                let child = self.identified(constraint, "SequenceConstraint", version)?;
+                self.graph
+                    .insert_uri(&subject, &sbol2("sequenceConstraint"), &child)?;
+                let restriction = sbol2_term(&constraint.restriction)?;
+                self.graph
+                    .insert_uri(&child, &sbol2("restriction"), &restriction)?;
+                self.insert_uri(&child, "subject", &constraint.subject)?;
+                self.insert_uri(&child, "object", &constraint.object)?;
+            } else {
+                self.backport_constraint(&subject, constraint, &maps_to)?;
+            }
+        }
+        for interaction in &component.has_interaction {
+            self.insert_backport(&subject, "hasInteraction", &interaction.identity)?;
+            sbol3::write_interaction(&mut self.graph, interaction)?;
+        }
+        for uri in &component.has_model {
+            self.insert_backport(&subject, "hasModel", uri)?;
+        }
+        self.backport_common(&subject, component, &maps_to)
+    }
+
+    fn module_definition(&mut self, component: &Component) -> Result<(), SbolError> {
+        let version = version(component);
+        let version = version.as_deref();
+        let subject = self.definition(component, "ModuleDefinition")?;
+        for uri in &component.has_model {
+            self.insert_uri(&subject, "model", uri)?;
+        }
+        for uri in &component.has_sequence {
+            self.insert_backport(&subject, "hasSequence", uri)?;
+        }
+
+        let maps_to = MapsTo::find_all(component);
+        let interface = component.has_interface.as_ref();
+        let mut public = false;
+        for sub_component in &component.sub_components {
+            let child = if self.modules.contains(&sub_component.instance_of) {
+                self.sub_component(&subject, sub_component, "module", version)?
+            } else {
+                let child =
+                    self.sub_component(&subject, sub_component, "functionalComponent", version)?;
+                let access = access(sub_component)?;
+                public |= access.as_str() == sbol2("public");
+                self.insert_uri(&child, "access", &access)?;
+                let uri = &sub_component.identity;
+                let input = interface.is_some_and(|interface| interface.input.contains(uri));
+                let output = interface.is_some_and(|interface| interface.output.contains(uri));
+                let direction = match (input, output) {
+                    (true, true) => "inout",
+                    (true, false) => "in",
+                    (false, true) => "out",
+                    (false, false) => "none",
+                };
+                self.graph.insert_uri(
+                    &child,
+                    &sbol2("direction"),
+                    &Url::parse(&sbol2(direction))?,
+                )?;
+                child
+            };
+            for role in &sub_component.role {
+                self.insert_backport(&child, "role", &role.uri()?)?;
+            }
+            if let Some(role_integration) = &sub_component.role_integration {
+                self.insert_backport(&child, "roleIntegration", &role_integration.uri()?)?;
+            }
+            for location in &sub_component.has_location {
+                self.insert_backport(&child, "hasLocation", &location.identity())?;
+                sbol3::write_location(&mut self.graph, location)?;
+            }
+            for location in &sub_component.source_location {
+                self.insert_backport(&child, "sourceLocation", &location.identity())?;
+                sbol3::write_location(&mut self.graph, location)?;
+            }
+            self.maps_to(&child, sub_component, &maps_to, version)?;
+        }
+        // Public functional components without a direction would imply an `Interface`
+        if interface.is_none() && public {
+            self.graph
+                .insert_literal(&subject, &backport("noInterface"), true)?;
+        }
+
+        for feature in &component.sequence_features {
+            self.insert_backport(&subject, "hasFeature", &feature.identity)?;
+            sbol3::write_sequence_feature(&mut self.graph, feature)?;
+        }
+        for constraint in &component.has_constraint {
+            self.backport_constraint(&subject, constraint, &maps_to)?;
+        }
+        let sub_components: HashSet<&Url> = component
+            .sub_components
+            .iter()
+            .map(|sub_component| &sub_component.identity)
+            .collect();
+        for interaction in &component.has_interaction {
+            let expressible = interaction
+                .has_participation
+                .iter()
+                .all(|participation| sub_components.contains(&participation.participant));
+            if expressible {
+                self.interaction(&subject, interaction, version)?;
+            } else {
+                self.insert_backport(&subject, "hasInteraction", &interaction.identity)?;
+                sbol3::write_interaction(&mut self.graph, interaction)?;
+            }
+        }
+        self.backport_common(&subject, component, &maps_to)
+    }
+
+    /// Write a `Component`, `FunctionalComponent` or `Module` as the `property` of `parent`
+    fn sub_component(
+        &mut self,
+        parent: &Url,
+        sub_component: &SubComponent,
+        property: &str,
+        version: Option<&str>,
+    ) -> Result<Url, SbolError> {
+        let class = match property {
+            "component" => "Component",
+            "functionalComponent" => "FunctionalComponent",
+            _ => "Module",
+        };
+        let child = self.identified(sub_component, class, version)?;
+        self.graph.insert_uri(parent, &sbol2(property), &child)?;
+        self.insert_uri(&child, "definition", &sub_component.instance_of)?;
+        if let Some(orientation) = &sub_component.orientation {
+            self.insert_backport(&child, "orientation", &orientation.uri()?)?;
+        }
+        Ok(child)
+    }
+
+    /// Write the `MapsTo` objects whose reference is within `sub_component`
+    fn maps_to(
+        &mut self,
+        child: &Url,
+        sub_component: &SubComponent,
+        maps_to: &[MapsTo],
+        version: Option<&str>,
+    ) -> Result<(), SbolError> {
+        for maps_to in maps_to {
+            let reference = maps_to.reference;
+            if reference.in_child_of != sub_component.identity {
+                continue;
+            }
+            let subject = self.identified(reference, "MapsTo", version)?;
+            self.graph.insert_uri(child, &sbol2("mapsTo"), &subject)?;
+            self.graph
+                .insert_uri(&subject, &sbol2("refinement"), &maps_to.refinement)?;
+            self.insert_uri(&subject, "local", maps_to.local)?;
+            self.insert_uri(&subject, "remote", &reference.refers_to)?;
+        }
+        Ok(())
+    }
+
+    /// Write the `SequenceAnnotation` which holds the locations of a `SubComponent`
+    fn located_sub_component(
+        &mut self,
+        component: &Component,
+        sub_component: &SubComponent,
+        version: Option<&str>,
+    ) -> Result<(), SbolError> {
+        let recorded = sub_component
+            .annotations
+            .iter()
+            .find(|annotation| annotation.property.as_str() == sbol2("sequenceAnnotation"))
+            .and_then(|annotation| annotation.value.as_uri());
+        let subject = match recorded {
+            Some(uri) => uri.clone(),
+            None => {
+                let display_id = format!("{}_annotation", display_id(sub_component));
+                let identity = child_uri(&component.identity, &display_id)?;
+                let subject = versioned(&identity, version)?;
+                self.graph
+                    .insert_uri(&subject, &sbol2("persistentIdentity"), &identity)?;
+                self.graph
+                    .insert_literal(&subject, &sbol2("displayId"), display_id)?;
+                if let Some(version) = version {
+                    self.graph
+                        .insert_literal(&subject, &sbol2("version"), version)?;
+                }
+                subject
+            }
+        };
+        let parent = self.sbol2_uri(&component.identity);
+        self.graph
+            .insert_uri(&parent, &sbol2("sequenceAnnotation"), &subject)?;
+        let class = Url::parse(&sbol2("SequenceAnnotation"))?;
+        self.graph.insert_uri(&subject, RDF_TYPE, &class)?;
+        self.insert_uri(&subject, "component", &sub_component.identity)?;
+        for location in &sub_component.has_location {
+            self.location(&subject, "location", location, version)?;
+        }
+        Ok(())
+    }
+
+    fn location(
+        &mut self,
+        parent: &Url,
+        property: &str,
+        location: &Location,
+        version: Option<&str>,
+    ) -> Result<(), SbolError> {
+        let class = match location {
+            Location::Range(_) => "Range",
+            Location::Cut(_) => "Cut",
+            Location::EntireSequence(_) => "GenericLocation",
+        };
+        let subject = self.identified(location, class, version)?;
+        self.graph.insert_uri(parent, &sbol2(property), &subject)?;
+        self.insert_uri(&subject, "sequence", location.has_sequence())?;
+        if let Some(orientation) = location.orientation() {
+            let uri = match orientation {
+                Orientation::Inline | Orientation::InlineAlt => sbol2("inline"),
+                Orientation::ReverseComplement | Orientation::ReverseComplementAlt => {
+                    sbol2("reverseComplement")
+                }
+            };
+            self.graph
+                .insert_uri(&subject, &sbol2("orientation"), &Url::parse(&uri)?)?;
+            // SBOL2 has a single term for the Sequence Ontology and SBOL3 terms of each strand
+            if matches!(
+                orientation,
+                Orientation::InlineAlt | Orientation::ReverseComplementAlt
+            ) {
+                self.insert_backport(&subject, "orientation", &orientation.uri()?)?;
+            }
+        }
+        if let Some(order) = location.order() {
+            self.graph
+                .insert_literal(&subject, &backport("order"), order)?;
+        }
+        match location {
+            Location::Range(range) => {
+                self.graph
+                    .insert_literal(&subject, &sbol2("start"), range.start)?;
+                self.graph
+                    .insert_literal(&subject, &sbol2("end"), range.end)
+            }
+            Location::Cut(cut) => self.graph.insert_literal(&subject, &sbol2("at"), cut.at),
+            Location::EntireSequence(_) => Ok(()),
+        }
+    }
+
+    fn interaction(
+        &mut self,
+        parent: &Url,
+        interaction: &Interaction,
+        version: Option<&str>,
+    ) -> Result<(), SbolError> {
+        let subject = self.identified(interaction, "Interaction", version)?;
+        self.graph
+            .insert_uri(parent, &sbol2("interaction"), &subject)?;
+        for r#type in &interaction.r#type {
+            self.graph
+                .insert_uri(&subject, &sbol2("type"), &r#type.uri()?)?;
+        }
+        for participation in &interaction.has_participation {
+            let child = self.identified(participation, "Participation", version)?;
+            self.graph
+                .insert_uri(&subject, &sbol2("participation"), &child)?;
+            for role in &participation.role {
+                self.graph
+                    .insert_uri(&child, &sbol2("role"), &role.uri()?)?;
+            }
+            self.insert_uri(&child, "participant", &participation.participant)?;
+        }
+        Ok(())
+    }
+
+    /// Embed `constraint` as SBOL3, unless it is written as part of a `MapsTo`
+    fn backport_constraint(
+        &mut self,
+        subject: &Url,
+        constraint: &Constraint,
+        maps_to: &[MapsTo],
+    ) -> Result<(), SbolError> {
+        if maps_to
+            .iter()
+            .any(|maps_to| std::ptr::eq(maps_to.constraint, constraint))
+        {
+            return Ok(());
+        }
+        self.insert_backport(subject, "hasConstraint", &constraint.identity)?;
+        sbol3::write_constraint(&mut self.graph, constraint)
+    }
+
+    /// Embed the references which are not written as `MapsTo` objects and the `Interface` of
+    /// `component` as SBOL3
+    fn backport_common(
+        &mut self,
+        subject: &Url,
+        component: &Component,
+        maps_to: &[MapsTo],
+    ) -> Result<(), SbolError> {
+        for reference in &component.component_references {
+            if maps_to
+                .iter()
+                .any(|maps_to| std::ptr::eq(maps_to.reference, reference))
+            {
+                continue;
+            }
+            self.insert_backport(subject, "hasFeature", &reference.identity)?;
+            sbol3::write_component_reference(&mut self.graph, reference)?;
+        }
+        if let Some(interface) = &component.has_interface {
+            self.insert_backport(subject, "hasInterface", &interface.identity)?;
+            sbol3::write_interface(&mut self.graph, interface)?;
+        }
+        Ok(())
+    }
+}
+
+/// SBOL2 access of `sub_component`, as recorded by its `sbol2:access` annotation
+fn access(sub_component: &SubComponent) -> Result<Url, SbolError> {
+    let recorded = sub_component
+        .annotations
+        .iter()
+        .find(|annotation| annotation.property.as_str() == sbol2("access"))
+        .and_then(|annotation| annotation.value.as_uri());
+    match recorded {
+        Some(access) => Ok(access.clone()),
+        None => Ok(Url::parse(&sbol2("public"))?),
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use crate::ontologies::{
+        ComponentRole, ComponentTypeOntology, Encoding, InteractionType, Orientation,
+        ParticipationRole, Restriction,
+    };
+    use crate::AnnotationValue;
+
+    use super::*;
+    use crate::test_utils::uri;
+
+    pub(crate) const INVERTER: &str = r#"<?xml version="1.0" ?>
+<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#"
+         xmlns:dcterms="http://purl.org/dc/terms/"
+         xmlns:prov="http://www.w3.org/ns/prov#"
+         xmlns:sbol="http://sbols.org/v2#"
+         xmlns:igem="http://wiki.synbiohub.org/wiki/Terms/igem#">
+  <sbol:Sequence rdf:about="https://test.org/lab/device_seq/1">
+    <sbol:persistentIdentity rdf:resource="https://test.org/lab/device_seq"/>
+    <sbol:displayId>device_seq</sbol:displayId>
+    <sbol:version>1</sbol:version>
+    <sbol:elements>ttgacagctagctcagtcctaggatgaaa</sbol:elements>
+    <sbol:encoding rdf:resource="http://www.chem.qmul.ac.uk/iubmb/misc/naseq.html"/>
+  </sbol:Sequence>
+  <sbol:ComponentDefinition rdf:about="https://test.org/lab/pTet/1">
+    <sbol:persistentIdentity rdf:resource="https://test.org/lab/pTet"/>
+    <sbol:displayId>pTet</sbol:displayId>
+    <sbol:version>1</sbol:version>
+    <sbol:type rdf:resource="http://www.biopax.org/release/biopax-level3.owl#DnaRegion"/>
+    <sbol:role rdf:resource="http://identifiers.org/so/SO:0000167"/>
+  </sbol:ComponentDefinition>
+  <sbol:ComponentDefinition rdf:about="https://test.org/lab/TetR/1">
+    <sbol:persistentIdentity rdf:resource="https://test.org/lab/TetR"/>
+    <sbol:displayId>TetR</sbol:displayId>
+    <sbol:version>1</sbol:version>
+    <sbol:type rdf:resource="http://www.biopax.org/release/biopax-level3.owl#Protein"/>
+  </sbol:ComponentDefinition>
+  <sbol:ComponentDefinition rdf:about="https://test.org/lab/device/1">
+    <sbol:persistentIdentity rdf:resource="https://test.org/lab/device"/>
+    <sbol:displayId>device</sbol:displayId>
+    <sbol:version>1</sbol:version>
+    <dcterms:title>Device</dcterms:title>
+    <prov:wasDerivedFrom rdf:resource="https://test.org/lab/pTet/1"/>
+    <igem:status>Available</igem:status>
+    <sbol:type rdf:resource="http://www.biopax.org/release/biopax-level3.owl#DnaRegion"/>
+    <sbol:type rdf:resource="http://identifiers.org/so/SO:0000987"/>
+    <sbol:sequence rdf:resource="https://test.org/lab/device_seq/1"/>
+    <sbol:component>
+      <sbol:Component rdf:about="https://test.org/lab/device/pTet_c/1">
+        <sbol:displayId>pTet_c</sbol:displayId>
+        <sbol:definition rdf:resource="https://test.org/lab/pTet/1"/>
+        <sbol:access rdf:resource="http://sbols.org/v2#public"/>
+      </sbol:Component>
+    </sbol:component>
+    <sbol:component>
+      <sbol:Component rdf:about="https://test.org/lab/device/cds_c/1">
+        <sbol:displayId>cds_c</sbol:displayId>
+        <sbol:definition rdf:resource="https://test.org/lab/cds/1"/>
+        <sbol:access rdf:resource="http://sbols.org/v2#private"/>
+      </sbol:Component>
+    </sbol:component>
+    <sbol:sequenceAnnotation>
+      <sbol:SequenceAnnotation rdf:about="https://test.org/lab/device/pTet_anno/1">
+        <sbol:displayId>pTet_anno</sbol:displayId>
+        <sbol:component rdf:resource="https://test.org/lab/device/pTet_c/1"/>
+        <sbol:location>
+          <sbol:Range rdf:about="https://test.org/lab/device/pTet_anno/range/1">
+            <sbol:displayId>range</sbol:displayId>
+            <sbol:start>1</sbol:start>
+            <sbol:end>20</sbol:end>
+            <sbol:orientation rdf:resource="http://sbols.org/v2#inline"/>
+          </sbol:Range>
+        </sbol:location>
+      </sbol:SequenceAnnotation>
+    </sbol:sequenceAnnotation>
+    <sbol:sequenceAnnotation>
+      <sbol:SequenceAnnotation rdf:about="https://test.org/lab/device/start/1">
+        <sbol:displayId>start</sbol:displayId>
+        <dcterms:title>start codon</dcterms:title>
+        <sbol:role rdf:resource="http://identifiers.org/so/SO:0000318"/>
+        <sbol:location>
+          <sbol:Range rdf:about="https://test.org/lab/device/start/range/1">
+            <sbol:displayId>range</sbol:displayId>
+            <sbol:start>24</sbol:start>
+            <sbol:end>26</sbol:end>
+            <sbol:orientation rdf:resource="http://sbols.org/v2#reverseComplement"/>
+          </sbol:Range>
+        </sbol:location>
+        <sbol:location>
+          <sbol:Cut rdf:about="https://test.org/lab/device/start/cut/1">
+            <sbol:displayId>cut</sbol:displayId>
+            <sbol:at>23</sbol:at>
+          </sbol:Cut>
+        </sbol:location>
+      </sbol:SequenceAnnotation>
+    </sbol:sequenceAnnotation>
+    <sbol:sequenceConstraint>
+      <sbol:SequenceConstraint rdf:about="https://test.org/lab/device/order/1">
+        <sbol:displayId>order</sbol:displayId>
+        <sbol:restriction rdf:resource="http://sbols.org/v2#precedes"/>
+        <sbol:subject rdf:resource="https://test.org/lab/device/pTet_c/1"/>
+        <sbol:object rdf:resource="https://test.org/lab/device/cds_c/1"/>
+      </sbol:SequenceConstraint>
+    </sbol:sequenceConstraint>
+  </sbol:ComponentDefinition>
+  <sbol:ModuleDefinition rdf:about="https://test.org/lab/inverter/1">
+    <sbol:persistentIdentity rdf:resource="https://test.org/lab/inverter"/>
+    <sbol:displayId>inverter</sbol:displayId>
+    <sbol:version>1</sbol:version>
+    <sbol:functionalComponent>
+      <sbol:FunctionalComponent rdf:about="https://test.org/lab/inverter/TetR_fc/1">
+        <sbol:displayId>TetR_fc</sbol:displayId>
+        <sbol:definition rdf:resource="https://test.org/lab/TetR/1"/>
+        <sbol:access rdf:resource="http://sbols.org/v2#public"/>
+        <sbol:direction rdf:resource="http://sbols.org/v2#in"/>
+      </sbol:FunctionalComponent>
+    </sbol:functionalComponent>
+    <sbol:functionalComponent>
+      <sbol:FunctionalComponent rdf:about="https://test.org/lab/inverter/pTet_fc/1">
+        <sbol:displayId>pTet_fc</sbol:displayId>
+        <sbol:definition rdf:resource="https://test.org/lab/pTet/1"/>
+        <sbol:access rdf:resource="http://sbols.org/v2#public"/>
+        <sbol:direction rdf:resource="http://sbols.org/v2#none"/>
+      </sbol:FunctionalComponent>
+    </sbol:functionalComponent>
+    <sbol:interaction>
+      <sbol:Interaction rdf:about="https://test.org/lab/inverter/repression/1">
+        <sbol:displayId>repression</sbol:displayId>
+        <sbol:type rdf:resource="http://identifiers.org/biomodels.sbo/SBO:0000169"/>
+        <sbol:participation>
+          <sbol:Participation rdf:about="https://test.org/lab/inverter/repression/repressor/1">
+            <sbol:displayId>repressor</sbol:displayId>
+            <sbol:role rdf:resource="http://identifiers.org/biomodels.sbo/SBO:0000020"/>
+            <sbol:participant rdf:resource="https://test.org/lab/inverter/TetR_fc/1"/>
+          </sbol:Participation>
+        </sbol:participation>
+      </sbol:Interaction>
+    </sbol:interaction>
+  </sbol:ModuleDefinition>
+  <sbol:ModuleDefinition rdf:about="https://test.org/lab/system/1">
+    <sbol:persistentIdentity rdf:resource="https://test.org/lab/system"/>
+    <sbol:displayId>system</sbol:displayId>
+    <sbol:version>1</sbol:version>
+    <sbol:functionalComponent>
+      <sbol:FunctionalComponent rdf:about="https://test.org/lab/system/TetR_local/1">
+        <sbol:displayId>TetR_local</sbol:displayId>
+        <sbol:definition rdf:resource="https://test.org/lab/TetR/1"/>
+        <sbol:access rdf:resource="http://sbols.org/v2#private"/>
+        <sbol:direction rdf:resource="http://sbols.org/v2#none"/>
+      </sbol:FunctionalComponent>
+    </sbol:functionalComponent>
+    <sbol:module>
+      <sbol:Module rdf:about="https://test.org/lab/system/inverter_m/1">
+        <sbol:displayId>inverter_m</sbol:displayId>
+        <sbol:definition rdf:resource="https://test.org/lab/inverter/1"/>
+        <sbol:mapsTo>
+          <sbol:MapsTo rdf:about="https://test.org/lab/system/inverter_m/TetR_map/1">
+            <sbol:displayId>TetR_map</sbol:displayId>
+            <sbol:refinement rdf:resource="http://sbols.org/v2#useRemote"/>
+            <sbol:local rdf:resource="https://test.org/lab/system/TetR_local/1"/>
+            <sbol:remote rdf:resource="https://test.org/lab/inverter/TetR_fc/1"/>
+          </sbol:MapsTo>
+        </sbol:mapsTo>
+      </sbol:Module>
+    </sbol:module>
+  </sbol:ModuleDefinition>
+</rdf:RDF>
+"#;
+
+    #[test]
+    fn test_sequence() {
+        let document = parse(INVERTER).unwrap();
+        let sequence = &document.sequences[0];
+        assert_eq!(sequence.identity, uri("https://test.org/lab/device_seq"));
+        assert_eq!(sequence.namespace, uri("https://test.org/lab"));
+        assert_eq!(sequence.encoding, Some(Encoding::NucleicAcid));
+        assert_eq!(
+            sequence.annotation_values(&uri("http://sbols.org/v2#version")),
+            vec![AnnotationValue::Literal("1".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_sbol3_document() {
+        let input = r#"<?xml version="1.0" ?>
+<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#"
+         xmlns:sbol="http://sbols.org/v3#">
+  <sbol:Component rdf:about="https://test.org/lab/pTet">
+    <sbol:displayId>pTet</sbol:displayId>
+    <sbol:hasNamespace rdf:resource="https://test.org/lab"/>
+  </sbol:Component>
+</rdf:RDF>
+"#;
+        let err = parse(input).unwrap_err().to_string();
+        assert!(err.contains("https://test.org/lab/pTet is an SBOL3 object"));
+    }
+
+    #[test]
+    fn test_unsupported_top_levels() {
+        let input = INVERTER.replace(
+            "</rdf:RDF>",
+            r#"  <sbol:Collection rdf:about="https://test.org/lab/parts/1">
+    <sbol:persistentIdentity rdf:resource="https://test.org/lab/parts"/>
+    <sbol:member rdf:resource="https://test.org/lab/pTet/1"/>
+  </sbol:Collection>
+  <sbol:Model rdf:about="https://test.org/lab/model/1"/>
+</rdf:RDF>"#,
+        );
+        let err = parse(&input).unwrap_err().to_string();
+        assert!(err.contains("https://test.org/lab/parts/1, https://test.org/lab/model/1"));
+    }
+
+    #[test]
+    fn test_component_definition() {
+        let document = parse(INVERTER).unwrap();
+        let device = document
+            .component(&uri("https://test.org/lab/device"))
+            .unwrap();
+        assert_eq!(device.name.as_deref(), Some("Device"));
+        assert_eq!(device.derived_from, vec![uri("https://test.org/lab/pTet")]);
+        assert_eq!(
+            device.annotation_values(&uri("http://wiki.synbiohub.org/wiki/Terms/igem#status")),
+            vec![AnnotationValue::Literal("Available".to_string())]
+        );
+        assert_eq!(
+            device.r#type[0],
+            ComponentType::Type(ComponentTypeOntology::DNA)
+        );
+        assert_eq!(
+            device.has_sequence,
+            vec![uri("https://test.org/lab/device_seq")]
+        );
+
+        let promoter = &device.sub_components[0];
+        assert_eq!(promoter.identity, uri("https://test.org/lab/device/pTet_c"));
+        assert_eq!(promoter.instance_of, uri("https://test.org/lab/pTet"));
+        // External definitions lose their version too
+        assert_eq!(
+            device.sub_components[1].instance_of,
+            uri("https://test.org/lab/cds")
+        );
+        assert_eq!(promoter.has_location.len(), 1);
+        let Location::Range(range) = &promoter.has_location[0] else {
+            panic!("Expected a Range, found {:?}", promoter.has_location[0]);
+        };
+        assert_eq!(
+            range.identity,
+            uri("https://test.org/lab/device/pTet_c/range")
+        );
+        assert_eq!(range.has_sequence, uri("https://test.org/lab/device_seq"));
+        assert_eq!((range.start, range.end), (1, 20));
+        assert_eq!(range.orientation, Some(Orientation::Inline));
+        assert_eq!(
+            promoter.annotation_values(&uri("http://sbols.org/v2#sequenceAnnotation")),
+            vec![AnnotationValue::Uri(uri(
+                "https://test.org/lab/device/pTet_anno/1"
+            ))]
+        );
+
+        let feature = &device.sequence_features[0];
+        assert_eq!(feature.identity, uri("https://test.org/lab/device/start"));
+        assert_eq!(feature.name.as_deref(), Some("start codon"));
+        assert_eq!(
+            feature.role,
+            vec![ComponentRole::Other(uri(
+                "http://identifiers.org/so/SO:0000318"
+            ))]
+        );
+        assert!(matches!(feature.has_location[1], Location::Cut(_)));
+
+        let constraint = &device.has_constraint[0];
+        assert_eq!(constraint.restriction, Restriction::Precedes);
+        assert_eq!(constraint.subject, promoter.identity);
+        assert_eq!(constraint.object, device.sub_components[1].identity);
+    }
+
+    #[test]
+    fn test_module_definition() {
+        let document = parse(INVERTER).unwrap();
+        let inverter = document
+            .component(&uri("https://test.org/lab/inverter"))
+            .unwrap();
+        assert_eq!(
+            inverter.r#type,
+            vec![ComponentType::Type(ComponentTypeOntology::FunctionalEntity)]
+        );
+        let interface = inverter.has_interface.as_ref().unwrap();
+        assert_eq!(
+            interface.input,
+            vec![uri("https://test.org/lab/inverter/TetR_fc")]
+        );
+        assert_eq!(
+            interface.nondirectional,
+            vec![uri("https://test.org/lab/inverter/pTet_fc")]
+        );
+
+        let interaction = &inverter.has_interaction[0];
+        assert_eq!(
+            interaction.identity,
+            uri("https://test.org/lab/inverter/repression")
+        );
+        assert_eq!(interaction.r#type, vec![InteractionType::Inhibition]);
+        let participation = &interaction.has_participation[0];
+        assert_eq!(
+            participation.identity,
+            uri("https://test.org/lab/inverter/repression/repressor")
+        );
+        assert_eq!(participation.role, vec![ParticipationRole::Inhibitor]);
+        assert_eq!(
+            participation.participant,
+            uri("https://test.org/lab/inverter/TetR_fc")
+        );
+
+        let system = document
+            .component(&uri("https://test.org/lab/system"))
+            .unwrap();
+        assert!(system.has_interface.is_none());
+        let module = &system.sub_components[1];
+        assert_eq!(module.instance_of, inverter.identity);
+
+        let reference = &system.component_references[0];
+        assert_eq!(
+            reference.identity,
+            uri("https://test.org/lab/system/TetR_map")
+        );
+        assert_eq!(reference.in_child_of, module.identity);
+        assert_eq!(
+            reference.refers_to,
+            uri("https://test.org/lab/inverter/TetR_fc")
+        );
+        let constraint = &system.has_constraint[0];
+        assert_eq!(constraint.restriction, Restriction::Replaces);
+        assert_eq!(constraint.subject, reference.identity);
+        assert_eq!(constraint.object, system.sub_components[0].identity);
+        assert!(system.has_feature().contains(&reference.identity));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let document = parse(INVERTER).unwrap();
+        let sbol2 = to_string(&document).unwrap();
+        assert!(sbol2
+            .contains(r#"<sbol:ModuleDefinition rdf:about="https://test.org/lab/inverter/1">"#));
+        assert!(sbol2.contains(r#"<sbol:direction rdf:resource="http://sbols.org/v2#in"/>"#));
+        assert!(
+            sbol2.contains(r#"<sbol:refinement rdf:resource="http://sbols.org/v2#useRemote"/>"#)
+        );
+        assert_eq!(parse(&sbol2).unwrap(), document);
+    }
+
+    #[test]
+    fn test_backport() {
+        let namespace = uri("https://example.org");
+        let mut document = Document::new();
+        let mut sequence = Sequence::new(namespace.clone(), "device_seq").unwrap();
+        sequence.elements = Some("atgcatgcatgc".to_string());
+        document.sequences.push(sequence);
+
+        // A namespace which differs from the URI of the `Component`
+        let mut device = Component::new(uri("https://example.org/lab"), "device").unwrap();
+        device.namespace = namespace.clone();
+        device.r#type = vec![ComponentType::Type(ComponentTypeOntology::DNA)];
+        device.has_sequence = vec![uri("https://example.org/device_seq")];
+        device.has_measure = vec![uri("https://example.org/measure")];
+        let mut part =
+            SubComponent::new(&device.identity, "part", uri("https://example.org/part")).unwrap();
+        part.orientation = Some(Orientation::ReverseComplement);
+        let mut range = Range::new(
+            &part.identity,
+            "range",
+            uri("https://example.org/device_seq"),
+            1,
+            4,
+        )
+        .unwrap();
+        range.order = Some(2);
+        part.has_location.push(Location::Range(range));
+        let mut feature = SequenceFeature::new(&device.identity, "feature").unwrap();
+        feature.orientation = Some(Orientation::Inline);
+        let mut range = Range::new(
+            &feature.identity,
+            "range",
+            uri("https://example.org/device_seq"),
+            5,
+            8,
+        )
+        .unwrap();
+        range.orientation = Some(Orientation::InlineAlt);
+        feature.has_location.push(Location::Range(range));
+        let reference = ComponentReference::new(
+            &device.identity,
+            "inner",
+            part.identity.clone(),
+            uri("https://example.org/part/inner"),
+        )
+        .unwrap();
+        device.has_constraint.push(
+            Constraint::new(
+                &device.identity,
+                "meets",
+                Restriction::Meets,
+                part.identity.clone(),
+                feature.identity.clone(),
+            )
+            .unwrap(),
+        );
+        let mut interface = Interface::new(&device.identity, "interface").unwrap();
+        interface.output.push(part.identity.clone());
+        device.has_interface = Some(interface);
+        device.sub_components.push(part);
+        device.sequence_features.push(feature);
+        device.component_references.push(reference);
+
+        let mut circuit = Component::new(namespace.clone(), "circuit").unwrap();
+        circuit.r#type = vec![ComponentType::Type(ComponentTypeOntology::FunctionalEntity)];
+        let device_sub_component =
+            SubComponent::new(&circuit.identity, "device", device.identity.clone()).unwrap();
+        let reference = ComponentReference::new(
+            &circuit.identity,
+            "part",
+            device_sub_component.identity.clone(),
+            uri("https://example.org/lab/device/part"),
+        )
+        .unwrap();
+        // Participants which are not sub-components cannot be expressed in SBOL2
+        let mut interaction = Interaction::new(&circuit.identity, "production").unwrap();
+        interaction.r#type.push(InteractionType::GeneticProduction);
+        let mut participation = Participation::new(
+            &interaction.identity,
+            "template",
+            reference.identity.clone(),
+        )
+        .unwrap();
+        participation.role.push(ParticipationRole::Template);
+        interaction.has_participation.push(participation);
+        circuit.sub_components.push(device_sub_component);
+        circuit.component_references.push(reference);
+        circuit.has_interaction.push(interaction);
+        let mut activity = Activity::new(namespace.clone(), "assembly").unwrap();
+        activity.r#type = vec![uri("https://identifiers.org/SBO:0000004")];
+        activity.start_time = Some("2024-01-01T00:00:00Z".to_string());
+        activity.informed_by = vec![uri("https://example.org/design")];
+        activity
+            .has_usage
+            .push(Usage::new(&activity.identity, "device", device.identity.clone()).unwrap());
+        circuit.generated_by = vec![activity.identity.clone()];
+        document.components.push(device);
+        document.components.push(circuit);
+        document.activities.push(activity);
+
+        let sbol2 = to_string(&document).unwrap();
+        assert!(sbol2
+            .contains(r#"<sbol:ComponentDefinition rdf:about="https://example.org/lab/device">"#));
+        assert!(
+            sbol2.contains(r#"<sbol:ModuleDefinition rdf:about="https://example.org/circuit">"#)
+        );
+        assert!(sbol2.contains("<backport:hasInterface"));
+        assert!(sbol2.contains("<backport:order>2</backport:order>"));
+        // SBOL3 orientation terms are written as their SBOL2 equivalent
+        assert!(sbol2.contains(r#"<sbol:orientation rdf:resource="http://sbols.org/v2#inline"/>"#));
+        assert!(!sbol2.contains(r#"<sbol:orientation rdf:resource="https://sbols.org/v3#"#));
+        assert!(sbol2.contains(r#"<prov:Activity rdf:about="https://example.org/assembly">"#));
+        assert_eq!(parse(&sbol2).unwrap(), document);
+    }
+}
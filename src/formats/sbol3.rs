@@ -0,0 +1,542 @@
+//! SBOL3 objects as RDF triples, in the vocabulary of the SBOL3 specification.
+//!
+//! Used to embed SBOL3 objects which have no SBOL2 equivalent into SBOL2 documents, and to compare
+//! objects property by property.
+
+use url::Url;
+
+use super::rdf_xml::{Graph, PROV_DERIVED_FROM, PROV_GENERATED_BY, RDF_TYPE};
+use super::sbol2::prov;
+use super::Common;
+use crate::ontologies::{
+    ComponentRole, InteractionType, Ontology, Orientation, ParticipationRole, Restriction,
+    RoleIntegration,
+};
+use crate::{
+    Activity, Annotation, Component, ComponentReference, ComponentType, Constraint, Cut, Document,
+    EntireSequence, Feature, Identified, Interaction, Interface, Location, Participation, Range,
+    SbolError, Sequence, SequenceFeature, SubComponent, TopLevel,
+};
+
+/// Namespace of the SBOL3 data model
+pub(crate) const SBOL3_RDF_NS: &str = "http://sbols.org/v3#";
+
+/// URI of a term of the SBOL3 data model
+pub(crate) fn sbol3(local: &str) -> String {
+    format!("{SBOL3_RDF_NS}{local}")
+}
+
+fn write_identified(
+    graph: &mut Graph,
+    object: &dyn Identified,
+    class: &str,
+) -> Result<Url, SbolError> {
+    write_typed(graph, object, &Url::parse(&sbol3(class))?)
+}
+
+/// Write the `Identified` properties of `object`, as an instance of `class`
+fn write_typed(graph: &mut Graph, object: &dyn Identified, class: &Url) -> Result<Url, SbolError> {
+    let subject = object.identity();
+    graph.insert_uri(&subject, RDF_TYPE, class)?;
+    if let Some(display_id) = object.display_id() {
+        graph.insert_literal(&subject, &sbol3("displayId"), display_id)?;
+    }
+    if let Some(name) = object.name() {
+        graph.insert_literal(&subject, &sbol3("name"), name)?;
+    }
+    if let Some(description) = object.description() {
+        graph.insert_literal(&subject, &sbol3("description"), description)?;
+    }
+    for uri in object.derived_from() {
+        graph.insert_uri(&subject, PROV_DERIVED_FROM, &uri)?;
+    }
+    for uri in object.generated_by() {
+        graph.insert_uri(&subject, PROV_GENERATED_BY, &uri)?;
+    }
+    for uri in object.has_measure() {
+        graph.insert_uri(&subject, &sbol3("hasMeasure"), &uri)?;
+    }
+    for annotation in object.annotations() {
+        graph.insert(subject.clone(), annotation.property, annotation.value);
+    }
+    Ok(subject)
+}
+
+fn write_terms<T: Ontology>(
+    graph: &mut Graph,
+    subject: &Url,
+    property: &str,
+    terms: &[T],
+) -> Result<(), SbolError> {
+    for term in terms {
+        graph.insert_uri(subject, &sbol3(property), &term.uri()?)?;
+    }
+    Ok(())
+}
+
+fn write_uris(
+    graph: &mut Graph,
+    subject: &Url,
+    property: &str,
+    uris: &[Url],
+) -> Result<(), SbolError> {
+    for uri in uris {
+        graph.insert_uri(subject, &sbol3(property), uri)?;
+    }
+    Ok(())
+}
+
+/// Properties shared by every `Feature`
+fn write_feature(graph: &mut Graph, feature: &dyn Feature, class: &str) -> Result<Url, SbolError> {
+    let subject = write_identified(graph, feature, class)?;
+    write_terms(graph, &subject, "role", feature.role())?;
+    if let Some(orientation) = feature.orientation() {
+        graph.insert_uri(&subject, &sbol3("orientation"), &orientation.uri()?)?;
+    }
+    Ok(subject)
+}
+
+pub(crate) fn write_sequence_feature(
+    graph: &mut Graph,
+    feature: &SequenceFeature,
+) -> Result<(), SbolError> {
+    let subject = write_feature(graph, feature, "SequenceFeature")?;
+    for location in &feature.has_location {
+        graph.insert_uri(&subject, &sbol3("hasLocation"), &location.identity())?;
+        write_location(graph, location)?;
+    }
+    Ok(())
+}
+
+pub(crate) fn write_component_reference(
+    graph: &mut Graph,
+    reference: &ComponentReference,
+) -> Result<(), SbolError> {
+    let subject = write_feature(graph, reference, "ComponentReference")?;
+    graph.insert_uri(&subject, &sbol3("inChildOf"), &reference.in_child_of)?;
+    graph.insert_uri(&subject, &sbol3("refersTo"), &reference.refers_to)
+}
+
+pub(crate) fn write_location(graph: &mut Graph, location: &Location) -> Result<(), SbolError> {
+    let class = match location {
+        Location::Range(_) => "Range",
+        Location::Cut(_) => "Cut",
+        Location::EntireSequence(_) => "EntireSequence",
+    };
+    let subject = write_identified(graph, location, class)?;
+    graph.insert_uri(&subject, &sbol3("hasSequence"), location.has_sequence())?;
+    if let Some(orientation) = location.orientation() {
+        graph.insert_uri(&subject, &sbol3("orientation"), &orientation.uri()?)?;
+    }
+    if let Some(order) = location.order() {
+        graph.insert_literal(&subject, &sbol3("order"), order)?;
+    }
+    match location {
+        Location::Range(range) => {
+            graph.insert_literal(&subject, &sbol3("start"), range.start)?;
+            graph.insert_literal(&subject, &sbol3("end"), range.end)
+        }
+        Location::Cut(cut) => graph.insert_literal(&subject, &sbol3("at"), cut.at),
+        Location::EntireSequence(_) => Ok(()),
+    }
+}
+
+pub(crate) fn write_constraint(
+    graph: &mut Graph,
+    constraint: &Constraint,
+) -> Result<(), SbolError> {
+    let subject = write_identified(graph, constraint, "Constraint")?;
+    let restriction = constraint.restriction.uri()?;
+    graph.insert_uri(&subject, &sbol3("restriction"), &restriction)?;
+    graph.insert_uri(&subject, &sbol3("subject"), &constraint.subject)?;
+    graph.insert_uri(&subject, &sbol3("object"), &constraint.object)
+}
+
+pub(crate) fn write_interaction(
+    graph: &mut Graph,
+    interaction: &Interaction,
+) -> Result<(), SbolError> {
+    let subject = write_identified(graph, interaction, "Interaction")?;
+    write_terms(graph, &subject, "type", &interaction.r#type)?;
+    for participation in &interaction.has_participation {
+        graph.insert_uri(
+            &subject,
+            &sbol3("hasParticipation"),
+            &participation.identity,
+        )?;
+        let participation_subject = write_identified(graph, participation, "Participation")?;
+        write_terms(graph, &participation_subject, "role", &participation.role)?;
+        graph.insert_uri(
+            &participation_subject,
+            &sbol3("participant"),
+            &participation.participant,
+        )?;
+    }
+    Ok(())
+}
+
+pub(crate) fn write_interface(graph: &mut Graph, interface: &Interface) -> Result<(), SbolError> {
+    let subject = write_identified(graph, interface, "Interface")?;
+    write_uris(graph, &subject, "input", &interface.input)?;
+    write_uris(graph, &subject, "output", &interface.output)?;
+    write_uris(graph, &subject, "nondirectional", &interface.nondirectional)
+}
+
+pub(crate) fn write_sub_component(
+    graph: &mut Graph,
+    sub_component: &SubComponent,
+) -> Result<(), SbolError> {
+    let subject = write_feature(graph, sub_component, "SubComponent")?;
+    if let Some(role_integration) = &sub_component.role_integration {
+        graph.insert_uri(
+            &subject,
+            &sbol3("roleIntegration"),
+            &role_integration.uri()?,
+        )?;
+    }
+    graph.insert_uri(&subject, &sbol3("instanceOf"), &sub_component.instance_of)?;
+    for (property, locations) in [
+        ("hasLocation", &sub_component.has_location),
+        ("sourceLocation", &sub_component.source_location),
+    ] {
+        for location in locations {
+            graph.insert_uri(&subject, &sbol3(property), &location.identity())?;
+            write_location(graph, location)?;
+        }
+    }
+    Ok(())
+}
+
+/// Properties shared by every `TopLevel`
+fn write_top_level(
+    graph: &mut Graph,
+    object: &dyn TopLevel,
+    class: &Url,
+) -> Result<Url, SbolError> {
+    let subject = write_typed(graph, object, class)?;
+    graph.insert_uri(&subject, &sbol3("hasNamespace"), &object.has_namespace())?;
+    write_uris(graph, &subject, "hasAttachment", &object.has_attachment())?;
+    Ok(subject)
+}
+
+/// Write `component` and all of its children
+pub(crate) fn write_component(graph: &mut Graph, component: &Component) -> Result<(), SbolError> {
+    let subject = write_top_level(graph, component, &Url::parse(&sbol3("Component"))?)?;
+    for r#type in &component.r#type {
+        let uri = match r#type {
+            ComponentType::Type(term) => term.uri()?,
+            ComponentType::Topology(term) => term.uri()?,
+        };
+        graph.insert_uri(&subject, &sbol3("type"), &uri)?;
+    }
+    write_terms(graph, &subject, "role", &component.role)?;
+    write_uris(graph, &subject, "hasSequence", &component.has_sequence)?;
+    for feature in component.has_feature() {
+        graph.insert_uri(&subject, &sbol3("hasFeature"), &feature)?;
+    }
+    for sub_component in &component.sub_components {
+        write_sub_component(graph, sub_component)?;
+    }
+    for feature in &component.sequence_features {
+        write_sequence_feature(graph, feature)?;
+    }
+    for reference in &component.component_references {
+        write_component_reference(graph, reference)?;
+    }
+    for constraint in &component.has_constraint {
+        graph.insert_uri(&subject, &sbol3("hasConstraint"), &constraint.identity)?;
+        write_constraint(graph, constraint)?;
+    }
+    for interaction in &component.has_interaction {
+        graph.insert_uri(&subject, &sbol3("hasInteraction"), &interaction.identity)?;
+        write_interaction(graph, interaction)?;
+    }
+    if let Some(interface) = &component.has_interface {
+        graph.insert_uri(&subject, &sbol3("hasInterface"), &interface.identity)?;
+        write_interface(graph, interface)?;
+    }
+    write_uris(graph, &subject, "hasModel", &component.has_model)
+}
+
+pub(crate) fn write_sequence(graph: &mut Graph, sequence: &Sequence) -> Result<(), SbolError> {
+    let subject = write_top_level(graph, sequence, &Url::parse(&sbol3("Sequence"))?)?;
+    if let Some(elements) = &sequence.elements {
+        graph.insert_literal(&subject, &sbol3("elements"), elements)?;
+    }
+    if let Some(encoding) = &sequence.encoding {
+        graph.insert_uri(&subject, &sbol3("encoding"), &encoding.uri()?)?;
+    }
+    Ok(())
+}
+
+/// Write `activity` and its `Usage` objects, in the PROV-O vocabulary
+pub(crate) fn write_activity(graph: &mut Graph, activity: &Activity) -> Result<(), SbolError> {
+    let subject = write_top_level(graph, activity, &Url::parse(&prov("Activity"))?)?;
+    write_uris(graph, &subject, "type", &activity.r#type)?;
+    if let Some(start_time) = &activity.start_time {
+        graph.insert_literal(&subject, &prov("startedAtTime"), start_time)?;
+    }
+    if let Some(end_time) = &activity.end_time {
+        graph.insert_literal(&subject, &prov("endedAtTime"), end_time)?;
+    }
+    let class = Url::parse(&prov("Usage"))?;
+    for usage in &activity.has_usage {
+        graph.insert_uri(&subject, &prov("qualifiedUsage"), &usage.identity)?;
+        let child = write_typed(graph, usage, &class)?;
+        graph.insert_uri(&child, &prov("entity"), &usage.entity)?;
+        for role in &usage.role {
+            graph.insert_uri(&child, &prov("hadRole"), role)?;
+        }
+    }
+    for informed_by in &activity.informed_by {
+        graph.insert_uri(&subject, &prov("wasInformedBy"), informed_by)?;
+    }
+    Ok(())
+}
+
+/// Graph of the `TopLevel` of `document` identified by `uri` and of all of its children, or
+/// `None` if the document has no such object
+pub(crate) fn top_level_graph(document: &Document, uri: &Url) -> Result<Option<Graph>, SbolError> {
+    let mut graph = Graph::default();
+    if let Some(component) = document.component(uri) {
+        write_component(&mut graph, component)?;
+    } else if let Some(sequence) = document.sequence(uri) {
+        write_sequence(&mut graph, sequence)?;
+    } else if let Some(activity) = document.activity(uri) {
+        write_activity(&mut graph, activity)?;
+    } else {
+        return Ok(None);
+    }
+    Ok(Some(graph))
+}
+
+/// Read the `Identified` properties of `subject`.
+///
+/// Properties outside of the SBOL3 and PROV vocabularies become annotations.
+pub(crate) fn read_common(graph: &Graph, subject: &Url) -> Common {
+    let annotations = graph
+        .properties(subject)
+        .iter()
+        .filter(|(property, _)| {
+            let property = property.as_str();
+            !property.starts_with(SBOL3_RDF_NS)
+                && ![RDF_TYPE, PROV_DERIVED_FROM, PROV_GENERATED_BY].contains(&property)
+        })
+        .map(|(property, value)| Annotation {
+            property: property.clone(),
+            value: value.clone(),
+        })
+        .collect();
+    Common {
+        identity: subject.clone(),
+        display_id: graph.literal(subject, &sbol3("displayId")),
+        name: graph.literal(subject, &sbol3("name")),
+        description: graph.literal(subject, &sbol3("description")),
+        derived_from: graph.uris(subject, PROV_DERIVED_FROM),
+        generated_by: graph.uris(subject, PROV_GENERATED_BY),
+        has_measure: graph.uris(subject, &sbol3("hasMeasure")),
+        annotations,
+    }
+}
+
+fn required_uri(graph: &Graph, subject: &Url, property: &str) -> Result<Url, SbolError> {
+    graph
+        .uri(subject, &sbol3(property))
+        .ok_or_else(|| SbolError::Validation(format!("{subject} has no sbol3:{property} property")))
+}
+
+fn number<T: std::str::FromStr>(
+    graph: &Graph,
+    subject: &Url,
+    property: &str,
+) -> Result<Option<T>, SbolError> {
+    graph
+        .literal(subject, &sbol3(property))
+        .map(|value| {
+            value.trim().parse().map_err(|_| {
+                SbolError::Parse(format!("{subject} has an invalid {property}: {value:?}"))
+            })
+        })
+        .transpose()
+}
+
+fn required_number<T: std::str::FromStr>(
+    graph: &Graph,
+    subject: &Url,
+    property: &str,
+) -> Result<T, SbolError> {
+    number(graph, subject, property)?
+        .ok_or_else(|| SbolError::Validation(format!("{subject} has no sbol3:{property} property")))
+}
+
+fn read_roles(graph: &Graph, subject: &Url) -> Vec<ComponentRole> {
+    graph
+        .uris(subject, &sbol3("role"))
+        .into_iter()
+        .map(ComponentRole::from)
+        .collect()
+}
+
+fn read_orientation(graph: &Graph, subject: &Url) -> Result<Option<Orientation>, SbolError> {
+    graph
+        .uri(subject, &sbol3("orientation"))
+        .map(Orientation::try_from)
+        .transpose()
+}
+
+fn read_locations(
+    graph: &Graph,
+    subject: &Url,
+    property: &str,
+) -> Result<Vec<Location>, SbolError> {
+    graph
+        .uris(subject, &sbol3(property))
+        .iter()
+        .map(|location| read_location(graph, location))
+        .collect()
+}
+
+/// Read the `Feature` identified by `subject` into `component`
+pub(crate) fn read_feature(
+    graph: &Graph,
+    subject: &Url,
+    component: &mut Component,
+) -> Result<(), SbolError> {
+    if graph.has_type(subject, &sbol3("SubComponent")) {
+        let sub_component = read_sub_component(graph, subject)?;
+        component.sub_components.push(sub_component);
+    } else if graph.has_type(subject, &sbol3("SequenceFeature")) {
+        let feature = read_sequence_feature(graph, subject)?;
+        component.sequence_features.push(feature);
+    } else if graph.has_type(subject, &sbol3("ComponentReference")) {
+        let reference = read_component_reference(graph, subject)?;
+        component.component_references.push(reference);
+    } else {
+        return Err(SbolError::Parse(format!(
+            "{subject} is not a supported Feature"
+        )));
+    }
+    Ok(())
+}
+
+fn read_sub_component(graph: &Graph, subject: &Url) -> Result<SubComponent, SbolError> {
+    let common = read_common(graph, subject);
+    let instance_of = required_uri(graph, subject, "instanceOf")?;
+    let mut sub_component = SubComponent::new(subject, common.display_id(), instance_of)?;
+    set_common!(sub_component, common);
+    sub_component.role = read_roles(graph, subject);
+    sub_component.orientation = read_orientation(graph, subject)?;
+    sub_component.role_integration = graph
+        .uri(subject, &sbol3("roleIntegration"))
+        .map(RoleIntegration::try_from)
+        .transpose()?;
+    sub_component.has_location = read_locations(graph, subject, "hasLocation")?;
+    sub_component.source_location = read_locations(graph, subject, "sourceLocation")?;
+    Ok(sub_component)
+}
+
+fn read_sequence_feature(graph: &Graph, subject: &Url) -> Result<SequenceFeature, SbolError> {
+    let common = read_common(graph, subject);
+    let mut feature = SequenceFeature::new(subject, common.display_id())?;
+    set_common!(feature, common);
+    feature.role = read_roles(graph, subject);
+    feature.orientation = read_orientation(graph, subject)?;
+    feature.has_location = read_locations(graph, subject, "hasLocation")?;
+    Ok(feature)
+}
+
+fn read_component_reference(graph: &Graph, subject: &Url) -> Result<ComponentReference, SbolError> {
+    let common = read_common(graph, subject);
+    let mut reference = ComponentReference::new(
+        subject,
+        common.display_id(),
+        required_uri(graph, subject, "inChildOf")?,
+        required_uri(graph, subject, "refersTo")?,
+    )?;
+    set_common!(reference, common);
+    reference.role = read_roles(graph, subject);
+    reference.orientation = read_orientation(graph, subject)?;
+    Ok(reference)
+}
+
+pub(crate) fn read_location(graph: &Graph, subject: &Url) -> Result<Location, SbolError> {
+    let common = read_common(graph, subject);
+    let has_sequence = required_uri(graph, subject, "hasSequence")?;
+    let orientation = read_orientation(graph, subject)?;
+    let order = number(graph, subject, "order")?;
+
+    let location = if graph.has_type(subject, &sbol3("Range")) {
+        let start = required_number(graph, subject, "start")?;
+        let end = required_number(graph, subject, "end")?;
+        let mut range = Range::new(subject, common.display_id(), has_sequence, start, end)?;
+        set_common!(range, common);
+        range.orientation = orientation;
+        range.order = order;
+        Location::Range(range)
+    } else if graph.has_type(subject, &sbol3("Cut")) {
+        let at = required_number(graph, subject, "at")?;
+        let mut cut = Cut::new(subject, common.display_id(), has_sequence, at)?;
+        set_common!(cut, common);
+        cut.orientation = orientation;
+        cut.order = order;
+        Location::Cut(cut)
+    } else if graph.has_type(subject, &sbol3("EntireSequence")) {
+        let mut entire = EntireSequence::new(subject, common.display_id(), has_sequence)?;
+        set_common!(entire, common);
+        entire.orientation = orientation;
+        entire.order = order;
+        Location::EntireSequence(entire)
+    } else {
+        return Err(SbolError::Parse(format!(
+            "{subject} is not a known Location"
+        )));
+    };
+    Ok(location)
+}
+
+pub(crate) fn read_constraint(graph: &Graph, subject: &Url) -> Result<Constraint, SbolError> {
+    let common = read_common(graph, subject);
+    let mut constraint = Constraint::new(
+        subject,
+        common.display_id(),
+        Restriction::from(required_uri(graph, subject, "restriction")?),
+        required_uri(graph, subject, "subject")?,
+        required_uri(graph, subject, "object")?,
+    )?;
+    set_common!(constraint, common);
+    Ok(constraint)
+}
+
+pub(crate) fn read_interaction(graph: &Graph, subject: &Url) -> Result<Interaction, SbolError> {
+    let common = read_common(graph, subject);
+    let mut interaction = Interaction::new(subject, common.display_id())?;
+    set_common!(interaction, common);
+    interaction.r#type = graph
+        .uris(subject, &sbol3("type"))
+        .into_iter()
+        .map(InteractionType::from)
+        .collect();
+    for participation in graph.uris(subject, &sbol3("hasParticipation")) {
+        let common = read_common(graph, &participation);
+        let participant = required_uri(graph, &participation, "participant")?;
+        let mut converted = Participation::new(subject, common.display_id(), participant)?;
+        set_common!(converted, common);
+        converted.role = graph
+            .uris(&participation, &sbol3("role"))
+            .into_iter()
+            .map(ParticipationRole::from)
+            .collect();
+        interaction.has_participation.push(converted);
+    }
+    Ok(interaction)
+}
+
+pub(crate) fn read_interface(graph: &Graph, subject: &Url) -> Result<Interface, SbolError> {
+    let common = read_common(graph, subject);
+    let mut interface = Interface::new(subject, common.display_id())?;
+    set_common!(interface, common);
+    interface.input = graph.uris(subject, &sbol3("input"));
+    interface.output = graph.uris(subject, &sbol3("output"));
+    interface.nondirectional = graph.uris(subject, &sbol3("nondirectional"));
+    Ok(interface)
+}
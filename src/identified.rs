@@ -1,11 +1,16 @@
 use url::Url;
 
+use crate::{Annotation, AnnotationValue, SbolError};
+
 /// Base interface implementations for Identified class.
 ///
 /// This interface is used to derive (either directly or indirectly) all other SBOL objects. This
 /// allows objects to be uniquely identified using URI's and referenced from within a SBOL document
 /// or at locations on the web.
 pub trait Identified {
+    /// URI which uniquely identifies this object.
+    fn identity(&self) -> Url;
+
     /// Intermediate between URI and name property
     ///
     /// This is equivalent to "displayId" as per the SBOL specification.
@@ -48,4 +53,108 @@ pub trait Identified {
     /// `Measure` objects are defined by the OM ontology and is located in the
     /// "http://www.ontology-of-units-of-measure.org/resource/om-2" namespace.
     fn has_measure(&self) -> Vec<Url>;
+
+    /// Application-specific properties which are not defined by SBOL.
+    fn annotations(&self) -> Vec<Annotation>;
+
+    /// Values of every annotation of this object whose property is `property`
+    fn annotation_values(&self, property: &Url) -> Vec<AnnotationValue> {
+        self.annotations()
+            .into_iter()
+            .filter(|annotation| &annotation.property == property)
+            .map(|annotation| annotation.value)
+            .collect()
+    }
+}
+
+/// URI formed as `parent/display_id`
+///
+/// Used for the URIs of child objects, and of `TopLevel` objects within their namespace.
+pub(crate) fn child_uri(parent: &Url, display_id: &str) -> Result<Url, SbolError> {
+    Ok(Url::parse(&format!(
+        "{}/{display_id}",
+        parent.as_str().trim_end_matches('/')
+    ))?)
+}
+
+/// `prefix`, or `prefix` followed by the first number for which `taken` is false
+///
+/// Used for the `display_id` of new `TopLevel` objects. Child objects are named after their class
+/// by [`child_display_id`](crate::uris::child_display_id).
+pub(crate) fn unused_display_id(prefix: &str, taken: impl Fn(&str) -> bool) -> String {
+    if !taken(prefix) {
+        return prefix.to_string();
+    }
+    (1..)
+        .map(|i| format!("{prefix}{i}"))
+        .find(|display_id| !taken(display_id))
+        .unwrap_or_default()
+}
+
+/// Implement `Identified` for structs which store each property in a field of the same name
+macro_rules! impl_identified {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl $crate::Identified for $ty {
+                fn identity(&self) -> url::Url {
+                    self.identity.clone()
+                }
+
+                fn display_id(&self) -> Option<String> {
+                    self.display_id.clone()
+                }
+
+                fn name(&self) -> Option<String> {
+                    self.name.clone()
+                }
+
+                fn description(&self) -> Option<String> {
+                    self.description.clone()
+                }
+
+                fn derived_from(&self) -> Vec<url::Url> {
+                    self.derived_from.clone()
+                }
+
+                fn generated_by(&self) -> Vec<url::Url> {
+                    self.generated_by.clone()
+                }
+
+                fn has_measure(&self) -> Vec<url::Url> {
+                    self.has_measure.clone()
+                }
+
+                fn annotations(&self) -> Vec<$crate::Annotation> {
+                    self.annotations.clone()
+                }
+            }
+        )*
+    };
+}
+pub(crate) use impl_identified;
+
+/// Convert arbitrary text (ie: a file header) into a valid `display_id`.
+///
+/// Characters which are not alphanumeric are replaced by underscores, and an underscore is
+/// prepended if the text would begin with a digit.
+pub fn sanitize_display_id(text: &str) -> String {
+    let display_id: String = text
+        .trim()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    match display_id.chars().next() {
+        Some(c) if c.is_ascii_digit() => format!("_{display_id}"),
+        Some(_) => display_id,
+        None => "_".to_string(),
+    }
+}
+
+/// Whether `display_id` is made of alphanumeric or underscore characters only, and does not begin
+/// with a digit, as required by the SBOL specification
+pub fn is_valid_display_id(display_id: &str) -> bool {
+    display_id.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && display_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_')
 }
@@ -0,0 +1,84 @@
+use url::Url;
+
+use crate::identified::{child_uri, impl_identified};
+use crate::ontologies::{InteractionType, ParticipationRole};
+use crate::{Annotation, SbolError};
+
+/// Functional relationship between the `Feature` objects of a `Component`, ie: the repression of
+/// a promoter by a transcription factor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Interaction {
+    pub identity: Url,
+    pub display_id: Option<String>,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub derived_from: Vec<Url>,
+    pub generated_by: Vec<Url>,
+    pub has_measure: Vec<Url>,
+    pub annotations: Vec<Annotation>,
+
+    /// Describes the behavior represented by the `Interaction`, ie: inhibition.
+    ///
+    /// Should contain exactly one term from the occurring entity branch of the SBO.
+    pub r#type: Vec<InteractionType>,
+
+    /// Participating `Feature` objects and their roles
+    pub has_participation: Vec<Participation>,
+}
+
+/// Role of a `Feature` within an `Interaction`, ie: the inhibitor of an inhibition.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Participation {
+    pub identity: Url,
+    pub display_id: Option<String>,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub derived_from: Vec<Url>,
+    pub generated_by: Vec<Url>,
+    pub has_measure: Vec<Url>,
+    pub annotations: Vec<Annotation>,
+
+    /// Should contain exactly one term from the participant role branch of the SBO.
+    pub role: Vec<ParticipationRole>,
+
+    /// `Feature` of the parent `Component` which participates
+    pub participant: Url,
+}
+
+impl Interaction {
+    /// Create an `Interaction` whose URI is `parent/display_id`
+    pub fn new(parent: &Url, display_id: &str) -> Result<Self, SbolError> {
+        Ok(Self {
+            identity: child_uri(parent, display_id)?,
+            display_id: Some(display_id.to_string()),
+            name: None,
+            description: None,
+            derived_from: vec![],
+            generated_by: vec![],
+            has_measure: vec![],
+            annotations: vec![],
+            r#type: vec![],
+            has_participation: vec![],
+        })
+    }
+}
+
+impl Participation {
+    /// Create a `Participation` whose URI is `parent/display_id`
+    pub fn new(parent: &Url, display_id: &str, participant: Url) -> Result<Self, SbolError> {
+        Ok(Self {
+            identity: child_uri(parent, display_id)?,
+            display_id: Some(display_id.to_string()),
+            name: None,
+            description: None,
+            derived_from: vec![],
+            generated_by: vec![],
+            has_measure: vec![],
+            annotations: vec![],
+            role: vec![],
+            participant,
+        })
+    }
+}
+
+impl_identified!(Interaction, Participation);
@@ -0,0 +1,48 @@
+use url::Url;
+
+use crate::identified::{child_uri, impl_identified};
+use crate::{Annotation, SbolError};
+
+/// Declares which `Feature` objects of a `Component` are exposed to the `Component` objects which
+/// include it, and in which direction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Interface {
+    pub identity: Url,
+    pub display_id: Option<String>,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub derived_from: Vec<Url>,
+    pub generated_by: Vec<Url>,
+    pub has_measure: Vec<Url>,
+    pub annotations: Vec<Annotation>,
+
+    /// `Feature` objects which are inputs of the `Component`
+    pub input: Vec<Url>,
+
+    /// `Feature` objects which are outputs of the `Component`
+    pub output: Vec<Url>,
+
+    /// `Feature` objects which are exposed without a direction
+    pub nondirectional: Vec<Url>,
+}
+
+impl Interface {
+    /// Create an empty `Interface` whose URI is `parent/display_id`
+    pub fn new(parent: &Url, display_id: &str) -> Result<Self, SbolError> {
+        Ok(Self {
+            identity: child_uri(parent, display_id)?,
+            display_id: Some(display_id.to_string()),
+            name: None,
+            description: None,
+            derived_from: vec![],
+            generated_by: vec![],
+            has_measure: vec![],
+            annotations: vec![],
+            input: vec![],
+            output: vec![],
+            nondirectional: vec![],
+        })
+    }
+}
+
+impl_identified!(Interface);
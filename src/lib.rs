@@ -1,14 +1,43 @@
-extern crate url;
+pub extern crate url;
 
+mod activity;
+pub mod annotate;
+mod annotation;
+pub mod assembly;
+pub mod cloning;
 mod component;
+mod constraint;
+pub mod coordinates;
+pub mod copy;
+pub mod diff;
+mod document;
+mod error;
 mod feature;
+pub mod formats;
 mod identified;
+mod interaction;
+mod interface;
+mod location;
+pub mod merge;
 pub mod ontologies;
+pub mod resolver;
 mod sequence;
+#[cfg(test)]
+mod test_utils;
 mod toplevel;
+pub mod uris;
+pub mod validation;
 
-pub use component::Component;
+pub use activity::{Activity, Usage};
+pub use annotation::{Annotation, AnnotationValue};
+pub use component::{Component, ComponentType};
+pub use constraint::Constraint;
+pub use document::Document;
+pub use error::SbolError;
 pub use feature::*;
-pub use identified::Identified;
-pub use sequence::Sequence;
+pub use identified::{is_valid_display_id, sanitize_display_id, Identified};
+pub use interaction::{Interaction, Participation};
+pub use interface::Interface;
+pub use location::{Cut, EntireSequence, Location, Range};
+pub use sequence::{alphabet, nucleic_acid, search, translation, Sequence};
 pub use toplevel::TopLevel;
@@ -0,0 +1,241 @@
+use url::Url;
+
+use crate::identified::{child_uri, impl_identified};
+use crate::ontologies::Orientation;
+use crate::{Annotation, Identified, SbolError};
+
+/// Specifies the region of a `Sequence` that is referred to by a `Feature`.
+///
+/// Every variant refers to exactly one `Sequence` via `has_sequence`, and may specify an
+/// `orientation` and an `order` relative to the other `Location` objects of the same `Feature`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Location {
+    Range(Range),
+    Cut(Cut),
+    EntireSequence(EntireSequence),
+}
+
+/// Region of a `Sequence` between two inclusive positions.
+///
+/// Positions are 1-based: the first element of a `Sequence` is at position 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Range {
+    pub identity: Url,
+    pub display_id: Option<String>,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub derived_from: Vec<Url>,
+    pub generated_by: Vec<Url>,
+    pub has_measure: Vec<Url>,
+    pub annotations: Vec<Annotation>,
+
+    /// `Sequence` that this `Range` refers to
+    pub has_sequence: Url,
+
+    /// Whether the region is on the `elements` or on the reverse complement mapping of the
+    /// `elements` of the `Sequence`.
+    pub orientation: Option<Orientation>,
+
+    /// Ordering relative to the other `Location` objects of the same `Feature`
+    pub order: Option<i64>,
+
+    /// Inclusive position of the first element of the region.
+    ///
+    /// Must be greater than zero.
+    pub start: usize,
+
+    /// Inclusive position of the last element of the region.
+    ///
+    /// Must be greater than or equal to `start`: a region which spans the origin of a circular
+    /// sequence is split into two `Range` objects (see [`coordinates`](crate::coordinates)).
+    pub end: usize,
+}
+
+/// Location between two adjacent elements of a `Sequence`, ie: a restriction site.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cut {
+    pub identity: Url,
+    pub display_id: Option<String>,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub derived_from: Vec<Url>,
+    pub generated_by: Vec<Url>,
+    pub has_measure: Vec<Url>,
+    pub annotations: Vec<Annotation>,
+    pub has_sequence: Url,
+    pub orientation: Option<Orientation>,
+    pub order: Option<i64>,
+
+    /// Position of the element after which the cut is located.
+    ///
+    /// A value of zero indicates a location before the first element.
+    pub at: usize,
+}
+
+/// The entirety of a `Sequence`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntireSequence {
+    pub identity: Url,
+    pub display_id: Option<String>,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub derived_from: Vec<Url>,
+    pub generated_by: Vec<Url>,
+    pub has_measure: Vec<Url>,
+    pub annotations: Vec<Annotation>,
+    pub has_sequence: Url,
+    pub orientation: Option<Orientation>,
+    pub order: Option<i64>,
+}
+
+impl_identified!(Range, Cut, EntireSequence);
+
+impl Range {
+    /// Create a `Range` whose URI is `parent/display_id`
+    pub fn new(
+        parent: &Url,
+        display_id: &str,
+        has_sequence: Url,
+        start: usize,
+        end: usize,
+    ) -> Result<Self, SbolError> {
+        Ok(Self {
+            identity: child_uri(parent, display_id)?,
+            display_id: Some(display_id.to_string()),
+            name: None,
+            description: None,
+            derived_from: vec![],
+            generated_by: vec![],
+            has_measure: vec![],
+            annotations: vec![],
+            has_sequence,
+            orientation: None,
+            order: None,
+            start,
+            end,
+        })
+    }
+
+    /// Number of elements within the region
+    pub fn len(&self) -> usize {
+        (self.end + 1).saturating_sub(self.start)
+    }
+
+    /// Whether the region contains no elements
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Cut {
+    /// Create a `Cut` whose URI is `parent/display_id`
+    pub fn new(
+        parent: &Url,
+        display_id: &str,
+        has_sequence: Url,
+        at: usize,
+    ) -> Result<Self, SbolError> {
+        Ok(Self {
+            identity: child_uri(parent, display_id)?,
+            display_id: Some(display_id.to_string()),
+            name: None,
+            description: None,
+            derived_from: vec![],
+            generated_by: vec![],
+            has_measure: vec![],
+            annotations: vec![],
+            has_sequence,
+            orientation: None,
+            order: None,
+            at,
+        })
+    }
+}
+
+impl EntireSequence {
+    /// Create an `EntireSequence` whose URI is `parent/display_id`
+    pub fn new(parent: &Url, display_id: &str, has_sequence: Url) -> Result<Self, SbolError> {
+        Ok(Self {
+            identity: child_uri(parent, display_id)?,
+            display_id: Some(display_id.to_string()),
+            name: None,
+            description: None,
+            derived_from: vec![],
+            generated_by: vec![],
+            has_measure: vec![],
+            annotations: vec![],
+            has_sequence,
+            orientation: None,
+            order: None,
+        })
+    }
+}
+
+impl Location {
+    fn inner(&self) -> &dyn Identified {
+        match self {
+            Self::Range(range) => range,
+            Self::Cut(cut) => cut,
+            Self::EntireSequence(entire) => entire,
+        }
+    }
+
+    /// `Sequence` that this `Location` refers to
+    pub fn has_sequence(&self) -> &Url {
+        match self {
+            Self::Range(range) => &range.has_sequence,
+            Self::Cut(cut) => &cut.has_sequence,
+            Self::EntireSequence(entire) => &entire.has_sequence,
+        }
+    }
+
+    pub fn orientation(&self) -> Option<&Orientation> {
+        match self {
+            Self::Range(range) => range.orientation.as_ref(),
+            Self::Cut(cut) => cut.orientation.as_ref(),
+            Self::EntireSequence(entire) => entire.orientation.as_ref(),
+        }
+    }
+
+    pub fn order(&self) -> Option<i64> {
+        match self {
+            Self::Range(range) => range.order,
+            Self::Cut(cut) => cut.order,
+            Self::EntireSequence(entire) => entire.order,
+        }
+    }
+}
+
+impl Identified for Location {
+    fn identity(&self) -> Url {
+        self.inner().identity()
+    }
+
+    fn display_id(&self) -> Option<String> {
+        self.inner().display_id()
+    }
+
+    fn name(&self) -> Option<String> {
+        self.inner().name()
+    }
+
+    fn description(&self) -> Option<String> {
+        self.inner().description()
+    }
+
+    fn derived_from(&self) -> Vec<Url> {
+        self.inner().derived_from()
+    }
+
+    fn generated_by(&self) -> Vec<Url> {
+        self.inner().generated_by()
+    }
+
+    fn has_measure(&self) -> Vec<Url> {
+        self.inner().has_measure()
+    }
+
+    fn annotations(&self) -> Vec<Annotation> {
+        self.inner().annotations()
+    }
+}
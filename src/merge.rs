@@ -0,0 +1,291 @@
+//! Merge of `Document` objects, ie: two edited copies of the same design library.
+//!
+//! `TopLevel` objects are matched by URI. Objects are compared property by property, as RDF
+//! triples of the SBOL3 vocabulary, so that the order of multi-valued properties is irrelevant.
+
+use std::fmt;
+
+use url::Url;
+
+use crate::formats::{top_level_graph, Graph};
+use crate::{AnnotationValue, Document, Identified, SbolError};
+
+/// How to resolve objects which have the same URI but different properties
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MergeStrategy {
+    /// Keep the object of the left document
+    PreferLeft,
+
+    /// Keep the object of the right document
+    PreferRight,
+
+    /// Fail with a description of the conflicts
+    #[default]
+    Fail,
+}
+
+/// Property of an object which has different values in two documents
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PropertyConflict {
+    /// The `TopLevel`, or the child object of a `TopLevel`, which has the property
+    pub object: Url,
+    pub property: Url,
+
+    /// Values in the left document, empty if the object or property is missing
+    pub left: Vec<AnnotationValue>,
+
+    /// Values in the right document, empty if the object or property is missing
+    pub right: Vec<AnnotationValue>,
+}
+
+impl fmt::Display for PropertyConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let values = |values: &[AnnotationValue]| {
+            values
+                .iter()
+                .map(|value| match value {
+                    AnnotationValue::Uri(uri) => format!("<{uri}>"),
+                    AnnotationValue::Literal(literal) => format!("{literal:?}"),
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        write!(
+            f,
+            "{} {}: [{}] != [{}]",
+            self.object,
+            self.property,
+            values(&self.left),
+            values(&self.right)
+        )
+    }
+}
+
+/// Result of a merge
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Merged {
+    pub document: Document,
+
+    /// Conflicts which were resolved by the merge strategy
+    pub conflicts: Vec<PropertyConflict>,
+}
+
+/// Every property conflict between `left` and `right`, among the `TopLevel` objects which both
+/// documents have.
+pub fn conflicts(left: &Document, right: &Document) -> Result<Vec<PropertyConflict>, SbolError> {
+    let mut conflicts = vec![];
+    for top_level in right.top_levels() {
+        let uri = top_level.identity();
+        if let (Some(left), Some(right)) =
+            (top_level_graph(left, &uri)?, top_level_graph(right, &uri)?)
+        {
+            conflicts.extend(property_conflicts(&left, &right));
+        }
+    }
+    Ok(conflicts)
+}
+
+/// Merge `right` into `left`.
+///
+/// `TopLevel` objects of either document are kept once, and objects whose properties differ are
+/// resolved with `strategy`. Objects of the left document come first, in their original order.
+pub fn merge(
+    left: &Document,
+    right: &Document,
+    strategy: MergeStrategy,
+) -> Result<Merged, SbolError> {
+    let mut document = left.clone();
+    let mut conflicts = vec![];
+    let mut added = Document::new();
+    for top_level in right.top_levels() {
+        let uri = top_level.identity();
+        let Some(left_graph) = top_level_graph(left, &uri)? else {
+            added.extend(only(right, &uri));
+            continue;
+        };
+        let right_graph = top_level_graph(right, &uri)?.unwrap_or_default();
+        let differences = property_conflicts(&left_graph, &right_graph);
+        if differences.is_empty() {
+            continue;
+        }
+        if strategy == MergeStrategy::PreferRight {
+            replace(&mut document.components, &uri, right.component(&uri));
+            replace(&mut document.sequences, &uri, right.sequence(&uri));
+            replace(&mut document.activities, &uri, right.activity(&uri));
+        }
+        conflicts.extend(differences);
+    }
+    if strategy == MergeStrategy::Fail && !conflicts.is_empty() {
+        return Err(SbolError::Validation(
+            conflicts
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("; "),
+        ));
+    }
+    document.extend(added);
+    Ok(Merged {
+        document,
+        conflicts,
+    })
+}
+
+/// Document with only the `TopLevel` of `document` identified by `uri`
+fn only(document: &Document, uri: &Url) -> Document {
+    Document {
+        components: document.component(uri).into_iter().cloned().collect(),
+        sequences: document.sequence(uri).into_iter().cloned().collect(),
+        activities: document.activity(uri).into_iter().cloned().collect(),
+    }
+}
+
+/// Replace the object of `objects` identified by `uri` with `replacement`, at the same position
+fn replace<T: Identified + Clone>(objects: &mut Vec<T>, uri: &Url, replacement: Option<&T>) {
+    let position = objects.iter().position(|object| &object.identity() == uri);
+    match (position, replacement) {
+        (Some(i), Some(replacement)) => objects[i] = replacement.clone(),
+        (Some(i), None) => {
+            objects.remove(i);
+        }
+        (None, Some(replacement)) => objects.push(replacement.clone()),
+        (None, None) => (),
+    }
+}
+
+/// Values of `property` of `subject`, in a canonical order
+fn values(graph: &Graph, subject: &Url, property: &Url) -> Vec<AnnotationValue> {
+    let mut values: Vec<AnnotationValue> =
+        graph.values(subject, property.as_str()).cloned().collect();
+    values.sort_by(|a, b| {
+        (a.as_str(), a.as_uri().is_some()).cmp(&(b.as_str(), b.as_uri().is_some()))
+    });
+    values
+}
+
+/// Properties of the subjects of `left` and `right` whose values differ
+pub(crate) fn property_conflicts(left: &Graph, right: &Graph) -> Vec<PropertyConflict> {
+    let mut subjects: Vec<&Url> = left.subjects().iter().collect();
+    subjects.extend(
+        right
+            .subjects()
+            .iter()
+            .filter(|s| !left.subjects().contains(s)),
+    );
+    let mut conflicts = vec![];
+    for subject in subjects {
+        let mut properties: Vec<&Url> = vec![];
+        let all = left
+            .properties(subject)
+            .iter()
+            .chain(right.properties(subject));
+        for (property, _) in all {
+            if !properties.contains(&property) {
+                properties.push(property);
+            }
+        }
+        for property in properties {
+            let (left, right) = (
+                values(left, subject, property),
+                values(right, subject, property),
+            );
+            if left != right {
+                conflicts.push(PropertyConflict {
+                    object: subject.clone(),
+                    property: property.clone(),
+                    left,
+                    right,
+                });
+            }
+        }
+    }
+    conflicts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ontologies::ComponentRole;
+    use crate::test_utils::{dna_sequence, uri};
+    use crate::Component;
+
+    fn library() -> Document {
+        let namespace = uri("https://example.org");
+        let mut promoter = Component::new(namespace.clone(), "pTet").unwrap();
+        promoter.role = vec![ComponentRole::Promoter];
+        let sequence = dna_sequence(&namespace, "pTet_seq", "tccctatcagtgatagaga");
+        Document {
+            components: vec![promoter],
+            sequences: vec![sequence],
+            activities: vec![],
+        }
+    }
+
+    #[test]
+    fn test_merge_identical() {
+        let left = library();
+        let mut right = library();
+        let namespace = uri("https://example.org");
+        right
+            .components
+            .push(Component::new(namespace, "terminator").unwrap());
+        for strategy in [MergeStrategy::Fail, MergeStrategy::PreferLeft] {
+            let merged = merge(&left, &right, strategy).unwrap();
+            assert!(merged.conflicts.is_empty());
+            assert_eq!(merged.document.components.len(), 2);
+            assert_eq!(merged.document.sequences.len(), 1);
+        }
+    }
+
+    #[test]
+    fn test_merge_conflicts() {
+        let left = library();
+        let mut right = library();
+        right.components[0].role = vec![ComponentRole::Operator, ComponentRole::Promoter];
+        right.sequences[0].elements = Some("TCCCTATCAGTGATAGAGA".to_string());
+
+        let found = conflicts(&left, &right).unwrap();
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].object, left.components[0].identity);
+        assert_eq!(found[0].property.as_str(), "http://sbols.org/v3#role");
+        assert_eq!(found[0].left.len(), 1);
+        assert_eq!(found[0].right.len(), 2);
+        assert_eq!(
+            found[1].to_string(),
+            "https://example.org/pTet_seq http://sbols.org/v3#elements: \
+             [\"tccctatcagtgatagaga\"] != [\"TCCCTATCAGTGATAGAGA\"]"
+        );
+
+        assert!(merge(&left, &right, MergeStrategy::Fail).is_err());
+        let merged = merge(&left, &right, MergeStrategy::PreferLeft).unwrap();
+        assert_eq!(merged.document, left);
+        assert_eq!(merged.conflicts, found);
+        let merged = merge(&left, &right, MergeStrategy::PreferRight).unwrap();
+        assert_eq!(merged.document.components, right.components);
+        assert_eq!(merged.document.sequences, right.sequences);
+
+        // Replaced objects keep their position in the left document
+        let namespace = uri("https://example.org");
+        let mut left = left.clone();
+        left.components
+            .push(Component::new(namespace.clone(), "terminator").unwrap());
+        let mut right = right.clone();
+        right
+            .components
+            .insert(0, Component::new(namespace, "rbs").unwrap());
+        let merged = merge(&left, &right, MergeStrategy::PreferRight).unwrap();
+        let display_ids: Vec<_> = merged
+            .document
+            .components
+            .iter()
+            .filter_map(|c| c.display_id.as_deref())
+            .collect();
+        assert_eq!(display_ids, ["pTet", "terminator", "rbs"]);
+        assert_eq!(merged.document.components[0], right.components[1]);
+
+        // The order of multi-valued properties is irrelevant
+        let mut reordered = right.clone();
+        reordered.components[0].role.reverse();
+        assert!(conflicts(&right, &reordered).unwrap().is_empty());
+    }
+}
@@ -1,95 +1,153 @@
-use url::Url;
+use crate::ontology;
 
-use super::{Ontology, CHEBI_NS, GO_NS, INVALID_URI, JOIN_ERROR_MSG, SBO_NS, SO_NS};
+use super::{CHEBI_NS, GO_NS, SBO_NS, SO_NS};
 
-/// Component Type Ontologies
-/// Pulled from SBOL 3 spec, Section 6.4, Table 2
-pub enum ComponentTypeOntology {
-    DNA,
-    RNA,
-    Protein,
-    SimpleChemical,
-    NonCovalentComplex,
-    FunctionalEntity,
-    Other(&'static str),
-}
-impl Ontology for ComponentTypeOntology {
-    fn uri(&self) -> Url {
-        let uri = match self {
-            Self::DNA => SBO_NS.to_string() + "0000251",
-            Self::RNA => SBO_NS.to_string() + "0000250",
-            Self::Protein => SBO_NS.to_string() + "0000252",
-            Self::SimpleChemical => SBO_NS.to_string() + "0000249",
-            Self::NonCovalentComplex => SBO_NS.to_string() + "0000253",
-            Self::FunctionalEntity => SBO_NS.to_string() + "0000241",
-            // If `ComponentType::error` variant, return entire URI
-            Self::Other(uri) => return Url::parse(uri).expect(INVALID_URI),
-        };
-        Url::parse(uri.as_str()).expect(INVALID_URI)
+ontology! {
+    /// Component Type Ontologies
+    /// Pulled from SBOL 3 spec, Section 6.4, Table 2
+    pub enum ComponentTypeOntology(Other) {
+        DNA {
+            namespace: SBO_NS,
+            id: "0000251",
+            label: "deoxyribonucleic acid",
+            parent: "0000246",
+        },
+        RNA {
+            namespace: SBO_NS,
+            id: "0000250",
+            label: "ribonucleic acid",
+            parent: "0000246",
+        },
+        Protein {
+            namespace: SBO_NS,
+            id: "0000252",
+            label: "polypeptide chain",
+            parent: "0000246",
+        },
+        SimpleChemical {
+            namespace: SBO_NS,
+            id: "0000249",
+            label: "simple chemical",
+            parent: "0000240",
+        },
+        NonCovalentComplex {
+            namespace: SBO_NS,
+            id: "0000253",
+            label: "non-covalent complex",
+            parent: "0000240",
+        },
+        FunctionalEntity {
+            namespace: SBO_NS,
+            id: "0000241",
+            label: "functional entity",
+            parent: "0000236",
+        },
     }
 }
 
-/// Topology type field ontologies for `Component`
-/// Pulled from SBOL 3 spec, Section 6.4, Table ___
-pub enum TopologyOntology {
-    Linear,
-    Circular,
-    SingleStranded,
-    DoubleStranded,
-    Other(&'static str),
-}
-impl Ontology for TopologyOntology {
-    fn uri(&self) -> Url {
-        let uri = match self {
-            Self::Linear => SO_NS.to_string() + "0000987",
-            Self::Circular => SO_NS.to_string() + "0000988",
-            Self::SingleStranded => SO_NS.to_string() + "0000984",
-            Self::DoubleStranded => SO_NS.to_string() + "0000985",
-            Self::Other(uri) => return Url::parse(uri).expect(INVALID_URI),
-        };
-        Url::parse(uri.as_str()).expect(JOIN_ERROR_MSG)
+ontology! {
+    /// Topology type field ontologies for `Component`
+    /// Pulled from SBOL 3 spec, Section 6.4, Table ___
+    pub enum TopologyOntology(Other) {
+        Linear {
+            namespace: SO_NS,
+            id: "0000987",
+            label: "linear",
+            parent: "0000986",
+        },
+        Circular {
+            namespace: SO_NS,
+            id: "0000988",
+            label: "circular",
+            parent: "0000986",
+        },
+        SingleStranded {
+            namespace: SO_NS,
+            id: "0000984",
+            label: "single",
+            parent: "0000983",
+        },
+        DoubleStranded {
+            namespace: SO_NS,
+            id: "0000985",
+            label: "double",
+            parent: "0000983",
+        },
     }
 }
 
-/// Describe the role of a `Component`
-///
-/// Might describe the role properties of a protein or simple chemical component, but can also
-/// identify biological roles, such as "metabolic pathway" and "signaling cascade", or more
-/// abstract roles describing the function of design such as "logical" roles (i.e: "inverter" or
-/// "AND gate"). Interpretation of the meaning of such roles currently depends on the software
-/// tools that read and write them.
-///
-/// Variants must align with `ComponentTypeOntology` and must not conflict.
-#[allow(non_camel_case_types)]
-pub enum ComponentRole {
-    Promoter,
-    RBS,
-    CDS,
-    Terminator,
-    Gene,
-    Operator,
-    EngineeredRegion,
-    mRNA,
-    Effector,
-    TranscriptionFactor,
-    Other(&'static str),
-}
-impl Ontology for ComponentRole {
-    fn uri(&self) -> Url {
-        let uri = match self {
-            Self::Promoter => SO_NS.to_string() + "0000167",
-            Self::RBS => SO_NS.to_string() + "0000139",
-            Self::CDS => SO_NS.to_string() + "0000316",
-            Self::Terminator => SO_NS.to_string() + "0000141",
-            Self::Gene => SO_NS.to_string() + "0000704",
-            Self::Operator => SO_NS.to_string() + "0000057",
-            Self::EngineeredRegion => SO_NS.to_string() + "0000804",
-            Self::mRNA => SO_NS.to_string() + "0000234",
-            Self::Effector => CHEBI_NS.to_string() + "35224",
-            Self::TranscriptionFactor => GO_NS.to_string() + "0003700",
-            Self::Other(uri) => return Url::parse(uri).expect(INVALID_URI),
-        };
-        Url::parse(uri.as_str()).expect(JOIN_ERROR_MSG)
+ontology! {
+    /// Describe the role of a `Component`
+    ///
+    /// Might describe the role properties of a protein or simple chemical component, but can also
+    /// identify biological roles, such as "metabolic pathway" and "signaling cascade", or more
+    /// abstract roles describing the function of design such as "logical" roles (i.e: "inverter" or
+    /// "AND gate"). Interpretation of the meaning of such roles currently depends on the software
+    /// tools that read and write them.
+    ///
+    /// Variants must align with `ComponentTypeOntology` and must not conflict.
+    #[allow(non_camel_case_types)]
+    pub enum ComponentRole(Other) {
+        Promoter {
+            namespace: SO_NS,
+            id: "0000167",
+            label: "promoter",
+            parent: "0001055",
+        },
+        RBS {
+            namespace: SO_NS,
+            id: "0000139",
+            label: "ribosome_entry_site",
+            parent: "0000837",
+        },
+        CDS {
+            namespace: SO_NS,
+            id: "0000316",
+            label: "CDS",
+            parent: "0000836",
+        },
+        Terminator {
+            namespace: SO_NS,
+            id: "0000141",
+            label: "terminator",
+            parent: "0005836",
+        },
+        Gene {
+            namespace: SO_NS,
+            id: "0000704",
+            label: "gene",
+            parent: "0001411",
+        },
+        Operator {
+            namespace: SO_NS,
+            id: "0000057",
+            label: "operator",
+            parent: "0000752",
+        },
+        EngineeredRegion {
+            namespace: SO_NS,
+            id: "0000804",
+            label: "engineered_region",
+            parent: "0001409",
+        },
+        mRNA {
+            namespace: SO_NS,
+            id: "0000234",
+            label: "mRNA",
+            parent: "0000233",
+        },
+        Effector {
+            namespace: CHEBI_NS,
+            id: "35224",
+            label: "effector",
+            parent: "24432",
+        },
+        TranscriptionFactor {
+            namespace: GO_NS,
+            id: "0003700",
+            label: "DNA-binding transcription factor activity",
+            parent: "0140110",
+        },
     }
 }
 
@@ -97,7 +155,10 @@ impl Ontology for ComponentRole {
 mod tests {
     use url::Url;
 
-    use crate::ontologies::{ComponentRole, ComponentTypeOntology, Ontology, TopologyOntology};
+    use crate::ontologies::{
+        ComponentRole, ComponentTypeOntology, Ontology, TopologyOntology, SO_NS,
+    };
+    use crate::SbolError;
 
     #[test]
     fn test_topology_ontology() {
@@ -119,12 +180,12 @@ mod tests {
                 "https://identifiers.org/SO:0000985",
             ),
             (
-                TopologyOntology::Other("https://test.org"),
+                TopologyOntology::Other(Url::parse("https://test.org").unwrap()),
                 "https://test.org",
             ),
         ];
         for (variant, expected) in variants.iter() {
-            assert_eq!(variant.uri(), Url::parse(expected).unwrap())
+            assert_eq!(variant.uri().unwrap(), Url::parse(expected).unwrap())
         }
     }
 
@@ -157,7 +218,7 @@ mod tests {
             ),
         ];
         for (variant, expected) in variants.iter() {
-            assert_eq!(variant.uri(), Url::parse(expected).unwrap())
+            assert_eq!(variant.uri().unwrap(), Url::parse(expected).unwrap())
         }
     }
 
@@ -192,10 +253,118 @@ mod tests {
                 ComponentRole::TranscriptionFactor,
                 "https://identifiers.org/GO:0003700",
             ),
-            (ComponentRole::Other("https://test.com"), "https://test.com"),
+            (
+                ComponentRole::Other(Url::parse("https://test.com").unwrap()),
+                "https://test.com",
+            ),
         ];
         for (variant, expected) in variants.iter() {
-            assert_eq!(variant.uri(), Url::parse(expected).unwrap())
+            assert_eq!(variant.uri().unwrap(), Url::parse(expected).unwrap())
+        }
+    }
+
+    #[test]
+    fn test_topology_from_uri() {
+        let variants = [
+            (
+                "http://identifiers.org/SO:0000987",
+                TopologyOntology::Linear,
+            ),
+            (
+                "https://identifiers.org/so/SO:0000988",
+                TopologyOntology::Circular,
+            ),
+            (
+                "http://purl.obolibrary.org/obo/SO_0000985",
+                TopologyOntology::DoubleStranded,
+            ),
+        ];
+        for (uri, expected) in variants.iter() {
+            assert_eq!(&uri.parse::<TopologyOntology>().unwrap(), expected)
         }
     }
+
+    #[test]
+    fn test_component_type_from_uri() {
+        let variants = [
+            (
+                "https://identifiers.org/SBO:0000251",
+                ComponentTypeOntology::DNA,
+            ),
+            (
+                "http://identifiers.org/biomodels.sbo/SBO:0000252",
+                ComponentTypeOntology::Protein,
+            ),
+            (
+                "http://purl.obolibrary.org/obo/SBO_0000249",
+                ComponentTypeOntology::SimpleChemical,
+            ),
+        ];
+        for (uri, expected) in variants.iter() {
+            let uri = Url::parse(uri).unwrap();
+            assert_eq!(&ComponentTypeOntology::from(uri), expected)
+        }
+    }
+
+    #[test]
+    fn test_component_role_hierarchy() {
+        let role = ComponentRole::Promoter;
+        assert_eq!(role.label(), Some("promoter"));
+        assert_eq!(role.namespace(), Some(SO_NS));
+        assert_eq!(
+            role.parent(),
+            Some(Url::parse("https://identifiers.org/SO:0001055").unwrap())
+        );
+
+        let other = ComponentRole::Other(Url::parse("https://test.com/role").unwrap());
+        assert_eq!(other.label(), None);
+        assert_eq!(other.parent(), None);
+    }
+
+    #[test]
+    fn test_component_role_from_uri() {
+        let variants = [
+            (
+                "https://identifiers.org/SO:0000167",
+                ComponentRole::Promoter,
+            ),
+            ("http://identifiers.org/SO:0000139", ComponentRole::RBS),
+            (
+                "http://purl.obolibrary.org/obo/SO_0000316",
+                ComponentRole::CDS,
+            ),
+            (
+                "http://identifiers.org/chebi/CHEBI:35224",
+                ComponentRole::Effector,
+            ),
+            (
+                "http://purl.obolibrary.org/obo/GO_0003700",
+                ComponentRole::TranscriptionFactor,
+            ),
+            (
+                "https://test.com/role",
+                ComponentRole::Other(Url::parse("https://test.com/role").unwrap()),
+            ),
+        ];
+        for (uri, expected) in variants.iter() {
+            assert_eq!(&uri.parse::<ComponentRole>().unwrap(), expected)
+        }
+        assert!("not a uri".parse::<ComponentRole>().is_err());
+    }
+
+    #[test]
+    fn test_component_role_other() {
+        assert!(matches!(
+            ComponentRole::other("https://test.com/role"),
+            Ok(ComponentRole::Other(_))
+        ));
+        assert!(matches!(
+            ComponentRole::other("not a uri"),
+            Err(SbolError::Parse(_))
+        ));
+        assert!(matches!(
+            ComponentRole::other("http://identifiers.org/SO:0000167"),
+            Err(SbolError::Validation(_))
+        ));
+    }
 }
@@ -0,0 +1,112 @@
+use crate::ontology;
+
+use super::SBOL3_NS;
+
+ontology! {
+    /// Relationship between the subject and object `Feature` objects of a `Constraint`.
+    /// Pulled from SBOL 3 spec, Section 6.4, Tables 10 to 13
+    pub enum Restriction(Other) {
+        /// The subject and object refer to the same instance
+        VerifyIdentical {
+            namespace: SBOL3_NS,
+            id: "verifyIdentical",
+            label: "verifyIdentical",
+        },
+        /// The subject and object refer to different instances
+        DifferentFrom {
+            namespace: SBOL3_NS,
+            id: "differentFrom",
+            label: "differentFrom",
+        },
+        /// The subject replaces the object, ie: a `ComponentReference` replaces a `SubComponent`
+        Replaces {
+            namespace: SBOL3_NS,
+            id: "replaces",
+            label: "replaces",
+        },
+        IsDisjointFrom {
+            namespace: SBOL3_NS,
+            id: "isDisjointFrom",
+            label: "isDisjointFrom",
+        },
+        StrictlyContains {
+            namespace: SBOL3_NS,
+            id: "strictlyContains",
+            label: "strictlyContains",
+        },
+        Contains {
+            namespace: SBOL3_NS,
+            id: "contains",
+            label: "contains",
+        },
+        Equals {
+            namespace: SBOL3_NS,
+            id: "equals",
+            label: "equals",
+        },
+        /// The end of the subject is immediately followed by the start of the object
+        Meets {
+            namespace: SBOL3_NS,
+            id: "meets",
+            label: "meets",
+        },
+        Covers {
+            namespace: SBOL3_NS,
+            id: "covers",
+            label: "covers",
+        },
+        Overlaps {
+            namespace: SBOL3_NS,
+            id: "overlaps",
+            label: "overlaps",
+        },
+        /// The subject is located before the object, possibly with a gap in between
+        Precedes {
+            namespace: SBOL3_NS,
+            id: "precedes",
+            label: "precedes",
+        },
+        StrictlyPrecedes {
+            namespace: SBOL3_NS,
+            id: "strictlyPrecedes",
+            label: "strictlyPrecedes",
+        },
+        Finishes {
+            namespace: SBOL3_NS,
+            id: "finishes",
+            label: "finishes",
+        },
+        Starts {
+            namespace: SBOL3_NS,
+            id: "starts",
+            label: "starts",
+        },
+        SameOrientationAs {
+            namespace: SBOL3_NS,
+            id: "sameOrientationAs",
+            label: "sameOrientationAs",
+        },
+        OppositeOrientationAs {
+            namespace: SBOL3_NS,
+            id: "oppositeOrientationAs",
+            label: "oppositeOrientationAs",
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use url::Url;
+
+    use crate::ontologies::{Ontology, Restriction};
+
+    #[test]
+    fn test_restriction() {
+        assert_eq!(
+            Restriction::Precedes.uri().unwrap().as_str(),
+            "https://sbols.org/v3#precedes"
+        );
+        let uri = Url::parse("http://sbols.org/v3#replaces").unwrap();
+        assert_eq!(Restriction::from(uri), Restriction::Replaces);
+    }
+}
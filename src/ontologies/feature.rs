@@ -1,26 +1,55 @@
-use url::Url;
+use crate::ontology;
 
-use super::{Ontology, INVALID_URI, SBOL3_NS, SO_NS};
+use super::{SBOL3_NS, SO_NS};
 
-pub enum Orientation {
-    /// The region specified by this `Feature` or `Location` is on the `elements` of a `Sequence`
-    Inline,
-    /// The region specified by this `Feature` or `Location` is on the reverse-complement mapping
-    /// of the `elements of a `Sequence`. The exact nature of this mapping depends on the
-    /// `encoding` of the `Sequence`.
-    ReverseComplement,
-    InlineAlt,
-    ReverseComplementAlt,
+ontology! {
+    pub enum Orientation {
+        /// The region specified by this `Feature` or `Location` is on the `elements` of a `Sequence`
+        Inline {
+            namespace: SO_NS,
+            id: "0001030",
+            label: "forward",
+            parent: "0001029",
+        },
+        /// The region specified by this `Feature` or `Location` is on the reverse-complement mapping
+        /// of the `elements of a `Sequence`. The exact nature of this mapping depends on the
+        /// `encoding` of the `Sequence`.
+        ReverseComplement {
+            namespace: SO_NS,
+            id: "0001031",
+            label: "reverse",
+            parent: "0001029",
+        },
+        InlineAlt {
+            namespace: SBOL3_NS,
+            id: "inline",
+            label: "inline",
+        },
+        ReverseComplementAlt {
+            namespace: SBOL3_NS,
+            id: "reverseComplement",
+            label: "reverseComplement",
+        },
+    }
 }
-impl Ontology for Orientation {
-    fn uri(&self) -> url::Url {
-        let uri = match self {
-            Self::Inline => SO_NS.to_string() + "0001030",
-            Self::ReverseComplement => SO_NS.to_string() + "0001031",
-            Self::InlineAlt => SBOL3_NS.to_string() + "inline",
-            Self::ReverseComplementAlt => SBOL3_NS.to_string() + "reverseComplement",
-        };
-        Url::parse(uri.as_str()).expect(INVALID_URI)
+
+ontology! {
+    /// Relationship between the `role` properties of a `SubComponent` and the `role` properties
+    /// of the `Component` that it is an `instance_of`.
+    pub enum RoleIntegration {
+        /// The `role` properties of the `SubComponent` replace those of the included `Component`
+        OverrideRoles {
+            namespace: SBOL3_NS,
+            id: "overrideRoles",
+            label: "overrideRoles",
+        },
+        /// The `role` properties of the `SubComponent` are added to those of the included
+        /// `Component`
+        MergeRoles {
+            namespace: SBOL3_NS,
+            id: "mergeRoles",
+            label: "mergeRoles",
+        },
     }
 }
 
@@ -29,29 +58,56 @@ mod tests {
     use url::Url;
 
     use crate::ontologies::{Ontology, Orientation};
+    use crate::SbolError;
 
     #[test]
     fn test_inline() {
         let val = Orientation::Inline;
         let expected = Url::parse("https://identifiers.org/SO:0001030").unwrap();
-        assert_eq!(val.uri(), expected);
+        assert_eq!(val.uri().unwrap(), expected);
     }
     #[test]
     fn test_reverse_complement() {
         let val = Orientation::ReverseComplement;
         let expected = Url::parse("https://identifiers.org/SO:0001031").unwrap();
-        assert_eq!(val.uri(), expected);
+        assert_eq!(val.uri().unwrap(), expected);
     }
     #[test]
     fn test_inline_alt() {
         let val = Orientation::InlineAlt;
         let expected = Url::parse("https://sbols.org/v3#inline").unwrap();
-        assert_eq!(val.uri(), expected);
+        assert_eq!(val.uri().unwrap(), expected);
     }
     #[test]
     fn test_reverse_complement_alt() {
         let val = Orientation::ReverseComplementAlt;
         let expected = Url::parse("https://sbols.org/v3#reverseComplement").unwrap();
-        assert_eq!(val.uri(), expected);
+        assert_eq!(val.uri().unwrap(), expected);
+    }
+    #[test]
+    fn test_from_uri() {
+        let variants = [
+            ("http://identifiers.org/SO:0001030", Orientation::Inline),
+            (
+                "http://purl.obolibrary.org/obo/SO_0001031",
+                Orientation::ReverseComplement,
+            ),
+            ("http://sbols.org/v3#inline", Orientation::InlineAlt),
+            (
+                "https://sbols.org/v3#reverseComplement",
+                Orientation::ReverseComplementAlt,
+            ),
+        ];
+        for (uri, expected) in variants.iter() {
+            assert_eq!(&uri.parse::<Orientation>().unwrap(), expected)
+        }
+    }
+    #[test]
+    fn test_unknown_term() {
+        let uri = Url::parse("https://test.org").unwrap();
+        assert!(matches!(
+            Orientation::try_from(uri),
+            Err(SbolError::Parse(_))
+        ));
     }
 }
@@ -0,0 +1,130 @@
+use crate::ontology;
+
+use super::SBO_NS;
+
+ontology! {
+    /// Interaction Type Ontologies
+    /// Pulled from SBOL 3 spec, Section 6.4, Table 14
+    pub enum InteractionType(Other) {
+        Inhibition {
+            namespace: SBO_NS,
+            id: "0000169",
+            label: "inhibition",
+            parent: "0000168",
+        },
+        Stimulation {
+            namespace: SBO_NS,
+            id: "0000170",
+            label: "stimulation",
+            parent: "0000168",
+        },
+        BiochemicalReaction {
+            namespace: SBO_NS,
+            id: "0000176",
+            label: "biochemical reaction",
+        },
+        NonCovalentBinding {
+            namespace: SBO_NS,
+            id: "0000177",
+            label: "non-covalent binding",
+        },
+        Degradation {
+            namespace: SBO_NS,
+            id: "0000179",
+            label: "degradation",
+        },
+        GeneticProduction {
+            namespace: SBO_NS,
+            id: "0000589",
+            label: "genetic production",
+        },
+        Control {
+            namespace: SBO_NS,
+            id: "0000168",
+            label: "control",
+        },
+    }
+}
+
+ontology! {
+    /// Participation Role Ontologies
+    /// Pulled from SBOL 3 spec, Section 6.4, Table 15
+    pub enum ParticipationRole(Other) {
+        Inhibitor {
+            namespace: SBO_NS,
+            id: "0000020",
+            label: "inhibitor",
+        },
+        Inhibited {
+            namespace: SBO_NS,
+            id: "0000642",
+            label: "inhibited",
+        },
+        Stimulator {
+            namespace: SBO_NS,
+            id: "0000459",
+            label: "stimulator",
+        },
+        Stimulated {
+            namespace: SBO_NS,
+            id: "0000643",
+            label: "stimulated",
+        },
+        Reactant {
+            namespace: SBO_NS,
+            id: "0000010",
+            label: "reactant",
+        },
+        Product {
+            namespace: SBO_NS,
+            id: "0000011",
+            label: "product",
+        },
+        Promoter {
+            namespace: SBO_NS,
+            id: "0000598",
+            label: "promoter",
+        },
+        Modifier {
+            namespace: SBO_NS,
+            id: "0000019",
+            label: "modifier",
+        },
+        Modified {
+            namespace: SBO_NS,
+            id: "0000644",
+            label: "modified",
+        },
+        Template {
+            namespace: SBO_NS,
+            id: "0000645",
+            label: "template",
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use url::Url;
+
+    use crate::ontologies::{InteractionType, Ontology, ParticipationRole};
+
+    #[test]
+    fn test_interaction_type() {
+        let uri = Url::parse("http://identifiers.org/biomodels.sbo/SBO:0000169").unwrap();
+        assert_eq!(InteractionType::from(uri), InteractionType::Inhibition);
+        assert_eq!(
+            InteractionType::Inhibition.parent(),
+            Some(InteractionType::Control.uri().unwrap())
+        );
+    }
+
+    #[test]
+    fn test_participation_role() {
+        assert_eq!(
+            ParticipationRole::Template.uri().unwrap().as_str(),
+            "https://identifiers.org/SBO:0000645"
+        );
+        assert_eq!(ParticipationRole::Inhibitor.label(), Some("inhibitor"));
+    }
+}
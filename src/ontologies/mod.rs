@@ -1,22 +1,235 @@
 mod component;
+mod constraint;
 mod feature;
+mod interaction;
 mod namespaces;
 mod sequence;
+mod sequence_ontology;
 
 pub use component::*;
+pub use constraint::*;
 pub use feature::*;
+pub use interaction::*;
 pub use namespaces::*;
 pub use sequence::*;
+pub use sequence_ontology::*;
 
 use url::Url;
 
-/// Generic error message when joining URI/URL
-const JOIN_ERROR_MSG: &str = "Error joining URI";
-
-/// Generic error message when parsing URI/URL
-const INVALID_URI: &str = "Error parsing URI";
+use crate::SbolError;
 
 /// Interface for strictly type-checked ontologies
-trait Ontology {
-    fn uri(&self) -> Url;
+///
+/// Implementations are usually generated with the [`ontology!`](crate::ontology) macro, which
+/// allows downstream crates to define their own controlled vocabularies (ie: lab-specific roles,
+/// or the SBOL Visual glyph ontology) alongside the vocabularies provided by this crate.
+pub trait Ontology {
+    /// URI which identifies this term
+    ///
+    /// Fails if the namespace and id of the term do not form a valid URI.
+    fn uri(&self) -> Result<Url, SbolError>;
+
+    /// Human readable label of this term
+    ///
+    /// Returns `None` for terms which are not known to the vocabulary.
+    fn label(&self) -> Option<&'static str>;
+
+    /// URI of the parent term in the ontology hierarchy (ie: the `is_a` relation)
+    fn parent(&self) -> Option<Url>;
+
+    /// Namespace which contains this term (ie: [`SO_NS`])
+    ///
+    /// Returns `None` for terms which are not known to the vocabulary.
+    fn namespace(&self) -> Option<&'static str>;
+
+    /// All terms known to the vocabulary
+    fn terms() -> Vec<Self>
+    where
+        Self: Sized;
+
+    /// Find the known term that is identified by `uri`
+    ///
+    /// `uri` is compared in its canonical form, so any of the http/https, `identifiers.org` and
+    /// `purl.obolibrary.org` variants of a term are accepted.
+    fn from_uri(uri: &Url) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let canonical = canonical_uri(uri);
+        Self::terms()
+            .into_iter()
+            .find(|term| term.uri().ok().as_ref() == Some(&canonical))
+    }
+}
+
+/// Generate an ontology enum along with its [`Ontology`] implementation.
+///
+/// Each variant declares the `namespace` and `id` which together form the URI of the term, a
+/// human readable `label`, and optionally the `id` of its `parent` term within the same namespace.
+///
+/// An enum declared as `enum Name(Other)` is an open vocabulary: unknown URIs are retained in the
+/// `Other(Url)` variant, and `From<Url>` is implemented. Otherwise the vocabulary is closed and
+/// `TryFrom<Url>` rejects unknown terms. Both implement `FromStr`. Open vocabularies also provide a
+/// fallible `other()` constructor, which never panics on invalid input.
+///
+/// # Example
+/// ```
+/// use sbol::ontology;
+/// use sbol::ontologies::Ontology;
+///
+/// const SBOLV_NS: &str = "https://identifiers.org/SBOLV:";
+///
+/// ontology! {
+///     /// Glyphs of the SBOL Visual ontology
+///     pub enum Glyph(Other) {
+///         Promoter {
+///             namespace: SBOLV_NS,
+///             id: "0000001",
+///             label: "promoter",
+///         },
+///         Insulator {
+///             namespace: SBOLV_NS,
+///             id: "0000002",
+///             label: "insulator",
+///         },
+///     }
+/// }
+///
+/// let glyph: Glyph = "http://identifiers.org/SBOLV:0000001".parse().unwrap();
+/// assert_eq!(glyph, Glyph::Promoter);
+/// assert_eq!(glyph.label(), Some("promoter"));
+/// assert!(Glyph::other("not a uri").is_err());
+/// ```
+#[macro_export]
+macro_rules! ontology {
+    (
+        $(#[$meta:meta])*
+        $vis:vis enum $name:ident($other:ident) {
+            $($body:tt)*
+        }
+    ) => {
+        $crate::ontology!(@enum $(#[$meta])* $vis $name [$other(#[doc = "Term which is not known to this vocabulary"] $crate::url::Url)] $($body)*);
+
+        impl $name {
+            /// Construct a term which is not known to this vocabulary.
+            ///
+            /// Fails if `uri` is not a valid URI, or if it identifies a known term, in which case
+            /// the corresponding variant must be used instead.
+            pub fn other(uri: &str) -> ::std::result::Result<Self, $crate::SbolError> {
+                let uri = $crate::url::Url::parse(uri)?;
+                match <Self as $crate::ontologies::Ontology>::from_uri(&uri) {
+                    Some(term) => Err($crate::SbolError::Validation(format!(
+                        "{uri} identifies the known term {term:?}"
+                    ))),
+                    None => Ok(Self::$other(uri)),
+                }
+            }
+        }
+        impl ::std::convert::From<$crate::url::Url> for $name {
+            /// Unknown terms are retained in the open variant
+            fn from(uri: $crate::url::Url) -> Self {
+                <Self as $crate::ontologies::Ontology>::from_uri(&uri).unwrap_or(Self::$other(uri))
+            }
+        }
+        impl ::std::str::FromStr for $name {
+            type Err = $crate::SbolError;
+
+            fn from_str(s: &str) -> ::std::result::Result<Self, Self::Err> {
+                Ok($crate::url::Url::parse(s)?.into())
+            }
+        }
+    };
+    (
+        $(#[$meta:meta])*
+        $vis:vis enum $name:ident {
+            $($body:tt)*
+        }
+    ) => {
+        $crate::ontology!(@enum $(#[$meta])* $vis $name [] $($body)*);
+
+        impl ::std::convert::TryFrom<$crate::url::Url> for $name {
+            type Error = $crate::SbolError;
+
+            /// The vocabulary is closed, therefore unknown terms are rejected.
+            fn try_from(uri: $crate::url::Url) -> ::std::result::Result<Self, Self::Error> {
+                <Self as $crate::ontologies::Ontology>::from_uri(&uri)
+                    .ok_or_else(|| $crate::SbolError::Parse(format!("Unknown ontology term: {uri}")))
+            }
+        }
+        impl ::std::str::FromStr for $name {
+            type Err = $crate::SbolError;
+
+            fn from_str(s: &str) -> ::std::result::Result<Self, Self::Err> {
+                $crate::url::Url::parse(s)?.try_into()
+            }
+        }
+    };
+    (
+        @enum $(#[$meta:meta])* $vis:vis $name:ident [$($other:ident($(#[$other_meta:meta])* $other_ty:ty))?]
+        $(
+            $(#[$variant_meta:meta])*
+            $variant:ident {
+                namespace: $ns:expr,
+                id: $id:expr,
+                label: $label:expr
+                $(, parent: $parent:expr)?
+                $(,)?
+            }
+        ),*
+        $(,)?
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        $vis enum $name {
+            $(
+                $(#[$variant_meta])*
+                $variant,
+            )*
+            $(
+                $(#[$other_meta])*
+                $other($other_ty),
+            )?
+        }
+
+        impl $crate::ontologies::Ontology for $name {
+            fn uri(&self) -> ::std::result::Result<$crate::url::Url, $crate::SbolError> {
+                let uri = match self {
+                    $(Self::$variant => format!("{}{}", $ns, $id),)*
+                    $(Self::$other(uri) => return Ok(uri.clone()),)?
+                };
+                Ok($crate::url::Url::parse(&uri)?)
+            }
+
+            fn label(&self) -> ::std::option::Option<&'static str> {
+                match self {
+                    $(Self::$variant => Some($label),)*
+                    $(Self::$other(_) => None,)?
+                }
+            }
+
+            fn parent(&self) -> ::std::option::Option<$crate::url::Url> {
+                match self {
+                    $(Self::$variant => $crate::ontology!(@parent $ns $(, $parent)?),)*
+                    $(Self::$other(_) => None,)?
+                }
+            }
+
+            fn namespace(&self) -> ::std::option::Option<&'static str> {
+                match self {
+                    $(Self::$variant => Some($ns),)*
+                    $(Self::$other(_) => None,)?
+                }
+            }
+
+            fn terms() -> ::std::vec::Vec<Self> {
+                vec![$(Self::$variant),*]
+            }
+        }
+    };
+    (@parent $ns:expr) => {
+        None
+    };
+    (@parent $ns:expr, $parent:expr) => {
+        $crate::url::Url::parse(&format!("{}{}", $ns, $parent)).ok()
+    };
 }
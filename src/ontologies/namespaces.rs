@@ -1,6 +1,117 @@
+use url::Url;
+
 pub const CHEBI_NS: &str = "https://identifiers.org/CHEBI:";
 pub const EDAM_NS: &str = "https://identifiers.org/edam:";
 pub const GO_NS: &str = "https://identifiers.org/GO:";
 pub const SBO_NS: &str = "https://identifiers.org/SBO:";
 pub const SBOL3_NS: &str = "https://sbols.org/v3#";
 pub const SO_NS: &str = "https://identifiers.org/SO:";
+
+/// Host used by the canonical form of every ontology term URI
+const IDENTIFIERS_HOST: &str = "identifiers.org";
+
+/// Rewrite an ontology term URI into the canonical form used by this crate.
+///
+/// Ontology terms are published under several equivalent URIs. Each of the following is mapped to
+/// `https://identifiers.org/SO:0000167`:
+///
+/// - `http://identifiers.org/SO:0000167`
+/// - `http://identifiers.org/so/SO:0000167`
+/// - `http://purl.obolibrary.org/obo/SO_0000167`
+///
+/// Similarly, `http://edamontology.org/format_1207` becomes `https://identifiers.org/edam:format_1207`
+/// and `http://sbols.org/v3#inline` becomes `https://sbols.org/v3#inline`.
+///
+/// URIs which are not recognized as ontology terms are returned unchanged.
+pub fn canonical_uri(uri: &Url) -> Url {
+    let host = uri
+        .host_str()
+        .unwrap_or_default()
+        .trim_start_matches("www.");
+    let segments: Vec<&str> = uri
+        .path_segments()
+        .map(|segments| segments.filter(|s| !s.is_empty()).collect())
+        .unwrap_or_default();
+
+    let curie = match (host, segments.as_slice()) {
+        (IDENTIFIERS_HOST, [curie]) => Some(curie.to_string()),
+        (IDENTIFIERS_HOST, [namespace, term]) => {
+            if term.contains(':') {
+                Some(term.to_string())
+            } else {
+                Some(format!("{namespace}:{term}"))
+            }
+        }
+        ("purl.obolibrary.org", ["obo", term]) => term
+            .split_once('_')
+            .map(|(prefix, id)| format!("{prefix}:{id}")),
+        ("edamontology.org", [term]) => Some(format!("edam:{term}")),
+        ("sbols.org", _) => {
+            let mut canonical = uri.clone();
+            return match canonical.set_scheme("https") {
+                Ok(()) => canonical,
+                Err(()) => uri.clone(),
+            };
+        }
+        _ => None,
+    };
+
+    curie
+        .and_then(|curie| {
+            let (prefix, id) = curie.split_once(':')?;
+            let prefix = if prefix.eq_ignore_ascii_case("edam") {
+                "edam".to_string()
+            } else {
+                prefix.to_ascii_uppercase()
+            };
+            Url::parse(&format!("https://{IDENTIFIERS_HOST}/{prefix}:{id}")).ok()
+        })
+        .unwrap_or_else(|| uri.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use url::Url;
+
+    use super::canonical_uri;
+
+    #[test]
+    fn test_canonical_uri() {
+        let variants = [
+            (
+                "https://identifiers.org/SO:0000167",
+                "https://identifiers.org/SO:0000167",
+            ),
+            (
+                "http://identifiers.org/SO:0000167",
+                "https://identifiers.org/SO:0000167",
+            ),
+            (
+                "http://identifiers.org/so/SO:0000167",
+                "https://identifiers.org/SO:0000167",
+            ),
+            (
+                "http://purl.obolibrary.org/obo/SO_0000167",
+                "https://identifiers.org/SO:0000167",
+            ),
+            (
+                "http://identifiers.org/biomodels.sbo/SBO:0000251",
+                "https://identifiers.org/SBO:0000251",
+            ),
+            (
+                "http://edamontology.org/format_1207",
+                "https://identifiers.org/edam:format_1207",
+            ),
+            (
+                "http://www.edamontology.org/format_1207",
+                "https://identifiers.org/edam:format_1207",
+            ),
+            ("http://sbols.org/v3#inline", "https://sbols.org/v3#inline"),
+            ("https://test.org/term", "https://test.org/term"),
+        ];
+        for (uri, expected) in variants.iter() {
+            let uri = Url::parse(uri).unwrap();
+            assert_eq!(canonical_uri(&uri), Url::parse(expected).unwrap())
+        }
+    }
+}
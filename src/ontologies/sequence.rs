@@ -1,33 +1,40 @@
-use url::Url;
+use crate::ontologies::EDAM_NS;
+use crate::ontology;
 
-use crate::ontologies::{Ontology, EDAM_NS, INVALID_URI};
-
-/// Indicates how `Sequence::elements` are formed and interpreted
-/// Pulled from SBOL 3 spec, Chapter 6.3, Table 1
-///
-/// # Variants
-/// - NucleicAcid => IUPAC DNA, RNA
-/// - Protein => IUPAC DNA, RNA
-/// - InChl =>
-/// - SMILES => Atoms and chemical bonds of a small molecule
-pub enum Encoding {
-    NucleicAcid,
-    Protein,
-    InChl,
-    SMILES,
-    Other(&'static str),
-}
-impl Ontology for Encoding {
-    fn uri(&self) -> url::Url {
-        let uri = EDAM_NS.to_string()
-            + match self {
-                Self::NucleicAcid => "format_1207",
-                Self::Protein => "format_1208",
-                Self::InChl => "format_1197",
-                Self::SMILES => "format_1196",
-                Self::Other(uri) => return Url::parse(uri).expect(INVALID_URI),
-            };
-        Url::parse(uri.as_str()).expect(INVALID_URI)
+ontology! {
+    /// Indicates how `Sequence::elements` are formed and interpreted
+    /// Pulled from SBOL 3 spec, Chapter 6.3, Table 1
+    ///
+    /// # Variants
+    /// - NucleicAcid => IUPAC DNA, RNA
+    /// - Protein => IUPAC DNA, RNA
+    /// - InChl =>
+    /// - SMILES => Atoms and chemical bonds of a small molecule
+    pub enum Encoding(Other) {
+        NucleicAcid {
+            namespace: EDAM_NS,
+            id: "format_1207",
+            label: "nucleotide",
+            parent: "format_2571",
+        },
+        Protein {
+            namespace: EDAM_NS,
+            id: "format_1208",
+            label: "protein",
+            parent: "format_2571",
+        },
+        InChl {
+            namespace: EDAM_NS,
+            id: "format_1197",
+            label: "InChI",
+            parent: "format_2035",
+        },
+        SMILES {
+            namespace: EDAM_NS,
+            id: "format_1196",
+            label: "SMILES",
+            parent: "format_2035",
+        },
     }
 }
 
@@ -50,10 +57,32 @@ mod tests {
             ),
             (Encoding::InChl, "https://identifiers.org/edam:format_1197"),
             (Encoding::SMILES, "https://identifiers.org/edam:format_1196"),
-            (Encoding::Other("https://test.org"), "https://test.org"),
+            (
+                Encoding::Other(Url::parse("https://test.org").unwrap()),
+                "https://test.org",
+            ),
         ];
         for (variant, expected) in variants.iter() {
-            assert_eq!(variant.uri(), Url::parse(expected).unwrap())
+            assert_eq!(variant.uri().unwrap(), Url::parse(expected).unwrap())
+        }
+    }
+
+    #[test]
+    fn test_encoding_from_uri() {
+        let variants = [
+            (
+                "http://identifiers.org/edam:format_1207",
+                Encoding::NucleicAcid,
+            ),
+            ("http://edamontology.org/format_1208", Encoding::Protein),
+            ("http://www.edamontology.org/format_1196", Encoding::SMILES),
+            (
+                "https://test.org",
+                Encoding::Other(Url::parse("https://test.org").unwrap()),
+            ),
+        ];
+        for (uri, expected) in variants.iter() {
+            assert_eq!(&uri.parse::<Encoding>().unwrap(), expected)
         }
     }
 }
@@ -0,0 +1,128 @@
+use url::Url;
+
+use super::{canonical_uri, ComponentRole, Ontology, SO_NS};
+
+/// Names and accessions of common terms of the Sequence Ontology, which are not variants of
+/// `ComponentRole`.
+///
+/// Names are case-sensitive, as in the ontology.
+pub const SO_TERMS: [(&str, &str); 41] = [
+    ("region", "0000001"),
+    ("sequence_feature", "0000110"),
+    ("exon", "0000147"),
+    ("intron", "0000188"),
+    ("five_prime_UTR", "0000204"),
+    ("three_prime_UTR", "0000205"),
+    ("transcript", "0000673"),
+    ("primary_transcript", "0000185"),
+    ("ncRNA", "0000655"),
+    ("rRNA", "0000252"),
+    ("tRNA", "0000253"),
+    ("enhancer", "0000165"),
+    ("regulatory_region", "0005836"),
+    ("operon", "0000178"),
+    ("origin_of_replication", "0000296"),
+    ("origin_of_transfer", "0000724"),
+    ("primer_binding_site", "0005850"),
+    ("primer", "0000112"),
+    ("protein_binding_site", "0000410"),
+    ("binding_site", "0000409"),
+    ("polyA_signal_sequence", "0000551"),
+    ("polyA_site", "0000553"),
+    ("signal_peptide", "0000418"),
+    ("mature_protein_region", "0000419"),
+    ("transit_peptide", "0000725"),
+    ("propeptide", "0001062"),
+    ("repeat_region", "0000657"),
+    ("long_terminal_repeat", "0000286"),
+    ("stem_loop", "0000313"),
+    ("mobile_genetic_element", "0001037"),
+    ("recombination_feature", "0000298"),
+    ("sequence_variant", "0001060"),
+    ("sequence_difference", "0000413"),
+    ("STS", "0000331"),
+    ("gap", "0000730"),
+    ("TSS", "0000315"),
+    ("insulator", "0000627"),
+    ("restriction_enzyme_recognition_site", "0001687"),
+    ("blunt_end_restriction_enzyme_cleavage_site", "0001691"),
+    (
+        "five_prime_sticky_end_restriction_enzyme_cleavage_site",
+        "0001975",
+    ),
+    (
+        "three_prime_sticky_end_restriction_enzyme_cleavage_site",
+        "0001976",
+    ),
+];
+
+/// Role identified by the name (ie: `promoter`) or accession (ie: `SO:0000167`) of a Sequence
+/// Ontology term.
+///
+/// Names of `ComponentRole` variants are their labels. Returns `None` for unknown names.
+pub fn so_role(term: &str) -> Option<ComponentRole> {
+    if let Some(id) = term.strip_prefix("SO:") {
+        return Url::parse(&format!("{SO_NS}{id}")).ok().map(Into::into);
+    }
+    let known = ComponentRole::terms()
+        .into_iter()
+        .find(|role| role.namespace() == Some(SO_NS) && role.label() == Some(term));
+    known.or_else(|| {
+        let (_, id) = SO_TERMS.iter().find(|(name, _)| *name == term)?;
+        Url::parse(&format!("{SO_NS}{id}")).ok().map(Into::into)
+    })
+}
+
+/// Name of the Sequence Ontology term identified by `role`.
+///
+/// Returns `None` if `role` is not a Sequence Ontology term, or if its name is not known.
+pub fn so_name(role: &ComponentRole) -> Option<&'static str> {
+    if role.namespace() == Some(SO_NS) {
+        return role.label();
+    }
+    let id = so_accession(role)?;
+    SO_TERMS
+        .iter()
+        .find(|(_, term)| *term == id)
+        .map(|(name, _)| *name)
+}
+
+/// Numeric part of the accession of the Sequence Ontology term identified by `role`, ie:
+/// `0000167`.
+pub fn so_accession(role: &ComponentRole) -> Option<String> {
+    let uri = canonical_uri(&role.uri().ok()?);
+    uri.as_str().strip_prefix(SO_NS).map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use url::Url;
+
+    use crate::ontologies::{so_accession, so_name, so_role, ComponentRole};
+
+    #[test]
+    fn test_so_role() {
+        assert_eq!(so_role("promoter"), Some(ComponentRole::Promoter));
+        assert_eq!(so_role("ribosome_entry_site"), Some(ComponentRole::RBS));
+        assert_eq!(so_role("SO:0000316"), Some(ComponentRole::CDS));
+        assert_eq!(
+            so_role("five_prime_UTR"),
+            Some(ComponentRole::Other(
+                Url::parse("https://identifiers.org/SO:0000204").unwrap()
+            ))
+        );
+        assert_eq!(so_role("not_a_term"), None);
+    }
+
+    #[test]
+    fn test_so_name() {
+        assert_eq!(so_name(&ComponentRole::Terminator), Some("terminator"));
+        assert_eq!(so_name(&so_role("exon").unwrap()), Some("exon"));
+        assert_eq!(so_name(&ComponentRole::Effector), None);
+
+        let role =
+            ComponentRole::Other(Url::parse("http://purl.obolibrary.org/obo/SO_0000999").unwrap());
+        assert_eq!(so_name(&role), None);
+        assert_eq!(so_accession(&role).as_deref(), Some("0000999"));
+    }
+}
@@ -0,0 +1,248 @@
+//! Resolution of references to objects which are defined in other documents, ie: the parts of a
+//! library stored in another file or repository.
+//!
+//! A [`Resolver`] finds the document which defines a URI. The built-in resolvers load SBOL2
+//! RDF/XML files, converted into SBOL3, from a directory or a zip archive, or keep documents in
+//! memory. SBOL3 files are not read, and are reported as an error rather than skipped. [`Document::resolve`] and [`Document::resolve_references`] consult a resolver for the
+//! URIs which a document is missing.
+//!
+//! Resolvers take `&self` and report failures as an `SbolError`, so that a resolver fetching URIs
+//! over the network can implement the same trait.
+
+use std::fs::{self, File};
+use std::io::{Read, Seek};
+use std::path::Path;
+
+use url::Url;
+
+use crate::formats::sbol2;
+use crate::{Document, SbolError};
+
+/// Extensions of the files read by [`DirectoryResolver`] and [`ZipResolver`]
+const EXTENSIONS: [&str; 3] = ["xml", "rdf", "sbol"];
+
+/// Source of the documents which define objects missing from a `Document`
+pub trait Resolver {
+    /// Document which defines the object identified by `uri`, either as a `TopLevel` or as a
+    /// child of a `TopLevel`, or `None` if the resolver does not know the object
+    fn resolve(&self, uri: &Url) -> Result<Option<Document>, SbolError>;
+}
+
+/// Resolver of the objects of documents kept in memory
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MemoryResolver {
+    pub documents: Vec<Document>,
+}
+
+impl MemoryResolver {
+    pub fn new(documents: Vec<Document>) -> Self {
+        Self { documents }
+    }
+}
+
+impl Resolver for MemoryResolver {
+    fn resolve(&self, uri: &Url) -> Result<Option<Document>, SbolError> {
+        Ok(find(&self.documents, uri))
+    }
+}
+
+/// Resolver of the objects of the SBOL files of a local directory and its subdirectories.
+///
+/// Files with an `.xml`, `.rdf` or `.sbol` extension are read as SBOL2 RDF/XML when the resolver
+/// is created, which fails if one of them is an SBOL3 document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DirectoryResolver {
+    documents: Vec<Document>,
+}
+
+impl DirectoryResolver {
+    pub fn new(path: impl AsRef<Path>) -> Result<Self, SbolError> {
+        let mut documents = vec![];
+        read_directory(path.as_ref(), &mut documents)?;
+        Ok(Self { documents })
+    }
+}
+
+impl Resolver for DirectoryResolver {
+    fn resolve(&self, uri: &Url) -> Result<Option<Document>, SbolError> {
+        Ok(find(&self.documents, uri))
+    }
+}
+
+/// Resolver of the objects of the SBOL files of a zip archive.
+///
+/// Files with an `.xml`, `.rdf` or `.sbol` extension are read as SBOL2 RDF/XML when the resolver
+/// is created, which fails if one of them is an SBOL3 document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ZipResolver {
+    documents: Vec<Document>,
+}
+
+impl ZipResolver {
+    /// Read the archive at `path`
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, SbolError> {
+        Self::new(File::open(path)?)
+    }
+
+    /// Read the archive from `reader`
+    pub fn new<R: Read + Seek>(reader: R) -> Result<Self, SbolError> {
+        let zip_error =
+            |err: zip::result::ZipError| SbolError::Parse(format!("Zip archive: {err}"));
+        let mut archive = zip::ZipArchive::new(reader).map_err(zip_error)?;
+        let mut documents = vec![];
+        for i in 0..archive.len() {
+            let file = archive.by_index(i).map_err(zip_error)?;
+            if file.is_file() && has_sbol_extension(Path::new(file.name())) {
+                documents.push(sbol2::read(file)?);
+            }
+        }
+        Ok(Self { documents })
+    }
+}
+
+impl Resolver for ZipResolver {
+    fn resolve(&self, uri: &Url) -> Result<Option<Document>, SbolError> {
+        Ok(find(&self.documents, uri))
+    }
+}
+
+/// Document with the `TopLevel` of `documents` which defines the object identified by `uri`
+fn find(documents: &[Document], uri: &Url) -> Option<Document> {
+    documents
+        .iter()
+        .find(|document| document.defining_top_level(uri).is_some())
+        .cloned()
+}
+
+fn has_sbol_extension(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| EXTENSIONS.contains(&extension.to_lowercase().as_str()))
+}
+
+fn read_directory(path: &Path, documents: &mut Vec<Document>) -> Result<(), SbolError> {
+    let mut entries = fs::read_dir(path)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    entries.sort();
+    for entry in entries {
+        if entry.is_dir() {
+            read_directory(&entry, documents)?;
+        } else if has_sbol_extension(&entry) {
+            documents.push(sbol2::read(File::open(&entry)?)?);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, Write};
+
+    use super::*;
+    use crate::test_utils::{dna_sequence, uri};
+    use crate::{Component, SequenceFeature, SubComponent};
+
+    /// Device whose promoter and sequence are defined in a separate library
+    fn documents() -> (Document, Document) {
+        let namespace = uri("https://example.org");
+        let sequence = dna_sequence(&namespace, "pTet_seq", "tccctatcagtgatagaga");
+        let mut promoter = Component::new(namespace.clone(), "pTet").unwrap();
+        promoter.has_sequence.push(sequence.identity.clone());
+        let terminator = Component::new(namespace.clone(), "terminator").unwrap();
+        let library = Document {
+            components: vec![promoter, terminator],
+            sequences: vec![sequence],
+            activities: vec![],
+        };
+
+        let mut device = Component::new(namespace, "device").unwrap();
+        let promoter = library.components[0].identity.clone();
+        let sub_component = SubComponent::new(&device.identity, "promoter", promoter).unwrap();
+        device.sub_components.push(sub_component);
+        let mut document = Document::new();
+        document.components.push(device);
+        (document, library)
+    }
+
+    fn check(resolver: &dyn Resolver, mut document: Document) {
+        let resolved = document.resolve_references(resolver).unwrap();
+        let uris: Vec<&str> = resolved.iter().map(|uri| uri.as_str()).collect();
+        assert_eq!(
+            uris,
+            ["https://example.org/pTet", "https://example.org/pTet_seq"]
+        );
+        assert_eq!(document.components.len(), 2);
+        assert_eq!(document.sequences.len(), 1);
+
+        let missing = uri("https://example.org/pBad");
+        assert!(!document.resolve(&missing, resolver).unwrap());
+    }
+
+    #[test]
+    fn test_memory_resolver() {
+        let (document, library) = documents();
+        check(&MemoryResolver::new(vec![library]), document);
+    }
+
+    #[test]
+    fn test_resolve_known_top_level() {
+        let (mut document, mut library) = documents();
+        let terminator = library.components[1].clone();
+        let feature = SequenceFeature::new(&terminator.identity, "feature").unwrap();
+        library.components[1]
+            .sequence_features
+            .push(feature.clone());
+        document.components.push(terminator);
+
+        let resolver = MemoryResolver::new(vec![library]);
+        assert!(!document.resolve(&feature.identity, &resolver).unwrap());
+        assert_eq!(document.components.len(), 2);
+    }
+
+    #[test]
+    fn test_directory_resolver() {
+        let (document, library) = documents();
+        let path = std::env::temp_dir().join(format!("sbol_resolver_{}", std::process::id()));
+        fs::create_dir_all(path.join("parts")).unwrap();
+        fs::write(
+            path.join("parts/library.xml"),
+            sbol2::to_string(&library).unwrap(),
+        )
+        .unwrap();
+        fs::write(path.join("notes.txt"), "not SBOL").unwrap();
+        let resolver = DirectoryResolver::new(&path);
+        fs::remove_dir_all(&path).unwrap();
+        check(&resolver.unwrap(), document);
+    }
+
+    #[test]
+    fn test_zip_resolver() {
+        let (document, library) = documents();
+        let mut archive = zip::ZipWriter::new(Cursor::new(vec![]));
+        let options = zip::write::FileOptions::default();
+        archive.start_file("library.sbol", options).unwrap();
+        let contents = sbol2::to_string(&library).unwrap();
+        archive.write_all(contents.as_bytes()).unwrap();
+        let archive = archive.finish().unwrap();
+        let resolver = ZipResolver::new(Cursor::new(archive.into_inner())).unwrap();
+        check(&resolver, document);
+    }
+
+    #[test]
+    fn test_sbol3_file() {
+        let mut archive = zip::ZipWriter::new(Cursor::new(vec![]));
+        archive
+            .start_file("library.sbol", zip::write::FileOptions::default())
+            .unwrap();
+        let contents = r#"<?xml version="1.0" ?>
+<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#"
+         xmlns:sbol="http://sbols.org/v3#">
+  <sbol:Component rdf:about="https://example.org/pTet"/>
+</rdf:RDF>
+"#;
+        archive.write_all(contents.as_bytes()).unwrap();
+        let archive = archive.finish().unwrap();
+        assert!(ZipResolver::new(Cursor::new(archive.into_inner())).is_err());
+    }
+}
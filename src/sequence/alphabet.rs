@@ -0,0 +1,401 @@
+//! Validation of `Sequence::elements` against the alphabet of their `Encoding`.
+//!
+//! IUPAC nucleic acid and protein elements are checked character by character. SMILES and InChI
+//! elements receive basic syntactic checks. Positions are 1-based, in agreement with SBOL `Range`
+//! locations.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::ontologies::Encoding;
+
+use super::nucleic_acid::IUPAC_NUCLEOTIDES;
+
+/// IUPAC amino acid codes, including ambiguity codes, `*` for stop and `-` for gaps
+pub const IUPAC_AMINO_ACIDS: &str = "ACDEFGHIKLMNPQRSTVWYBZXJUO*-";
+
+/// Atoms which may be written without brackets in SMILES
+const SMILES_ORGANIC_SUBSET: [&str; 17] = [
+    "Cl", "Br", "B", "C", "N", "O", "P", "S", "F", "I", "b", "c", "n", "o", "p", "s", "*",
+];
+
+/// Characters of SMILES bonds, branches and other symbols outside of brackets
+const SMILES_SYMBOLS: &str = "-=#$:/\\.";
+
+/// Reason why a character of `elements` is invalid
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ElementIssue {
+    /// Whitespace, such as line breaks left over from a flat file
+    Whitespace,
+    /// Digit, such as the position numbers of a GenBank ORIGIN
+    Digit,
+    /// Letter whose case differs from the first letter of the elements
+    MixedCase,
+    /// Character which is not part of the alphabet of the encoding
+    NotInAlphabet,
+    /// Violation of the syntax of the encoding
+    Syntax(&'static str),
+}
+
+/// Character of `elements` which does not conform to its encoding
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidElement {
+    /// 1-based position of the character
+    pub position: usize,
+    /// Invalid character. `None` when the elements end prematurely.
+    pub character: Option<char>,
+    pub issue: ElementIssue,
+}
+
+impl InvalidElement {
+    fn new(position: usize, character: Option<char>, issue: ElementIssue) -> Self {
+        Self {
+            position,
+            character,
+            issue,
+        }
+    }
+}
+
+impl fmt::Display for InvalidElement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let issue = match &self.issue {
+            ElementIssue::Whitespace => "whitespace",
+            ElementIssue::Digit => "digit",
+            ElementIssue::MixedCase => "mixed case",
+            ElementIssue::NotInAlphabet => "not in alphabet",
+            ElementIssue::Syntax(msg) => msg,
+        };
+        match self.character {
+            Some(c) => write!(f, "{c:?} at position {}: {issue}", self.position),
+            None => write!(f, "position {}: {issue}", self.position),
+        }
+    }
+}
+
+/// Find every character of `elements` which does not conform to `encoding`.
+///
+/// `Encoding::Other` is not checked.
+pub fn invalid_elements(elements: &str, encoding: &Encoding) -> Vec<InvalidElement> {
+    match encoding {
+        Encoding::NucleicAcid => check_iupac(elements, IUPAC_NUCLEOTIDES),
+        Encoding::Protein => check_iupac(elements, IUPAC_AMINO_ACIDS),
+        Encoding::SMILES => check_smiles(elements),
+        Encoding::InChl => check_inchi(elements),
+        Encoding::Other(_) => vec![],
+    }
+}
+
+/// Describe `issues` in a single line, ie: for an error message
+pub fn describe(issues: &[InvalidElement]) -> String {
+    issues
+        .iter()
+        .map(|issue| issue.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Remove whitespace and digits from IUPAC elements, and normalize their case.
+///
+/// Nucleic acid elements are lowercased, protein elements are uppercased. Other encodings are
+/// returned unchanged, as whitespace, digits and case are significant.
+pub fn normalize(elements: &str, encoding: &Encoding) -> String {
+    let strip = || {
+        elements
+            .chars()
+            .filter(|c| !c.is_whitespace() && !c.is_ascii_digit())
+    };
+    match encoding {
+        Encoding::NucleicAcid => strip().map(|c| c.to_ascii_lowercase()).collect(),
+        Encoding::Protein => strip().map(|c| c.to_ascii_uppercase()).collect(),
+        _ => elements.to_string(),
+    }
+}
+
+/// Check IUPAC elements, which are case-insensitive but must not mix cases
+fn check_iupac(elements: &str, alphabet: &str) -> Vec<InvalidElement> {
+    let lowercase = elements
+        .chars()
+        .find(|c| c.is_ascii_alphabetic())
+        .map(|c| c.is_ascii_lowercase());
+
+    elements
+        .chars()
+        .enumerate()
+        .filter_map(|(i, c)| {
+            let issue = match c {
+                c if c.is_whitespace() => ElementIssue::Whitespace,
+                c if c.is_ascii_digit() => ElementIssue::Digit,
+                c if !alphabet.contains(c.to_ascii_uppercase()) => ElementIssue::NotInAlphabet,
+                c if c.is_ascii_alphabetic() && Some(c.is_ascii_lowercase()) != lowercase => {
+                    ElementIssue::MixedCase
+                }
+                _ => return None,
+            };
+            Some(InvalidElement::new(i + 1, Some(c), issue))
+        })
+        .collect()
+}
+
+/// Basic syntactic check of SMILES.
+///
+/// Checks that atoms outside of brackets belong to the organic subset, that brackets and branches
+/// are balanced and that ring bonds are closed. Chemical validity is not checked.
+fn check_smiles(elements: &str) -> Vec<InvalidElement> {
+    let chars: Vec<char> = elements.chars().collect();
+    let mut issues = vec![];
+    let mut branches = vec![];
+    let mut rings: HashMap<String, usize> = HashMap::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let position = i + 1;
+        match c {
+            c if c.is_whitespace() => issues.push(InvalidElement::new(
+                position,
+                Some(c),
+                ElementIssue::Whitespace,
+            )),
+            '[' => match chars[i..].iter().position(|c| *c == ']') {
+                Some(end) => {
+                    let atom = &chars[i + 1..i + end];
+                    if !atom
+                        .first()
+                        .is_some_and(|c| c.is_ascii_alphanumeric() || *c == '*')
+                    {
+                        issues.push(InvalidElement::new(
+                            position,
+                            Some(c),
+                            ElementIssue::Syntax("bracket atom without element"),
+                        ));
+                    }
+                    for (j, c) in atom.iter().enumerate() {
+                        if !(c.is_ascii_alphanumeric() || "@+-:*".contains(*c)) {
+                            issues.push(InvalidElement::new(
+                                position + j + 1,
+                                Some(*c),
+                                ElementIssue::NotInAlphabet,
+                            ));
+                        }
+                    }
+                    i += end;
+                }
+                None => {
+                    issues.push(InvalidElement::new(
+                        position,
+                        Some(c),
+                        ElementIssue::Syntax("unclosed bracket"),
+                    ));
+                    i = chars.len();
+                }
+            },
+            ']' => issues.push(InvalidElement::new(
+                position,
+                Some(c),
+                ElementIssue::Syntax("unopened bracket"),
+            )),
+            '(' => branches.push(position),
+            ')' => {
+                if branches.pop().is_none() {
+                    issues.push(InvalidElement::new(
+                        position,
+                        Some(c),
+                        ElementIssue::Syntax("unopened branch"),
+                    ))
+                }
+            }
+            '%' | '0'..='9' => {
+                let label = match c {
+                    '%' => {
+                        let label: String = chars[i + 1..].iter().take(2).collect();
+                        if label.len() < 2 || !label.chars().all(|c| c.is_ascii_digit()) {
+                            issues.push(InvalidElement::new(
+                                position,
+                                Some(c),
+                                ElementIssue::Syntax("ring bond requires two digits"),
+                            ));
+                        }
+                        i += label.len();
+                        label
+                    }
+                    c => c.to_string(),
+                };
+                if rings.remove(&label).is_none() {
+                    rings.insert(label, position);
+                }
+            }
+            c if SMILES_SYMBOLS.contains(c) => (),
+            _ => {
+                let rest: String = chars[i..].iter().take(2).collect();
+                match SMILES_ORGANIC_SUBSET
+                    .iter()
+                    .find(|atom| rest.starts_with(**atom))
+                {
+                    Some(atom) => i += atom.len() - 1,
+                    None => issues.push(InvalidElement::new(
+                        position,
+                        Some(c),
+                        ElementIssue::NotInAlphabet,
+                    )),
+                }
+            }
+        }
+        i += 1;
+    }
+
+    for position in branches {
+        issues.push(InvalidElement::new(
+            position,
+            Some('('),
+            ElementIssue::Syntax("unclosed branch"),
+        ));
+    }
+    for position in rings.into_values() {
+        issues.push(InvalidElement::new(
+            position,
+            chars.get(position - 1).copied(),
+            ElementIssue::Syntax("unclosed ring bond"),
+        ));
+    }
+    issues.sort_by_key(|issue| issue.position);
+    issues
+}
+
+/// Basic syntactic check of InChI.
+///
+/// Checks the `InChI=` prefix, the version layer and that no whitespace or non-ASCII characters
+/// are present. Layers are not checked.
+fn check_inchi(elements: &str) -> Vec<InvalidElement> {
+    const PREFIX: &str = "InChI=";
+    let mut issues = vec![];
+
+    if !elements.starts_with(PREFIX) {
+        issues.push(InvalidElement::new(
+            1,
+            elements.chars().next(),
+            ElementIssue::Syntax("missing InChI= prefix"),
+        ));
+    } else {
+        let version = elements[PREFIX.len()..]
+            .split('/')
+            .next()
+            .unwrap_or_default();
+        if version != "1" && version != "1S" {
+            issues.push(InvalidElement::new(
+                PREFIX.len() + 1,
+                version.chars().next(),
+                ElementIssue::Syntax("invalid version layer"),
+            ));
+        }
+    }
+
+    for (i, c) in elements.chars().enumerate() {
+        let issue = match c {
+            c if c.is_whitespace() => ElementIssue::Whitespace,
+            c if !c.is_ascii_graphic() => ElementIssue::NotInAlphabet,
+            _ => continue,
+        };
+        issues.push(InvalidElement::new(i + 1, Some(c), issue));
+    }
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ontologies::Encoding;
+
+    use super::*;
+
+    fn positions(issues: Vec<InvalidElement>) -> Vec<usize> {
+        issues.iter().map(|issue| issue.position).collect()
+    }
+
+    #[test]
+    fn test_nucleic_acid() {
+        assert!(invalid_elements("gattacarynn-", &Encoding::NucleicAcid).is_empty());
+
+        let issues = invalid_elements("gat ta1cXa\nA", &Encoding::NucleicAcid);
+        assert_eq!(
+            issues,
+            vec![
+                InvalidElement::new(4, Some(' '), ElementIssue::Whitespace),
+                InvalidElement::new(7, Some('1'), ElementIssue::Digit),
+                InvalidElement::new(9, Some('X'), ElementIssue::NotInAlphabet),
+                InvalidElement::new(11, Some('\n'), ElementIssue::Whitespace),
+                InvalidElement::new(12, Some('A'), ElementIssue::MixedCase),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_protein() {
+        assert!(invalid_elements("MKTAYIAKQR*", &Encoding::Protein).is_empty());
+        assert_eq!(
+            positions(invalid_elements("MKT#Y", &Encoding::Protein)),
+            vec![4]
+        );
+    }
+
+    #[test]
+    fn test_smiles() {
+        let valid = [
+            "CCO",
+            "c1ccccc1",
+            "C(=O)O",
+            "[NH4+]",
+            "ClC(Br)F",
+            "C%12CC%12",
+        ];
+        for smiles in valid.iter() {
+            assert!(
+                invalid_elements(smiles, &Encoding::SMILES).is_empty(),
+                "{smiles}"
+            )
+        }
+        assert_eq!(
+            positions(invalid_elements("C(C", &Encoding::SMILES)),
+            vec![2]
+        );
+        assert_eq!(
+            positions(invalid_elements("C1CC", &Encoding::SMILES)),
+            vec![2]
+        );
+        assert_eq!(
+            positions(invalid_elements("CXC", &Encoding::SMILES)),
+            vec![2]
+        );
+        assert_eq!(
+            positions(invalid_elements("C[NH4", &Encoding::SMILES)),
+            vec![2]
+        );
+    }
+
+    #[test]
+    fn test_inchi() {
+        let valid = "InChI=1S/C2H6O/c1-2-3/h3H,2H2,1H3";
+        assert!(invalid_elements(valid, &Encoding::InChl).is_empty());
+        assert_eq!(
+            positions(invalid_elements("C2H6O", &Encoding::InChl)),
+            vec![1]
+        );
+        assert_eq!(
+            positions(invalid_elements("InChI=2/C2H6O", &Encoding::InChl)),
+            vec![7]
+        );
+        assert_eq!(
+            positions(invalid_elements("InChI=1S/C2H6O /c1", &Encoding::InChl)),
+            vec![15]
+        );
+    }
+
+    #[test]
+    fn test_normalize() {
+        let elements = "     1 gattaca GATTACA\n    15 ccg";
+        assert_eq!(
+            normalize(elements, &Encoding::NucleicAcid),
+            "gattacagattacaccg"
+        );
+        assert_eq!(normalize("mk ta", &Encoding::Protein), "MKTA");
+    }
+}
@@ -0,0 +1,177 @@
+pub mod alphabet;
+pub mod nucleic_acid;
+pub mod search;
+pub mod translation;
+
+use url::Url;
+
+use crate::identified::child_uri;
+use crate::ontologies::{Encoding, Orientation};
+use crate::toplevel::impl_toplevel;
+use crate::{Annotation, SbolError};
+
+use alphabet::InvalidElement;
+use search::{Hit, SearchOptions};
+use translation::TranslationOptions;
+
+/// Represents the primary structure of a `Component` object and the manner in which it is encoded.
+///
+/// Representation is accomplished by means of the `elements` property and `encoding` property
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sequence {
+    pub identity: Url,
+    pub display_id: Option<String>,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub derived_from: Vec<Url>,
+    pub generated_by: Vec<Url>,
+    pub has_measure: Vec<Url>,
+    pub annotations: Vec<Annotation>,
+    pub namespace: Url,
+    pub has_attachment: Vec<Url>,
+
+    /// Optional string of characters that represents the constituents of a biological or chemical
+    /// molecule.
+    ///
+    /// For example, these characters could represent nucleotide bases, amino acid residues, or the
+    /// atoms and chemical bonds of a small molecule.
+    ///
+    /// If the `elements` property is not set, then it means the particulars of this Sequence have
+    /// not yet been determined.
+    pub elements: Option<String>,
+
+    /// Indicates how `elements` property of a `Sequence` are formed and interpreted.
+    ///
+    /// Should be a URI indentifying from the textual format branch of the EDAM ontology.
+    ///
+    /// It is optional, unless `elements` is set, in that case is required.
+    ///
+    /// A partial list of possible URI values:
+    ///     *============================================================*
+    ///     | Encoding        | URI                                      |
+    ///     *============================================================*
+    ///     | IUPAC DNA, RNA  | https://identifiers.org/edam:format_1207 |
+    ///     | IUPAC Protein   | https://identifiers.org/edam:format_1208 |
+    ///     | InChl           | https://identifiers.org/edam:format_1197 |
+    ///     | SMILES          | https://identifiers.org/edam:format_1196 |
+    ///     *============================================================*
+    ///     When the `encoding` of a `Sequence` is well described by one of these, than it must
+    ///     contain that URI.
+    ///
+    pub encoding: Option<Encoding>,
+}
+
+impl Sequence {
+    /// Create an empty `Sequence` whose URI is `namespace/display_id`
+    pub fn new(namespace: Url, display_id: &str) -> Result<Self, SbolError> {
+        Ok(Self {
+            identity: child_uri(&namespace, display_id)?,
+            display_id: Some(display_id.to_string()),
+            name: None,
+            description: None,
+            derived_from: vec![],
+            generated_by: vec![],
+            has_measure: vec![],
+            annotations: vec![],
+            namespace,
+            has_attachment: vec![],
+            elements: None,
+            encoding: None,
+        })
+    }
+
+    /// Every character of `elements` which does not conform to `encoding`.
+    ///
+    /// Empty if either `elements` or `encoding` is not set.
+    pub fn invalid_elements(&self) -> Vec<InvalidElement> {
+        match (&self.elements, &self.encoding) {
+            (Some(elements), Some(encoding)) => alphabet::invalid_elements(elements, encoding),
+            _ => vec![],
+        }
+    }
+
+    /// Check that `encoding` is set whenever `elements` is set, and that `elements` conform to
+    /// the alphabet of `encoding`.
+    pub fn validate(&self) -> Result<(), SbolError> {
+        if self.elements.is_some() && self.encoding.is_none() {
+            return Err(SbolError::Validation(format!(
+                "{} has elements but no encoding",
+                self.identity
+            )));
+        }
+        let issues = self.invalid_elements();
+        if issues.is_empty() {
+            Ok(())
+        } else {
+            Err(SbolError::Validation(format!(
+                "{} has invalid elements: {}",
+                self.identity,
+                alphabet::describe(&issues)
+            )))
+        }
+    }
+
+    /// `elements` of a `Sequence` with `Encoding::NucleicAcid`
+    ///
+    /// Fails if `encoding` is not `Encoding::NucleicAcid` or if `elements` is not set.
+    pub fn nucleic_acid_elements(&self) -> Result<&str, SbolError> {
+        match (&self.encoding, &self.elements) {
+            (Some(Encoding::NucleicAcid), Some(elements)) => Ok(elements),
+            (Some(Encoding::NucleicAcid), None) => Err(SbolError::Validation(
+                "Sequence elements are not set".to_string(),
+            )),
+            (encoding, _) => Err(SbolError::Validation(format!(
+                "Expected nucleic acid encoding, found {encoding:?}"
+            ))),
+        }
+    }
+
+    /// Reverse complement mapping of `elements`, including IUPAC ambiguity codes.
+    pub fn reverse_complement(&self) -> Result<String, SbolError> {
+        nucleic_acid::reverse_complement(self.nucleic_acid_elements()?)
+    }
+
+    /// `elements` as read in the given `orientation`.
+    pub fn oriented_elements(&self, orientation: &Orientation) -> Result<String, SbolError> {
+        nucleic_acid::orient(self.nucleic_acid_elements()?, orientation)
+    }
+
+    /// Fraction of bases which are guanine or cytosine.
+    pub fn gc_content(&self) -> Result<f64, SbolError> {
+        nucleic_acid::gc_content(self.nucleic_acid_elements()?)
+    }
+
+    /// Estimated molecular weight (g/mol) of a single strand.
+    pub fn molecular_weight(&self) -> Result<f64, SbolError> {
+        nucleic_acid::molecular_weight(self.nucleic_acid_elements()?)
+    }
+
+    /// Estimated melting temperature (°C).
+    pub fn melting_temperature(&self) -> Result<f64, SbolError> {
+        nucleic_acid::melting_temperature(self.nucleic_acid_elements()?)
+    }
+
+    /// Every occurrence of `motif` within the nucleic acid `elements`.
+    pub fn search(&self, motif: &str, options: &SearchOptions) -> Result<Vec<Hit>, SbolError> {
+        search::find(self.nucleic_acid_elements()?, motif, options)
+    }
+
+    /// Translate the nucleic acid `elements` into a new protein `Sequence`.
+    ///
+    /// The protein `Sequence` shares the namespace of this `Sequence`, is identified by
+    /// `display_id`, and records that it is `derived_from` this `Sequence`.
+    pub fn translate(
+        &self,
+        display_id: &str,
+        options: &TranslationOptions,
+    ) -> Result<Sequence, SbolError> {
+        let elements = translation::translate(self.nucleic_acid_elements()?, options)?;
+        let mut protein = Sequence::new(self.namespace.clone(), display_id)?;
+        protein.elements = Some(elements);
+        protein.encoding = Some(Encoding::Protein);
+        protein.derived_from = vec![self.identity.clone()];
+        Ok(protein)
+    }
+}
+
+impl_toplevel!(Sequence);
@@ -0,0 +1,273 @@
+//! Operations on the `elements` of nucleic acid sequences.
+//!
+//! Elements are interpreted as IUPAC nucleotide codes (case-insensitive), including the ambiguity
+//! codes below. Positions reported by errors are 1-based, in agreement with SBOL `Range` locations.
+//!
+//! ```text
+//! R = A/G    Y = C/T    S = G/C    W = A/T    K = G/T    M = A/C
+//! B = C/G/T  D = A/G/T  H = A/C/T  V = A/C/G  N = any
+//! ```
+
+use crate::ontologies::{Encoding, Orientation};
+use crate::SbolError;
+
+use super::alphabet::{self, ElementIssue};
+
+/// IUPAC nucleotide codes, including ambiguity codes and `-` for gaps
+pub const IUPAC_NUCLEOTIDES: &str = "ACGTURYSWKMBDHVN-";
+
+/// Molecular weight (g/mol) of each DNA nucleotide within a single strand
+const DNA_WEIGHTS: [(char, f64); 4] = [('A', 313.21), ('C', 289.18), ('G', 329.21), ('T', 304.2)];
+
+/// Molecular weight (g/mol) of each RNA nucleotide within a single strand
+const RNA_WEIGHTS: [(char, f64); 4] = [('A', 329.21), ('C', 305.18), ('G', 345.21), ('U', 306.17)];
+
+/// Bases represented by an IUPAC nucleotide code
+pub(crate) fn bases(code: char) -> &'static str {
+    match code.to_ascii_uppercase() {
+        'A' => "A",
+        'C' => "C",
+        'G' => "G",
+        'T' => "T",
+        'U' => "U",
+        'R' => "AG",
+        'Y' => "CT",
+        'S' => "CG",
+        'W' => "AT",
+        'K' => "GT",
+        'M' => "AC",
+        'B' => "CGT",
+        'D' => "AGT",
+        'H' => "ACT",
+        'V' => "ACG",
+        'N' => "ACGT",
+        _ => "",
+    }
+}
+
+/// Whether `elements` are RNA, ie: contain uracil but no thymine
+fn is_rna(elements: &str) -> bool {
+    let mut uracil = false;
+    for c in elements.chars() {
+        match c.to_ascii_uppercase() {
+            'T' => return false,
+            'U' => uracil = true,
+            _ => (),
+        }
+    }
+    uracil
+}
+
+/// Check that every character of `elements` is an IUPAC nucleotide code
+///
+/// Mixed case is accepted, as it does not affect the operations of this module.
+pub fn validate(elements: &str) -> Result<(), SbolError> {
+    let issues: Vec<_> = alphabet::invalid_elements(elements, &Encoding::NucleicAcid)
+        .into_iter()
+        .filter(|issue| issue.issue != ElementIssue::MixedCase)
+        .collect();
+    if issues.is_empty() {
+        Ok(())
+    } else {
+        Err(SbolError::Validation(format!(
+            "Invalid nucleic acid elements: {}",
+            alphabet::describe(&issues)
+        )))
+    }
+}
+
+/// Complement of a single IUPAC nucleotide code, preserving case.
+///
+/// Adenine is complemented to thymine, unless `rna` is set, in which case it is complemented to
+/// uracil. Returns `None` if `code` is not an IUPAC nucleotide code.
+pub fn complement(code: char, rna: bool) -> Option<char> {
+    let complement = match code.to_ascii_uppercase() {
+        'A' if rna => 'U',
+        'A' => 'T',
+        'T' | 'U' => 'A',
+        'C' => 'G',
+        'G' => 'C',
+        'R' => 'Y',
+        'Y' => 'R',
+        'K' => 'M',
+        'M' => 'K',
+        'B' => 'V',
+        'V' => 'B',
+        'D' => 'H',
+        'H' => 'D',
+        c @ ('S' | 'W' | 'N' | '-') => c,
+        _ => return None,
+    };
+    if code.is_ascii_lowercase() {
+        Some(complement.to_ascii_lowercase())
+    } else {
+        Some(complement)
+    }
+}
+
+/// Reverse complement of `elements`, including ambiguity codes.
+///
+/// RNA elements (containing `U` but no `T`) are complemented into RNA.
+pub fn reverse_complement(elements: &str) -> Result<String, SbolError> {
+    validate(elements)?;
+    let rna = is_rna(elements);
+    Ok(elements
+        .chars()
+        .rev()
+        .filter_map(|c| complement(c, rna))
+        .collect())
+}
+
+/// Elements as read in the given `orientation`.
+///
+/// `Orientation::ReverseComplement` and `Orientation::ReverseComplementAlt` apply the reverse
+/// complement mapping, otherwise elements are returned unchanged.
+pub fn orient(elements: &str, orientation: &Orientation) -> Result<String, SbolError> {
+    match orientation {
+        Orientation::ReverseComplement | Orientation::ReverseComplementAlt => {
+            reverse_complement(elements)
+        }
+        Orientation::Inline | Orientation::InlineAlt => Ok(elements.to_string()),
+    }
+}
+
+/// Fraction of bases which are guanine or cytosine.
+///
+/// `S` is counted as G/C, and gaps are ignored. Other ambiguity codes are not counted as G/C.
+/// Returns 0 for empty elements.
+pub fn gc_content(elements: &str) -> Result<f64, SbolError> {
+    validate(elements)?;
+    let (gc, total) =
+        elements
+            .chars()
+            .filter(|c| *c != '-')
+            .fold((0usize, 0usize), |(gc, total), c| {
+                match c.to_ascii_uppercase() {
+                    'G' | 'C' | 'S' => (gc + 1, total + 1),
+                    _ => (gc, total + 1),
+                }
+            });
+    match total {
+        0 => Ok(0.0),
+        _ => Ok(gc as f64 / total as f64),
+    }
+}
+
+/// Estimated molecular weight (g/mol) of a single strand.
+///
+/// Uses the nucleotide weights of OligoCalc, accounting for the 5' terminal phosphate. Ambiguity
+/// codes contribute the mean weight of the bases they represent.
+pub fn molecular_weight(elements: &str) -> Result<f64, SbolError> {
+    validate(elements)?;
+    let rna = is_rna(elements);
+    let weights = if rna { &RNA_WEIGHTS } else { &DNA_WEIGHTS };
+    let weight_of = |base: char| {
+        let base = match (rna, base) {
+            (true, 'T') => 'U',
+            (false, 'U') => 'T',
+            (_, base) => base,
+        };
+        weights
+            .iter()
+            .find(|(b, _)| *b == base)
+            .map(|(_, w)| *w)
+            .unwrap_or_default()
+    };
+
+    let weight: f64 = elements
+        .chars()
+        .filter(|c| *c != '-')
+        .map(|c| {
+            let bases = bases(c);
+            bases.chars().map(weight_of).sum::<f64>() / bases.len() as f64
+        })
+        .sum();
+    if weight == 0.0 {
+        Ok(0.0)
+    } else if rna {
+        Ok(weight + 159.0)
+    } else {
+        Ok(weight - 61.96)
+    }
+}
+
+/// Estimated melting temperature (°C).
+///
+/// Sequences shorter than 14 bases use the Wallace rule `2(A+T) + 4(G+C)`, longer sequences use
+/// `64.9 + 41(G+C - 16.4) / N`. Both assume standard PCR conditions and are only estimates.
+pub fn melting_temperature(elements: &str) -> Result<f64, SbolError> {
+    let gc_content = gc_content(elements)?;
+    let length = elements.chars().filter(|c| *c != '-').count() as f64;
+    let gc = gc_content * length;
+    if length < 14.0 {
+        Ok(2.0 * (length - gc) + 4.0 * gc)
+    } else {
+        Ok(64.9 + 41.0 * (gc - 16.4) / length)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ontologies::Orientation;
+    use crate::SbolError;
+
+    use super::*;
+
+    #[test]
+    fn test_reverse_complement() {
+        let variants = [
+            ("gattaca", "tgtaatc"),
+            ("GATTACA", "TGTAATC"),
+            ("ACGU", "ACGU"),
+            ("RYSWKMBDHVN", "NBDHVKMWSRY"),
+            ("", ""),
+            ("GATtaca", "tgtaATC"),
+        ];
+        for (elements, expected) in variants.iter() {
+            assert_eq!(reverse_complement(elements).unwrap(), *expected)
+        }
+    }
+
+    #[test]
+    fn test_invalid_elements() {
+        match reverse_complement("gatxaca") {
+            Err(SbolError::Validation(msg)) => assert!(msg.contains("position 4")),
+            _ => panic!("Expected validation error"),
+        }
+    }
+
+    #[test]
+    fn test_orient() {
+        assert_eq!(
+            orient("gattaca", &Orientation::ReverseComplement).unwrap(),
+            "tgtaatc"
+        );
+        assert_eq!(orient("gattaca", &Orientation::Inline).unwrap(), "gattaca");
+    }
+
+    #[test]
+    fn test_gc_content() {
+        assert_eq!(gc_content("gattaca").unwrap(), 2.0 / 7.0);
+        assert_eq!(gc_content("GCSA").unwrap(), 0.75);
+        assert_eq!(gc_content("").unwrap(), 0.0);
+    }
+
+    #[test]
+    fn test_molecular_weight() {
+        let expected = 313.21 + 289.18 + 329.21 + 304.2 - 61.96;
+        assert!((molecular_weight("ACGT").unwrap() - expected).abs() < 1e-9);
+        let expected = 329.21 + 305.18 + 345.21 + 306.17 + 159.0;
+        assert!((molecular_weight("ACGU").unwrap() - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_melting_temperature() {
+        assert_eq!(
+            melting_temperature("gattaca").unwrap(),
+            2.0 * 5.0 + 4.0 * 2.0
+        );
+        let primer = "ATGCATGCATGCATGCATGC";
+        let expected = 64.9 + 41.0 * (10.0 - 16.4) / 20.0;
+        assert!((melting_temperature(primer).unwrap() - expected).abs() < 1e-9);
+    }
+}
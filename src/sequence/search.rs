@@ -0,0 +1,275 @@
+//! Search of nucleic acid elements for motifs.
+//!
+//! Motifs are IUPAC nucleotide codes. With `degenerate` matching, an ambiguity code of the motif
+//! matches any of the bases it represents, ie: `GGNCC` matches `GGACC` and `GGTCC`. Hits are
+//! reported as 1-based [`Span`]s, which wrap around the origin of circular elements.
+
+use url::Url;
+
+use crate::coordinates::Span;
+use crate::ontologies::Orientation;
+use crate::uris::child_display_id;
+use crate::{Component, Range, SbolError};
+
+use super::nucleic_acid;
+
+/// Parameters of a search
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SearchOptions {
+    /// Match ambiguity codes of the motif against the bases they represent. Otherwise, elements
+    /// must be equal to the motif, regardless of case.
+    pub degenerate: bool,
+
+    /// Also search for the reverse complement of the motif, ie: the motif on the other strand.
+    pub both_strands: bool,
+
+    /// Also find hits which span the end and the beginning of the elements.
+    pub circular: bool,
+}
+
+impl SearchOptions {
+    /// Degenerate search following the topology `type` of `component`: both strands are searched
+    /// if it is double-stranded, and hits may span the origin if it is circular.
+    pub fn of(component: &Component) -> Self {
+        Self {
+            degenerate: true,
+            both_strands: component.is_double_stranded(),
+            circular: component.is_circular(),
+        }
+    }
+}
+
+/// Occurrence of a motif
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hit {
+    /// Elements which match the motif, with `start > end` if they span the origin
+    pub span: Span,
+
+    /// `Orientation::ReverseComplement` when the motif was found on the other strand
+    pub orientation: Orientation,
+}
+
+impl Hit {
+    /// `Range` locations of the hit on `has_sequence`, a sequence of `length` elements, as
+    /// children of `parent`.
+    ///
+    /// A hit which spans the origin is split into two `Range` objects, listed and numbered by
+    /// their `order` in the order in which they are read.
+    pub fn ranges(
+        &self,
+        parent: &Url,
+        has_sequence: &Url,
+        length: usize,
+    ) -> Result<Vec<Range>, SbolError> {
+        let bounds = if self.span.wraps() {
+            vec![(self.span.start, length), (1, self.span.end)]
+        } else {
+            vec![(self.span.start, self.span.end)]
+        };
+        let mut ranges: Vec<Range> = vec![];
+        for (start, end) in bounds {
+            let display_id = child_display_id("Range", &ranges);
+            let mut range = Range::new(parent, &display_id, has_sequence.clone(), start, end)?;
+            range.orientation = Some(self.orientation.clone());
+            ranges.push(range);
+        }
+        if self.orientation == Orientation::ReverseComplement {
+            ranges.reverse();
+        }
+        if ranges.len() > 1 {
+            for (i, range) in ranges.iter_mut().enumerate() {
+                range.order = Some(i as i64 + 1);
+            }
+        }
+        Ok(ranges)
+    }
+}
+
+/// Every occurrence of `motif` within `elements`, ordered by position.
+///
+/// A motif which is its own reverse complement is only reported inline.
+pub fn find(elements: &str, motif: &str, options: &SearchOptions) -> Result<Vec<Hit>, SbolError> {
+    nucleic_acid::validate(elements)?;
+    if motif.is_empty() {
+        return Err(SbolError::Validation("Empty motif".to_string()));
+    }
+    let mut hits = find_strand(elements, motif, Orientation::Inline, options)?;
+    if options.both_strands {
+        let reverse = nucleic_acid::reverse_complement(motif)?;
+        if !reverse.eq_ignore_ascii_case(motif) {
+            hits.extend(find_strand(
+                elements,
+                &reverse,
+                Orientation::ReverseComplement,
+                options,
+            )?);
+        }
+    }
+    hits.sort_by_key(|hit| hit.span.start);
+    Ok(hits)
+}
+
+/// Occurrences of `motif` as read inline, reported with `orientation`
+fn find_strand(
+    elements: &str,
+    motif: &str,
+    orientation: Orientation,
+    options: &SearchOptions,
+) -> Result<Vec<Hit>, SbolError> {
+    nucleic_acid::validate(motif)?;
+    let (elements, motif) = (elements.as_bytes(), motif.as_bytes());
+    let length = elements.len();
+    if motif.len() > length {
+        return Ok(vec![]);
+    }
+    let starts = if options.circular {
+        length
+    } else {
+        length - motif.len() + 1
+    };
+    Ok((0..starts)
+        .filter(|start| {
+            motif
+                .iter()
+                .enumerate()
+                .all(|(i, code)| matches(elements[(start + i) % length], *code, options.degenerate))
+        })
+        .map(|start| Hit {
+            span: Span::new(start + 1, (start + motif.len() - 1) % length + 1),
+            orientation: orientation.clone(),
+        })
+        .collect())
+}
+
+/// Whether `element` matches the motif `code`
+fn matches(element: u8, code: u8, degenerate: bool) -> bool {
+    if !degenerate {
+        return element.eq_ignore_ascii_case(&code);
+    }
+    // Uracil is matched as thymine, so that DNA motifs are found in RNA
+    let bases = |code: u8| nucleic_acid::bases(code as char).replace('U', "T");
+    let (element, code) = (bases(element), bases(code));
+    !element.is_empty() && element.chars().all(|base| code.contains(base))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ontologies::{ComponentTypeOntology, TopologyOntology};
+    use crate::ComponentType;
+
+    use super::*;
+    use crate::test_utils::{dna_sequence, uri};
+
+    fn spans(hits: &[Hit]) -> Vec<(usize, usize, Orientation)> {
+        hits.iter()
+            .map(|hit| (hit.span.start, hit.span.end, hit.orientation.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_find() {
+        let options = SearchOptions::default();
+        let hits = find("GGTACCatggtacc", "ggtacc", &options).unwrap();
+        assert_eq!(
+            spans(&hits),
+            [(1, 6, Orientation::Inline), (9, 14, Orientation::Inline)]
+        );
+        assert!(find("GGACC", "GGNCC", &options).unwrap().is_empty());
+        assert!(find("GGACC", "", &options).is_err());
+        assert!(find("GGACC", "GGXCC", &options).is_err());
+
+        let degenerate = SearchOptions {
+            degenerate: true,
+            ..Default::default()
+        };
+        let hits = find("GGACCggtcc", "GGNCC", &degenerate).unwrap();
+        assert_eq!(hits.len(), 2);
+        // An ambiguous element only matches a code which includes all of its bases
+        assert_eq!(find("GGRCC", "GGNCC", &degenerate).unwrap().len(), 1);
+        assert!(find("GGNCC", "GGACC", &degenerate).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_find_strands_and_origin() {
+        let options = SearchOptions {
+            both_strands: true,
+            circular: true,
+            ..Default::default()
+        };
+        // GCAT is ATGC on the other strand, and spans the origin at 11..2
+        let hits = find("ATGCATATGCGC", "ATGC", &options).unwrap();
+        assert_eq!(
+            spans(&hits),
+            [
+                (1, 4, Orientation::Inline),
+                (3, 6, Orientation::ReverseComplement),
+                (7, 10, Orientation::Inline),
+                (11, 2, Orientation::ReverseComplement),
+            ]
+        );
+        // Palindromes are reported once
+        let hits = find("GAATTC", "GAATTC", &options).unwrap();
+        assert_eq!(spans(&hits), [(1, 6, Orientation::Inline)]);
+    }
+
+    #[test]
+    fn test_hit_ranges() {
+        let parent = uri("https://example.org/plasmid/site");
+        let sequence = uri("https://example.org/plasmid_seq");
+        let hit = Hit {
+            span: Span::new(9, 2),
+            orientation: Orientation::ReverseComplement,
+        };
+        let ranges = hit.ranges(&parent, &sequence, 10).unwrap();
+        let bounds: Vec<_> = ranges
+            .iter()
+            .map(|range| {
+                (
+                    range.display_id.as_deref().unwrap(),
+                    range.start,
+                    range.end,
+                    range.order,
+                )
+            })
+            .collect();
+        assert_eq!(
+            bounds,
+            [("Range2", 1, 2, Some(1)), ("Range1", 9, 10, Some(2))]
+        );
+        assert_eq!(ranges[0].orientation, Some(Orientation::ReverseComplement));
+
+        let hit = Hit {
+            span: Span::new(3, 6),
+            orientation: Orientation::Inline,
+        };
+        let ranges = hit.ranges(&parent, &sequence, 10).unwrap();
+        assert_eq!(ranges[0].order, None);
+    }
+
+    #[test]
+    fn test_search_sequence() {
+        let namespace = uri("https://example.org");
+        let mut plasmid = Component::new(namespace.clone(), "plasmid").unwrap();
+        plasmid.r#type = vec![
+            ComponentType::Type(ComponentTypeOntology::DNA),
+            ComponentType::Topology(TopologyOntology::Circular),
+            ComponentType::Topology(TopologyOntology::DoubleStranded),
+        ];
+        let sequence = dna_sequence(&namespace, "plasmid_seq", "ttcagaa");
+
+        let hits = sequence
+            .search("GAAT", &SearchOptions::of(&plasmid))
+            .unwrap();
+        assert_eq!(
+            spans(&hits),
+            [
+                (5, 1, Orientation::Inline),
+                (7, 3, Orientation::ReverseComplement),
+            ]
+        );
+        assert!(sequence
+            .search("GAAT", &SearchOptions::default())
+            .unwrap()
+            .is_empty());
+    }
+}
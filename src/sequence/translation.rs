@@ -0,0 +1,491 @@
+//! Translation of nucleic acid elements into protein elements.
+//!
+//! Codons are translated using the NCBI genetic code tables. Codons containing IUPAC ambiguity
+//! codes are translated when every base they represent yields the same amino acid, otherwise they
+//! are translated as `X`.
+
+use url::Url;
+
+use crate::ontologies::{ComponentTypeOntology, Encoding, Orientation};
+use crate::{
+    Component, ComponentType, Document, Feature, Identified, Location, SbolError, Sequence,
+};
+
+use super::nucleic_acid;
+
+/// Order of bases used to index the amino acid strings of NCBI genetic code tables
+const BASES: [char; 4] = ['T', 'C', 'A', 'G'];
+
+/// NCBI genetic code tables
+///
+/// Each table is identified by its NCBI transl_table id.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GeneticCode {
+    /// Table 1
+    #[default]
+    Standard,
+    /// Table 2
+    VertebrateMitochondrial,
+    /// Table 3
+    YeastMitochondrial,
+    /// Table 4
+    MoldMitochondrial,
+    /// Table 5
+    InvertebrateMitochondrial,
+    /// Table 6
+    Ciliate,
+    /// Table 11
+    Bacterial,
+}
+
+impl GeneticCode {
+    /// NCBI transl_table id
+    pub fn id(&self) -> u8 {
+        match self {
+            Self::Standard => 1,
+            Self::VertebrateMitochondrial => 2,
+            Self::YeastMitochondrial => 3,
+            Self::MoldMitochondrial => 4,
+            Self::InvertebrateMitochondrial => 5,
+            Self::Ciliate => 6,
+            Self::Bacterial => 11,
+        }
+    }
+
+    /// Amino acids of all 64 codons, ordered by `BASES`
+    fn amino_acids(&self) -> &'static [u8; 64] {
+        match self {
+            Self::Standard | Self::Bacterial => {
+                b"FFLLSSSSYY**CC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG"
+            }
+            Self::VertebrateMitochondrial => {
+                b"FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIMMTTTTNNKKSS**VVVVAAAADDEEGGGG"
+            }
+            Self::YeastMitochondrial => {
+                b"FFLLSSSSYY**CCWWTTTTPPPPHHQQRRRRIIMMTTTTNNKKSSRRVVVVAAAADDEEGGGG"
+            }
+            Self::MoldMitochondrial => {
+                b"FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG"
+            }
+            Self::InvertebrateMitochondrial => {
+                b"FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIMMTTTTNNKKSSSSVVVVAAAADDEEGGGG"
+            }
+            Self::Ciliate => b"FFLLSSSSYYQQCC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        }
+    }
+
+    /// Initiation codons of all 64 codons (marked by `M`), ordered by `BASES`
+    fn starts(&self) -> &'static [u8; 64] {
+        match self {
+            Self::Standard => b"---M---------------M---------------M----------------------------",
+            Self::VertebrateMitochondrial => {
+                b"--------------------------------MMMM---------------M------------"
+            }
+            Self::YeastMitochondrial => {
+                b"----------------------------------MM---------------M------------"
+            }
+            Self::MoldMitochondrial => {
+                b"--MM---------------M------------MMMM---------------M------------"
+            }
+            Self::InvertebrateMitochondrial => {
+                b"---M----------------------------MMMM---------------M------------"
+            }
+            Self::Ciliate => b"-----------------------------------M----------------------------",
+            Self::Bacterial => b"---M---------------M------------MMMM---------------M------------",
+        }
+    }
+
+    /// Translate a single unambiguous codon of DNA or RNA bases
+    fn lookup(&self, codon: &[char; 3], table: &[u8; 64]) -> Option<char> {
+        let mut index = 0;
+        for base in codon {
+            let base = match base.to_ascii_uppercase() {
+                'U' => 'T',
+                base => base,
+            };
+            index = index * 4 + BASES.iter().position(|b| *b == base)?;
+        }
+        Some(table[index] as char)
+    }
+
+    /// Translate a codon, resolving IUPAC ambiguity codes.
+    ///
+    /// Returns `X` when the possible bases do not all yield the same amino acid.
+    fn translate_codon(&self, codon: &[char; 3], table: &[u8; 64]) -> char {
+        let mut expanded = vec![String::new()];
+        for code in codon {
+            let bases = nucleic_acid::bases(*code);
+            expanded = expanded
+                .iter()
+                .flat_map(|prefix| bases.chars().map(move |b| format!("{prefix}{b}")))
+                .collect();
+        }
+        let mut amino_acids = expanded.iter().filter_map(|codon| {
+            let codon: Vec<char> = codon.chars().collect();
+            self.lookup(&[codon[0], codon[1], codon[2]], table)
+        });
+        match amino_acids.next() {
+            Some(first) if amino_acids.all(|aa| aa == first) => first,
+            _ => 'X',
+        }
+    }
+}
+
+impl TryFrom<u8> for GeneticCode {
+    type Error = SbolError;
+
+    fn try_from(id: u8) -> Result<Self, Self::Error> {
+        match id {
+            1 => Ok(Self::Standard),
+            2 => Ok(Self::VertebrateMitochondrial),
+            3 => Ok(Self::YeastMitochondrial),
+            4 => Ok(Self::MoldMitochondrial),
+            5 => Ok(Self::InvertebrateMitochondrial),
+            6 => Ok(Self::Ciliate),
+            11 => Ok(Self::Bacterial),
+            _ => Err(SbolError::Parse(format!(
+                "Unsupported genetic code table: {id}"
+            ))),
+        }
+    }
+}
+
+/// Parameters of a translation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TranslationOptions {
+    /// Genetic code table used to translate codons
+    pub table: GeneticCode,
+
+    /// Offset (0, 1 or 2) of the first codon, applied after `orientation`
+    pub frame: usize,
+
+    /// Strand which is translated. `Orientation::ReverseComplement` translates the reverse
+    /// complement of the elements.
+    pub orientation: Orientation,
+
+    /// Stop translating at the first stop codon, which is not included in the result. Otherwise,
+    /// stop codons are translated as `*`.
+    pub to_stop: bool,
+
+    /// Translate an alternative initiation codon at the beginning as methionine, as is done for
+    /// a complete coding sequence.
+    pub initiator: bool,
+}
+
+impl Default for TranslationOptions {
+    fn default() -> Self {
+        Self {
+            table: GeneticCode::default(),
+            frame: 0,
+            orientation: Orientation::Inline,
+            to_stop: false,
+            initiator: false,
+        }
+    }
+}
+
+/// Translate nucleic acid `elements` into IUPAC protein elements.
+///
+/// Trailing bases which do not form a complete codon are ignored.
+pub fn translate(elements: &str, options: &TranslationOptions) -> Result<String, SbolError> {
+    if options.frame > 2 {
+        return Err(SbolError::Validation(format!(
+            "Invalid reading frame: {}",
+            options.frame
+        )));
+    }
+    let elements: Vec<char> = nucleic_acid::orient(elements, &options.orientation)?
+        .chars()
+        .filter(|c| *c != '-')
+        .collect();
+    let amino_acids = options.table.amino_acids();
+    let starts = options.table.starts();
+
+    let mut protein = String::new();
+    for (i, codon) in elements[options.frame.min(elements.len())..]
+        .chunks_exact(3)
+        .enumerate()
+    {
+        let codon = [codon[0], codon[1], codon[2]];
+        let initiator = i == 0 && options.initiator;
+        let amino_acid = if initiator && options.table.translate_codon(&codon, starts) == 'M' {
+            'M'
+        } else {
+            options.table.translate_codon(&codon, amino_acids)
+        };
+        if amino_acid == '*' && options.to_stop {
+            break;
+        }
+        protein.push(amino_acid);
+    }
+    Ok(protein)
+}
+
+/// Translate the coding sequence of `feature`, a `SubComponent` or `SequenceFeature` of
+/// `component`, into a new protein `Component` identified by `display_id`.
+///
+/// The coding elements are read from the nucleic acid `Sequence` of `component` at the locations
+/// of the feature, in their orientation, or from the `Component` that a `SubComponent` without
+/// locations is an instance of. `options.orientation` applies to the coding elements as read.
+///
+/// The protein `Component` and its `Sequence` are added to `document`, in the namespace of
+/// `component`, and record that they are `derived_from` the feature. Returns the URI of the
+/// protein `Component`.
+pub fn translate_feature(
+    document: &mut Document,
+    component: &Url,
+    feature: &Url,
+    display_id: &str,
+    options: &TranslationOptions,
+) -> Result<Url, SbolError> {
+    let parent = document
+        .component(component)
+        .ok_or_else(|| SbolError::UnresolvedReference(component.clone()))?;
+    let sub_component = parent
+        .sub_components
+        .iter()
+        .find(|f| &f.identity == feature);
+    let sequence_feature = parent
+        .sequence_features
+        .iter()
+        .find(|f| &f.identity == feature);
+    let coding = match (sub_component, sequence_feature) {
+        (Some(sub_component), _) if sub_component.has_location.is_empty() => {
+            let child = document
+                .component(&sub_component.instance_of)
+                .ok_or_else(|| SbolError::UnresolvedReference(sub_component.instance_of.clone()))?;
+            let elements = document
+                .nucleic_acid_sequence(child)
+                .ok_or_else(|| no_sequence(&child.identity))?
+                .nucleic_acid_elements()?;
+            let elements = sub_component.source_elements(elements)?;
+            match &sub_component.orientation {
+                Some(orientation) => nucleic_acid::orient(&elements, orientation)?,
+                None => elements,
+            }
+        }
+        (Some(sub_component), _) => {
+            feature_elements(document, parent, sub_component, &sub_component.has_location)?
+        }
+        (None, Some(feature)) => {
+            feature_elements(document, parent, feature, &feature.has_location)?
+        }
+        (None, None) => return Err(SbolError::UnresolvedReference(feature.clone())),
+    };
+    let namespace = parent.namespace.clone();
+
+    let mut protein = Component::new(namespace.clone(), display_id)?;
+    let mut sequence = Sequence::new(namespace, &format!("{display_id}_seq"))?;
+    for uri in [&protein.identity, &sequence.identity] {
+        if document.contains(uri) {
+            return Err(SbolError::Validation(format!("{uri} already exists")));
+        }
+    }
+    sequence.elements = Some(translate(&coding, options)?);
+    sequence.encoding = Some(Encoding::Protein);
+    sequence.derived_from = vec![feature.clone()];
+    protein.r#type = vec![ComponentType::Type(ComponentTypeOntology::Protein)];
+    protein.has_sequence = vec![sequence.identity.clone()];
+    protein.derived_from = vec![feature.clone()];
+    let uri = protein.identity.clone();
+    document.components.push(protein);
+    document.sequences.push(sequence);
+    Ok(uri)
+}
+
+fn no_sequence(component: &Url) -> SbolError {
+    SbolError::Validation(format!("{component} has no nucleic acid Sequence"))
+}
+
+/// Elements of the nucleic acid `Sequence` of `parent` at the `locations` of `feature`, in reading
+/// order: by `order` when every location has one, otherwise along the strand of the feature.
+fn feature_elements<F: Feature + ?Sized>(
+    document: &Document,
+    parent: &Component,
+    feature: &F,
+    locations: &[Location],
+) -> Result<String, SbolError> {
+    let elements = document
+        .nucleic_acid_sequence(parent)
+        .ok_or_else(|| no_sequence(&parent.identity))?
+        .nucleic_acid_elements()?;
+    let orientation = |location: &Location| {
+        location
+            .orientation()
+            .or(feature.orientation())
+            .cloned()
+            .unwrap_or(Orientation::Inline)
+    };
+    let reverse = |orientation: &Orientation| {
+        matches!(
+            orientation,
+            Orientation::ReverseComplement | Orientation::ReverseComplementAlt
+        )
+    };
+    let mut regions = vec![];
+    for location in locations {
+        let (start, end) = match location {
+            Location::Range(range) => (range.start, range.end),
+            Location::EntireSequence(_) => (1, elements.len()),
+            Location::Cut(_) => continue,
+        };
+        let region = elements
+            .get(start.max(1) - 1..end)
+            .filter(|_| start > 0)
+            .ok_or_else(|| {
+                SbolError::Validation(format!(
+                    "{} is outside of the {} elements of its Sequence",
+                    location.identity(),
+                    elements.len()
+                ))
+            })?;
+        regions.push((location, start, region));
+    }
+    let Some((first, _, _)) = regions.first() else {
+        return Err(SbolError::Validation(format!(
+            "{} has no Range or EntireSequence location",
+            feature.identity()
+        )));
+    };
+    let strand = reverse(&orientation(first));
+    if regions
+        .iter()
+        .any(|(location, _, _)| reverse(&orientation(location)) != strand)
+    {
+        return Err(SbolError::Validation(format!(
+            "The locations of {} are on both strands",
+            feature.identity()
+        )));
+    }
+    if regions
+        .iter()
+        .all(|(location, _, _)| location.order().is_some())
+    {
+        regions.sort_by_key(|(location, _, _)| location.order());
+    } else {
+        regions.sort_by_key(|(_, start, _)| *start);
+        if strand {
+            regions.reverse();
+        }
+    }
+    let mut coding = String::new();
+    for (location, _, region) in regions {
+        coding.push_str(&nucleic_acid::orient(region, &orientation(location))?);
+    }
+    Ok(coding)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{dna_sequence, uri};
+    use crate::{Range, SequenceFeature};
+
+    #[test]
+    fn test_translate() {
+        let options = TranslationOptions::default();
+        assert_eq!(translate("ATGGCCTAA", &options).unwrap(), "MA*");
+        assert_eq!(translate("AUGGCCUAAGG", &options).unwrap(), "MA*");
+    }
+
+    #[test]
+    fn test_translate_options() {
+        let options = TranslationOptions {
+            frame: 1,
+            to_stop: true,
+            ..Default::default()
+        };
+        assert_eq!(translate("CATGGCCTAAGCC", &options).unwrap(), "MA");
+
+        let options = TranslationOptions {
+            orientation: Orientation::ReverseComplement,
+            ..Default::default()
+        };
+        assert_eq!(translate("TTAGGCCAT", &options).unwrap(), "MA*");
+    }
+
+    #[test]
+    fn test_genetic_code() {
+        let mitochondrial = TranslationOptions {
+            table: GeneticCode::try_from(2).unwrap(),
+            ..Default::default()
+        };
+        assert_eq!(translate("TGAAGA", &mitochondrial).unwrap(), "W*");
+
+        let bacterial = TranslationOptions {
+            table: GeneticCode::Bacterial,
+            initiator: true,
+            ..Default::default()
+        };
+        assert_eq!(translate("GTGGTG", &bacterial).unwrap(), "MV");
+        assert!(GeneticCode::try_from(7).is_err());
+    }
+
+    #[test]
+    fn test_ambiguous_codons() {
+        let options = TranslationOptions::default();
+        // GCN is always alanine, while NNN is ambiguous
+        assert_eq!(translate("GCNNNN", &options).unwrap(), "AX");
+    }
+
+    #[test]
+    fn test_translate_sequence() {
+        let namespace = uri("https://test.org/parts");
+        let cds = dna_sequence(&namespace, "cds_sequence", "atggcctaa");
+
+        let protein = cds
+            .translate("protein_sequence", &TranslationOptions::default())
+            .unwrap();
+        assert_eq!(protein.elements.as_deref(), Some("MA*"));
+        assert_eq!(protein.encoding, Some(Encoding::Protein));
+        assert_eq!(
+            protein.identity.as_str(),
+            "https://test.org/parts/protein_sequence"
+        );
+        assert_eq!(protein.derived_from, vec![cds.identity]);
+    }
+
+    #[test]
+    fn test_translate_feature() {
+        let namespace = uri("https://test.org/parts");
+        // A reverse complement CDS split by an intron: complement(join(4..6,10..15))
+        let sequence = dna_sequence(&namespace, "device_seq", "gggttacccggccatggg");
+        let mut device = Component::new(namespace, "device").unwrap();
+        device.has_sequence.push(sequence.identity.clone());
+        let mut cds = SequenceFeature::new(&device.identity, "cds").unwrap();
+        cds.orientation = Some(Orientation::ReverseComplement);
+        for (display_id, start, end) in [("exon1", 4, 6), ("exon2", 10, 15)] {
+            let range = Range::new(
+                &cds.identity,
+                display_id,
+                sequence.identity.clone(),
+                start,
+                end,
+            );
+            cds.has_location.push(Location::Range(range.unwrap()));
+        }
+        let cds_uri = cds.identity.clone();
+        device.sequence_features.push(cds);
+        let device_uri = device.identity.clone();
+        let mut document = Document::new();
+        document.components.push(device);
+        document.sequences.push(sequence);
+
+        let options = TranslationOptions::default();
+        let protein =
+            translate_feature(&mut document, &device_uri, &cds_uri, "protein", &options).unwrap();
+        let protein = document.component(&protein).unwrap();
+        assert_eq!(
+            protein.r#type,
+            vec![ComponentType::Type(ComponentTypeOntology::Protein)]
+        );
+        assert_eq!(protein.derived_from, vec![cds_uri.clone()]);
+        let sequence = document.sequence(&protein.has_sequence[0]).unwrap();
+        assert_eq!(sequence.elements.as_deref(), Some("MA*"));
+        assert_eq!(sequence.encoding, Some(Encoding::Protein));
+        assert_eq!(sequence.derived_from, vec![cds_uri.clone()]);
+
+        let result = translate_feature(&mut document, &device_uri, &cds_uri, "protein", &options);
+        assert!(matches!(result, Err(SbolError::Validation(_))));
+    }
+}
@@ -0,0 +1,42 @@
+//! Fixtures shared by the unit tests
+
+use url::Url;
+
+use crate::ontologies::{ComponentTypeOntology, Encoding, TopologyOntology};
+use crate::{Component, ComponentType, Document, Sequence};
+
+pub(crate) fn uri(uri: &str) -> Url {
+    Url::parse(uri).unwrap()
+}
+
+/// Nucleic acid `Sequence` of `elements` whose URI is `namespace/display_id`
+pub(crate) fn dna_sequence(namespace: &Url, display_id: &str, elements: &str) -> Sequence {
+    let mut sequence = Sequence::new(namespace.clone(), display_id).unwrap();
+    sequence.elements = Some(elements.to_string());
+    sequence.encoding = Some(Encoding::NucleicAcid);
+    sequence
+}
+
+/// Add a DNA `Component` with `topology`, and its `Sequence` of `elements`, to `document`.
+///
+/// The URI of the `Component` is `namespace/display_id`, which is returned, and the URI of the
+/// `Sequence` is `namespace/{display_id}_seq`.
+pub(crate) fn dna(
+    document: &mut Document,
+    namespace: &Url,
+    display_id: &str,
+    elements: &str,
+    topology: &[TopologyOntology],
+) -> Url {
+    let sequence = dna_sequence(namespace, &format!("{display_id}_seq"), elements);
+    let mut component = Component::new(namespace.clone(), display_id).unwrap();
+    component.r#type = vec![ComponentType::Type(ComponentTypeOntology::DNA)];
+    component
+        .r#type
+        .extend(topology.iter().cloned().map(ComponentType::Topology));
+    component.has_sequence.push(sequence.identity.clone());
+    let identity = component.identity.clone();
+    document.sequences.push(sequence);
+    document.components.push(component);
+    identity
+}
@@ -8,8 +8,32 @@ use crate::identified::Identified;
 /// any other object, instead subordinate `TopLevel` objects are referred to.
 pub trait TopLevel: Identified {
     /// URI that defines the namespace portion of URLs for this object and child objects.
+    ///
+    /// [`UriMinter`](crate::uris::UriMinter) mints URIs within a namespace, and
+    /// [`validation::compliance`](crate::validation::compliance) checks them.
     fn has_namespace(&self) -> Url;
 
     /// TopLevel can have 0 or more `Attachment` objects.
     fn has_attachment(&self) -> Vec<Url>;
 }
+
+/// Implement `TopLevel` (and `Identified`) for structs which store each property in a field of the
+/// same name
+macro_rules! impl_toplevel {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            $crate::identified::impl_identified!($ty);
+
+            impl $crate::TopLevel for $ty {
+                fn has_namespace(&self) -> url::Url {
+                    self.namespace.clone()
+                }
+
+                fn has_attachment(&self) -> Vec<url::Url> {
+                    self.has_attachment.clone()
+                }
+            }
+        )*
+    };
+}
+pub(crate) use impl_toplevel;
@@ -0,0 +1,195 @@
+//! Minting of compliant URIs.
+//!
+//! The SBOL specification recommends that the URI of a `TopLevel` is
+//! `namespace/[local/]display_id`, where the optional local path groups objects within their
+//! namespace, and that the URI of a child object is `parent/display_id`. The checks of these rules
+//! are found in [`validation::compliance`](crate::validation::compliance).
+
+use url::Url;
+
+use crate::identified::child_uri;
+use crate::{is_valid_display_id, Activity, Component, Document, Identified, SbolError, Sequence};
+
+/// Builder of compliant URIs within a namespace.
+///
+/// ```
+/// # use sbol::uris::UriMinter;
+/// # use sbol::url::Url;
+/// let minter = UriMinter::new(Url::parse("https://example.org").unwrap()).local("parts");
+/// let promoter = minter.top_level("pTet").unwrap();
+/// assert_eq!(promoter.as_str(), "https://example.org/parts/pTet");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UriMinter {
+    namespace: Url,
+    local: Vec<String>,
+}
+
+impl UriMinter {
+    /// Mint URIs directly within `namespace`
+    pub fn new(namespace: Url) -> Self {
+        Self {
+            namespace,
+            local: vec![],
+        }
+    }
+
+    /// Mint `TopLevel` URIs below the local path `local`, made of one or more segments separated
+    /// by `/`
+    pub fn local(mut self, local: &str) -> Self {
+        self.local = local
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .map(ToString::to_string)
+            .collect();
+        self
+    }
+
+    pub fn namespace(&self) -> &Url {
+        &self.namespace
+    }
+
+    /// URI of a `TopLevel` identified by `display_id`: `namespace/[local/]display_id`
+    pub fn top_level(&self, display_id: &str) -> Result<Url, SbolError> {
+        check_display_id(display_id)?;
+        let mut uri = self.namespace.clone();
+        for segment in &self.local {
+            uri = child_uri(&uri, segment)?;
+        }
+        child_uri(&uri, display_id)
+    }
+
+    /// URI of a `TopLevel` identified by `prefix`, or `prefix` followed by a number, which
+    /// identifies no object of `document`
+    pub fn unused_top_level(&self, document: &Document, prefix: &str) -> Result<Url, SbolError> {
+        check_display_id(prefix)?;
+        let mut uri = self.top_level(prefix)?;
+        let mut i = 0;
+        while document.contains(&uri) {
+            i += 1;
+            uri = self.top_level(&format!("{prefix}{i}"))?;
+        }
+        Ok(uri)
+    }
+
+    /// URI of a child identified by `display_id` within `parent`: `parent/display_id`
+    pub fn child(&self, parent: &Url, display_id: &str) -> Result<Url, SbolError> {
+        check_display_id(display_id)?;
+        child_uri(parent, display_id)
+    }
+
+    /// Empty `Component` identified by `display_id` within the namespace
+    pub fn component(&self, display_id: &str) -> Result<Component, SbolError> {
+        let mut component = Component::new(self.namespace.clone(), display_id)?;
+        component.identity = self.top_level(display_id)?;
+        Ok(component)
+    }
+
+    /// Empty `Sequence` identified by `display_id` within the namespace
+    pub fn sequence(&self, display_id: &str) -> Result<Sequence, SbolError> {
+        let mut sequence = Sequence::new(self.namespace.clone(), display_id)?;
+        sequence.identity = self.top_level(display_id)?;
+        Ok(sequence)
+    }
+
+    /// Empty `Activity` identified by `display_id` within the namespace
+    pub fn activity(&self, display_id: &str) -> Result<Activity, SbolError> {
+        let mut activity = Activity::new(self.namespace.clone(), display_id)?;
+        activity.identity = self.top_level(display_id)?;
+        Ok(activity)
+    }
+}
+
+/// `display_id` for a new child object of class `class`, such as `SubComponent1` or `Range2`.
+///
+/// The class is followed by the smallest number, starting from 1, which `siblings`, the other
+/// children of the same parent, do not use as their `display_id`.
+pub fn child_display_id<'a, T>(class: &str, siblings: impl IntoIterator<Item = &'a T>) -> String
+where
+    T: Identified + ?Sized + 'a,
+{
+    let taken: Vec<String> = siblings
+        .into_iter()
+        .filter_map(|sibling| sibling.display_id())
+        .collect();
+    (1..)
+        .map(|i| format!("{class}{i}"))
+        .find(|display_id| !taken.contains(display_id))
+        .unwrap_or_default()
+}
+
+fn check_display_id(display_id: &str) -> Result<(), SbolError> {
+    if is_valid_display_id(display_id) {
+        Ok(())
+    } else {
+        Err(SbolError::Validation(format!(
+            "Invalid display id: {display_id:?}"
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::uri;
+    use crate::{Location, Range, SubComponent};
+
+    #[test]
+    fn test_mint() {
+        let namespace = uri("https://example.org/");
+        let minter = UriMinter::new(namespace.clone());
+        assert_eq!(
+            minter.top_level("pTet").unwrap().as_str(),
+            "https://example.org/pTet"
+        );
+        assert!(minter.top_level("1pTet").is_err());
+        assert!(minter.top_level("p/Tet").is_err());
+
+        let minter = minter.local("/parts/promoters/");
+        let component = minter.component("pTet").unwrap();
+        assert_eq!(
+            component.identity.as_str(),
+            "https://example.org/parts/promoters/pTet"
+        );
+        assert_eq!(component.namespace, namespace);
+
+        let mut document = Document::new();
+        document.components.push(component);
+        assert_eq!(
+            minter.unused_top_level(&document, "pTet").unwrap().as_str(),
+            "https://example.org/parts/promoters/pTet1"
+        );
+    }
+
+    #[test]
+    fn test_child_display_id() {
+        let minter = UriMinter::new(uri("https://example.org"));
+        let mut component = minter.component("device").unwrap();
+        let display_id = child_display_id("SubComponent", component.children());
+        assert_eq!(display_id, "SubComponent1");
+        let sub_component =
+            SubComponent::new(&component.identity, &display_id, component.identity.clone())
+                .unwrap();
+        component.sub_components.push(sub_component);
+        assert_eq!(
+            child_display_id("SubComponent", component.children()),
+            "SubComponent2"
+        );
+
+        let sub_component = &mut component.sub_components[0];
+        let sequence = minter.top_level("device_seq").unwrap();
+        for _ in 0..2 {
+            let display_id = child_display_id("Range", &sub_component.has_location);
+            let uri = minter.child(&sub_component.identity, &display_id).unwrap();
+            let range = Range::new(&sub_component.identity, &display_id, sequence.clone(), 1, 2);
+            assert_eq!(range.as_ref().unwrap().identity, uri);
+            sub_component
+                .has_location
+                .push(Location::Range(range.unwrap()));
+        }
+        assert_eq!(
+            sub_component.has_location[1].identity().as_str(),
+            "https://example.org/device/SubComponent1/Range2"
+        );
+    }
+}
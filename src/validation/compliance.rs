@@ -0,0 +1,200 @@
+//! Compliance of URIs with the structure recommended by the SBOL specification.
+//!
+//! The URI of a `TopLevel` should be `namespace/[local/]display_id`, and the URI of a child
+//! object should be `parent/display_id`, as minted by [`UriMinter`](crate::uris::UriMinter).
+
+use std::collections::HashMap;
+use std::fmt;
+
+use url::Url;
+
+use crate::identified::child_uri;
+use crate::{is_valid_display_id, Document, SbolError};
+
+/// Reason why the URI of an object is not compliant
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UriIssue {
+    /// The object has no `display_id`
+    MissingDisplayId,
+
+    /// The `display_id` has characters other than alphanumeric or underscore, or begins with a
+    /// digit
+    InvalidDisplayId(String),
+
+    /// The URI of a `TopLevel` is not within its `namespace`
+    OutsideNamespace { namespace: Url },
+
+    /// The URI of a `TopLevel` does not end with its `display_id`
+    DisplayIdMismatch(String),
+
+    /// The URI of a child object is not `expected`, formed from its parent and `display_id`
+    ChildUri { expected: Url },
+}
+
+/// Object of a `Document` whose URI is not compliant
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NonCompliantUri {
+    pub object: Url,
+    pub issue: UriIssue,
+}
+
+impl fmt::Display for NonCompliantUri {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let object = &self.object;
+        match &self.issue {
+            UriIssue::MissingDisplayId => write!(f, "{object}: missing display id"),
+            UriIssue::InvalidDisplayId(display_id) => {
+                write!(f, "{object}: invalid display id {display_id:?}")
+            }
+            UriIssue::OutsideNamespace { namespace } => {
+                write!(f, "{object}: not within its namespace {namespace}")
+            }
+            UriIssue::DisplayIdMismatch(display_id) => {
+                write!(
+                    f,
+                    "{object}: does not end with its display id {display_id:?}"
+                )
+            }
+            UriIssue::ChildUri { expected } => write!(f, "{object}: expected {expected}"),
+        }
+    }
+}
+
+/// Check the URI of every object of `document`, and of its namespace for `TopLevel` objects.
+pub fn non_compliant_uris(document: &Document) -> Vec<NonCompliantUri> {
+    let mut namespaces: HashMap<Url, Url> = HashMap::new();
+    for top_level in document.top_levels() {
+        namespaces
+            .entry(top_level.identity())
+            .or_insert_with(|| top_level.has_namespace());
+    }
+    let mut issues = vec![];
+    for (parent, object) in document.objects() {
+        let identity = object.identity();
+        let mut issue = |issue| {
+            issues.push(NonCompliantUri {
+                object: identity.clone(),
+                issue,
+            })
+        };
+        let Some(display_id) = object.display_id() else {
+            issue(UriIssue::MissingDisplayId);
+            continue;
+        };
+        if !is_valid_display_id(&display_id) {
+            issue(UriIssue::InvalidDisplayId(display_id));
+            continue;
+        }
+        match parent {
+            Some(parent) => {
+                if let Ok(expected) = child_uri(parent, &display_id) {
+                    if expected != identity {
+                        issue(UriIssue::ChildUri { expected });
+                    }
+                }
+            }
+            None => {
+                let namespace = namespaces.get(&identity);
+                let prefix = namespace.map(|namespace| namespace.as_str().trim_end_matches('/'));
+                let path = prefix.and_then(|prefix| identity.as_str().strip_prefix(prefix));
+                match (namespace, path) {
+                    (Some(_), Some(path)) if path.starts_with('/') => {
+                        if !path.ends_with(&format!("/{display_id}")) {
+                            issue(UriIssue::DisplayIdMismatch(display_id));
+                        }
+                    }
+                    (Some(namespace), _) => issue(UriIssue::OutsideNamespace {
+                        namespace: namespace.clone(),
+                    }),
+                    (None, _) => {}
+                }
+            }
+        }
+    }
+    issues
+}
+
+/// Fail with a description of every issue found by [`non_compliant_uris`].
+pub fn validate_uris(document: &Document) -> Result<(), SbolError> {
+    let issues = non_compliant_uris(document);
+    if issues.is_empty() {
+        Ok(())
+    } else {
+        Err(SbolError::Validation(
+            issues
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("; "),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::uri;
+    use crate::uris::UriMinter;
+    use crate::{Location, Range, SequenceFeature};
+
+    #[test]
+    fn test_non_compliant_uris() {
+        let namespace = uri("https://example.org");
+        let minter = UriMinter::new(namespace.clone()).local("parts");
+        let mut component = minter.component("pTet").unwrap();
+        let sequence = minter.sequence("pTet_seq").unwrap();
+        let mut feature = SequenceFeature::new(&component.identity, "operator").unwrap();
+        let range = Range::new(
+            &feature.identity,
+            "Range1",
+            sequence.identity.clone(),
+            1,
+            10,
+        )
+        .unwrap();
+        feature.has_location.push(Location::Range(range));
+        component.sequence_features.push(feature);
+        let mut document = Document::new();
+        document.components.push(component);
+        document.sequences.push(sequence);
+        assert!(validate_uris(&document).is_ok());
+
+        let component = &mut document.components[0];
+        component.namespace = uri("https://other.org");
+        component.sequence_features[0].display_id = Some("tetO".to_string());
+        document.sequences[0].display_id = Some("pTet".to_string());
+        let component = component.identity.clone();
+        let feature = document.components[0].sequence_features[0].identity.clone();
+        let sequence = document.sequences[0].identity.clone();
+        let issues = non_compliant_uris(&document);
+        assert_eq!(
+            issues,
+            [
+                NonCompliantUri {
+                    object: component,
+                    issue: UriIssue::OutsideNamespace {
+                        namespace: uri("https://other.org")
+                    },
+                },
+                NonCompliantUri {
+                    object: feature.clone(),
+                    issue: UriIssue::ChildUri {
+                        expected: uri("https://example.org/parts/pTet/tetO")
+                    },
+                },
+                NonCompliantUri {
+                    object: sequence,
+                    issue: UriIssue::DisplayIdMismatch("pTet".to_string()),
+                },
+            ]
+        );
+
+        document.components[0].sequence_features[0].display_id = Some("2x".to_string());
+        let issues = non_compliant_uris(&document);
+        assert!(issues.contains(&NonCompliantUri {
+            object: feature,
+            issue: UriIssue::InvalidDisplayId("2x".to_string()),
+        }));
+        assert!(validate_uris(&document).is_err());
+    }
+}
@@ -0,0 +1,361 @@
+//! Consistency of sequences across the `SubComponent` hierarchy.
+//!
+//! As described by [`Component::has_feature`](crate::Component::has_feature), the elements of the
+//! parent `Sequence` at the locations of a `SubComponent` should be the elements of the
+//! `Component` that it is an instance of, reverse complemented for a reverse complement
+//! `orientation`. Positions are 1-based, in agreement with `Range` locations.
+
+use std::fmt;
+
+use url::Url;
+
+use crate::ontologies::Orientation;
+use crate::sequence::nucleic_acid;
+use crate::{Document, Identified, Location, SbolError, SubComponent};
+
+/// Reason why the locations of a `SubComponent` do not match the `Sequence` of its `Component`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MismatchIssue {
+    /// `location` extends beyond the `length` elements of its `Sequence`
+    OutOfBounds { location: Url, length: usize },
+
+    /// `location` starts at position 0, but positions are 1-based
+    ZeroStart { location: Url },
+
+    /// The locations span `found` elements, but the `Sequence` of the included `Component` has
+    /// `expected` elements
+    Length { expected: usize, found: usize },
+
+    /// The parent `Sequence` has `found` instead of `expected` at `position` of `location`, as read
+    /// in the orientation of `location`. `count` is the total number of differing elements.
+    Elements {
+        location: Url,
+        position: usize,
+        expected: char,
+        found: char,
+        count: usize,
+    },
+}
+
+/// `SubComponent` whose locations do not match the `Sequence` of the `Component` it is an
+/// instance of
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SequenceMismatch {
+    pub sub_component: Url,
+    pub instance_of: Url,
+    pub issue: MismatchIssue,
+}
+
+impl fmt::Display for SequenceMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sub_component = &self.sub_component;
+        match &self.issue {
+            MismatchIssue::OutOfBounds { location, length } => write!(
+                f,
+                "{sub_component}: {location} is outside of the {length} elements of its Sequence"
+            ),
+            MismatchIssue::ZeroStart { location } => write!(
+                f,
+                "{sub_component}: {location} starts at 0, but positions are 1-based"
+            ),
+            MismatchIssue::Length { expected, found } => write!(
+                f,
+                "{sub_component}: locations span {found} elements, but {} has {expected}",
+                self.instance_of
+            ),
+            MismatchIssue::Elements {
+                location,
+                position,
+                expected,
+                found,
+                count,
+            } => write!(
+                f,
+                "{sub_component}: expected {expected:?} but found {found:?} at position \
+                 {position} of {location} ({count} differing elements)"
+            ),
+        }
+    }
+}
+
+/// Check every `SubComponent` of `document` which has a location against the `Sequence` of the
+/// `Component` that it is an instance of.
+///
+/// Only `Range` and `EntireSequence` locations on nucleic acid sequences of the document are
+/// checked, and `SubComponent` objects whose `Component` has no nucleic acid `Sequence` in the
+/// document are skipped. Elements are compared regardless of case.
+pub fn sequence_mismatches(document: &Document) -> Vec<SequenceMismatch> {
+    document
+        .components
+        .iter()
+        .flat_map(|component| &component.sub_components)
+        .filter_map(|sub_component| check_sub_component(document, sub_component))
+        .collect()
+}
+
+/// Fail with a description of every mismatch found by [`sequence_mismatches`].
+pub fn validate_sequences(document: &Document) -> Result<(), SbolError> {
+    let mismatches = sequence_mismatches(document);
+    if mismatches.is_empty() {
+        Ok(())
+    } else {
+        Err(SbolError::Validation(
+            mismatches
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("; "),
+        ))
+    }
+}
+
+/// Element of a parent `Sequence`, as read in the orientation of a location
+struct Located<'a> {
+    location: &'a Location,
+    /// 1-based position within the parent `Sequence`
+    position: usize,
+    element: char,
+}
+
+fn check_sub_component(
+    document: &Document,
+    sub_component: &SubComponent,
+) -> Option<SequenceMismatch> {
+    let mismatch = |issue| {
+        Some(SequenceMismatch {
+            sub_component: sub_component.identity.clone(),
+            instance_of: sub_component.instance_of.clone(),
+            issue,
+        })
+    };
+    let child = document.component(&sub_component.instance_of)?;
+    let child_elements = document
+        .nucleic_acid_sequence(child)?
+        .nucleic_acid_elements()
+        .ok()?;
+    for location in &sub_component.source_location {
+        if let Some(issue) = range_issue(location, child_elements.len()) {
+            return mismatch(issue);
+        }
+    }
+    let expected = sub_component.source_elements(child_elements).ok()?;
+
+    let mut located = vec![];
+    for location in ordered_locations(sub_component) {
+        let parent = document.sequence(location.has_sequence())?;
+        let elements = parent.nucleic_acid_elements().ok()?;
+        let (start, end) = match location {
+            Location::Range(range) => (range.start, range.end),
+            Location::EntireSequence(_) => (1, elements.len()),
+            Location::Cut(_) => return None,
+        };
+        if let Some(issue) = range_issue(location, elements.len()) {
+            return mismatch(issue);
+        }
+        let region = elements.get(start - 1..end)?;
+        let orientation = location
+            .orientation()
+            .or(sub_component.orientation.as_ref())
+            .unwrap_or(&Orientation::Inline);
+        let positions: Box<dyn Iterator<Item = usize>> = match orientation {
+            Orientation::ReverseComplement | Orientation::ReverseComplementAlt => {
+                Box::new((start..=end).rev())
+            }
+            Orientation::Inline | Orientation::InlineAlt => Box::new(start..=end),
+        };
+        let oriented = nucleic_acid::orient(region, orientation).ok()?;
+        located.extend(
+            positions
+                .zip(oriented.chars())
+                .map(|(position, element)| Located {
+                    location,
+                    position,
+                    element,
+                }),
+        );
+    }
+    if located.is_empty() {
+        return None;
+    }
+
+    if located.len() != expected.len() {
+        return mismatch(MismatchIssue::Length {
+            expected: expected.len(),
+            found: located.len(),
+        });
+    }
+    let mut differences = located
+        .iter()
+        .zip(expected.chars())
+        .filter(|(located, expected)| !located.element.eq_ignore_ascii_case(expected));
+    let (first, expected) = differences.next()?;
+    mismatch(MismatchIssue::Elements {
+        location: first.location.identity(),
+        position: first.position,
+        expected,
+        found: first.element,
+        count: differences.count() + 1,
+    })
+}
+
+/// Issue of a `Range` location which is not within the `length` elements of its `Sequence`
+fn range_issue(location: &Location, length: usize) -> Option<MismatchIssue> {
+    let Location::Range(range) = location else {
+        return None;
+    };
+    if range.start == 0 {
+        Some(MismatchIssue::ZeroStart {
+            location: range.identity.clone(),
+        })
+    } else if range.end > length || range.start > range.end {
+        Some(MismatchIssue::OutOfBounds {
+            location: range.identity.clone(),
+            length,
+        })
+    } else {
+        None
+    }
+}
+
+/// Locations of `sub_component`, sorted by `order` when every location has one
+fn ordered_locations(sub_component: &SubComponent) -> Vec<&Location> {
+    let mut locations: Vec<&Location> = sub_component.has_location.iter().collect();
+    if locations.iter().all(|location| location.order().is_some()) {
+        locations.sort_by_key(|location| location.order());
+    }
+    locations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{dna_sequence, uri};
+    use crate::{Component, Range};
+
+    /// Plasmid `gattaca` with a `SubComponent` located at `start..end` which is an instance of a
+    /// part with `elements`
+    fn plasmid(elements: &str, start: usize, end: usize, orientation: Orientation) -> Document {
+        let namespace = uri("https://example.org");
+        let mut document = Document::new();
+        document
+            .sequences
+            .push(dna_sequence(&namespace, "plasmid_seq", "gattaca"));
+        document
+            .sequences
+            .push(dna_sequence(&namespace, "part_seq", elements));
+
+        let mut part = Component::new(namespace.clone(), "part").unwrap();
+        part.has_sequence.push(uri("https://example.org/part_seq"));
+        let mut plasmid = Component::new(namespace, "plasmid").unwrap();
+        plasmid
+            .has_sequence
+            .push(uri("https://example.org/plasmid_seq"));
+        let mut sub_component =
+            SubComponent::new(&plasmid.identity, "part", part.identity.clone()).unwrap();
+        let mut range = Range::new(
+            &sub_component.identity,
+            "range",
+            uri("https://example.org/plasmid_seq"),
+            start,
+            end,
+        )
+        .unwrap();
+        range.orientation = Some(orientation);
+        sub_component.has_location.push(Location::Range(range));
+        plasmid.sub_components.push(sub_component);
+        document.components.push(part);
+        document.components.push(plasmid);
+        document
+    }
+
+    #[test]
+    fn test_consistent() {
+        // Examples of the `has_feature` documentation
+        let document = plasmid("GATTA", 1, 5, Orientation::Inline);
+        assert!(sequence_mismatches(&document).is_empty());
+        let document = plasmid("tgta", 4, 7, Orientation::ReverseComplement);
+        assert!(validate_sequences(&document).is_ok());
+    }
+
+    #[test]
+    fn test_mismatches() {
+        let mismatches = sequence_mismatches(&plasmid("gatca", 1, 5, Orientation::Inline));
+        assert_eq!(
+            mismatches[0].issue,
+            MismatchIssue::Elements {
+                location: uri("https://example.org/plasmid/part/range"),
+                position: 4,
+                expected: 'c',
+                found: 't',
+                count: 1,
+            }
+        );
+        assert!(mismatches[0].to_string().contains("position 4"));
+
+        // Reverse complement positions count down from the end of the range
+        let mismatches =
+            sequence_mismatches(&plasmid("tgaa", 4, 7, Orientation::ReverseComplement));
+        let MismatchIssue::Elements { position, .. } = mismatches[0].issue else {
+            panic!("Expected an element mismatch, found {:?}", mismatches[0]);
+        };
+        assert_eq!(position, 5);
+
+        let mismatches = sequence_mismatches(&plasmid("gat", 1, 5, Orientation::Inline));
+        assert_eq!(
+            mismatches[0].issue,
+            MismatchIssue::Length {
+                expected: 3,
+                found: 5
+            }
+        );
+        let mismatches = sequence_mismatches(&plasmid("gattaca", 5, 11, Orientation::Inline));
+        assert!(matches!(
+            mismatches[0].issue,
+            MismatchIssue::OutOfBounds { length: 7, .. }
+        ));
+        let mismatches = sequence_mismatches(&plasmid("gattaca", 0, 6, Orientation::Inline));
+        assert_eq!(
+            mismatches[0].issue,
+            MismatchIssue::ZeroStart {
+                location: uri("https://example.org/plasmid/part/range")
+            }
+        );
+        assert!(validate_sequences(&plasmid("gat", 1, 5, Orientation::Inline)).is_err());
+    }
+
+    #[test]
+    fn test_source_ranges() {
+        // The part is included without its first and last elements
+        let mut document = plasmid("cgattacag", 1, 7, Orientation::Inline);
+        let plasmid = document
+            .component_mut(&uri("https://example.org/plasmid"))
+            .unwrap();
+        let sub_component = &mut plasmid.sub_components[0];
+        for (display_id, start, end) in [("source1", 2, 4), ("source2", 5, 8)] {
+            let range = Range::new(
+                &sub_component.identity,
+                display_id,
+                uri("https://example.org/part_seq"),
+                start,
+                end,
+            )
+            .unwrap();
+            sub_component.source_location.push(Location::Range(range));
+        }
+        assert!(sequence_mismatches(&document).is_empty());
+
+        let plasmid = document
+            .component_mut(&uri("https://example.org/plasmid"))
+            .unwrap();
+        let Location::Range(range) = &mut plasmid.sub_components[0].source_location[0] else {
+            unreachable!()
+        };
+        range.start = 0;
+        assert_eq!(
+            sequence_mismatches(&document)[0].issue,
+            MismatchIssue::ZeroStart {
+                location: uri("https://example.org/plasmid/part/source1")
+            }
+        );
+    }
+}
@@ -0,0 +1,5 @@
+//! Checks of the rules of the SBOL specification which span several objects of a `Document`.
+
+pub mod compliance;
+pub mod hierarchy;
+pub mod references;
@@ -0,0 +1,403 @@
+//! Integrity of the references between objects, ie: `Component::has_sequence` or
+//! `Constraint::subject`.
+//!
+//! A reference is dangling when its URI identifies no object of the document, nor of the linked
+//! documents it may refer to, or when it identifies an object of the wrong class.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use url::Url;
+
+use crate::{Document, Identified, Location, SbolError};
+
+/// Class of the SBOL data model which a reference may point to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Class {
+    Component,
+    Sequence,
+    Activity,
+    SubComponent,
+    SequenceFeature,
+    ComponentReference,
+    Location,
+    Constraint,
+    Interaction,
+    Participation,
+    Interface,
+    Usage,
+
+    /// Not modelled by this crate, so that no object of a `Document` has this class
+    Model,
+
+    /// Not modelled by this crate, so that no object of a `Document` has this class
+    Attachment,
+
+    /// Not modelled by this crate, so that no object of a `Document` has this class
+    Measure,
+}
+
+impl fmt::Display for Class {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+const ANY: &[Class] = &[];
+const FEATURE: &[Class] = &[
+    Class::SubComponent,
+    Class::SequenceFeature,
+    Class::ComponentReference,
+];
+
+/// Reason why a reference is dangling
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReferenceIssue {
+    /// The URI is within the namespace of a `TopLevel` of the documents, or below the URI of a
+    /// `TopLevel`, but identifies no object
+    Broken,
+
+    /// The URI is outside of the namespaces of the documents, ie: a part of a public repository
+    External,
+
+    /// The URI identifies an object of class `found`, which is not one of `expected`
+    WrongType { expected: Vec<Class>, found: Class },
+}
+
+/// Reference which does not resolve to an object of the expected class
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DanglingReference {
+    /// Object which has the reference
+    pub object: Url,
+
+    /// Name of the field of the reference, ie: `has_sequence`
+    pub property: &'static str,
+
+    pub target: Url,
+    pub issue: ReferenceIssue,
+}
+
+impl fmt::Display for DanglingReference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (object, property, target) = (&self.object, self.property, &self.target);
+        match &self.issue {
+            ReferenceIssue::Broken => write!(f, "{object} {property}: {target} does not exist"),
+            ReferenceIssue::External => {
+                write!(f, "{object} {property}: {target} is an external reference")
+            }
+            ReferenceIssue::WrongType { expected, found } => {
+                let expected: Vec<String> = expected.iter().map(ToString::to_string).collect();
+                write!(
+                    f,
+                    "{object} {property}: {target} is a {found}, expected {}",
+                    expected.join(" or ")
+                )
+            }
+        }
+    }
+}
+
+/// Check every reference of `document`, which may resolve to an object of `document` or of one
+/// of the `linked` documents.
+pub fn dangling_references(document: &Document, linked: &[&Document]) -> Vec<DanglingReference> {
+    // The first object found for a URI wins, the document before the linked documents
+    let mut objects: HashMap<Url, Class> = HashMap::new();
+    let mut namespaces: HashSet<String> = HashSet::new();
+    for linked in std::iter::once(document).chain(linked.iter().copied()) {
+        let mut walk = Walk::default();
+        walk.document(linked);
+        for (uri, class) in walk.objects {
+            objects.entry(uri).or_insert(class);
+        }
+        for top_level in linked.top_levels() {
+            for prefix in [top_level.has_namespace(), top_level.identity()] {
+                namespaces.insert(prefix.as_str().trim_end_matches('/').to_string());
+            }
+        }
+    }
+    let mut walk = Walk::default();
+    walk.document(document);
+
+    let mut dangling = vec![];
+    for reference in walk.references {
+        let found = objects.get(&reference.target).copied();
+        let issue = match found {
+            Some(found) if reference.expected.is_empty() || reference.expected.contains(&found) => {
+                continue
+            }
+            Some(found) => ReferenceIssue::WrongType {
+                expected: reference.expected.to_vec(),
+                found,
+            },
+            None if is_within(&reference.target, &namespaces) => ReferenceIssue::Broken,
+            None => ReferenceIssue::External,
+        };
+        dangling.push(DanglingReference {
+            object: reference.object,
+            property: reference.property,
+            target: reference.target,
+            issue,
+        });
+    }
+    dangling
+}
+
+/// Fail with a description of every broken reference, or reference to an object of the wrong
+/// class, found by [`dangling_references`]. External references are allowed.
+pub fn validate_references(document: &Document, linked: &[&Document]) -> Result<(), SbolError> {
+    let issues: Vec<String> = dangling_references(document, linked)
+        .iter()
+        .filter(|reference| reference.issue != ReferenceIssue::External)
+        .map(ToString::to_string)
+        .collect();
+    if issues.is_empty() {
+        Ok(())
+    } else {
+        Err(SbolError::Validation(issues.join("; ")))
+    }
+}
+
+/// Whether `uri` is one of `prefixes`, or below one of them. Prefixes have no trailing slash.
+fn is_within(uri: &Url, prefixes: &HashSet<String>) -> bool {
+    let uri = uri.as_str();
+    prefixes.contains(uri.trim_end_matches('/'))
+        || uri
+            .match_indices('/')
+            .any(|(i, _)| prefixes.contains(&uri[..i]))
+}
+
+struct Reference {
+    object: Url,
+    property: &'static str,
+    target: Url,
+
+    /// Classes which the target may have, any class if empty
+    expected: &'static [Class],
+}
+
+/// Objects of a document, with their class, and their references
+#[derive(Default)]
+struct Walk {
+    objects: Vec<(Url, Class)>,
+    references: Vec<Reference>,
+}
+
+impl Walk {
+    fn reference<'a>(
+        &mut self,
+        object: &Url,
+        property: &'static str,
+        targets: impl IntoIterator<Item = &'a Url>,
+        expected: &'static [Class],
+    ) {
+        for target in targets {
+            self.references.push(Reference {
+                object: object.clone(),
+                property,
+                target: target.clone(),
+                expected,
+            });
+        }
+    }
+
+    /// Add `object`, and its references shared by every `Identified` object
+    fn object(&mut self, object: &dyn Identified, class: Class) -> Url {
+        let identity = object.identity();
+        self.objects.push((identity.clone(), class));
+        self.reference(&identity, "derived_from", &object.derived_from(), ANY);
+        self.reference(
+            &identity,
+            "generated_by",
+            &object.generated_by(),
+            &[Class::Activity],
+        );
+        self.reference(
+            &identity,
+            "has_measure",
+            &object.has_measure(),
+            &[Class::Measure],
+        );
+        identity
+    }
+
+    fn locations(&mut self, locations: &[Location]) {
+        for location in locations {
+            let identity = self.object(location, Class::Location);
+            self.reference(
+                &identity,
+                "has_sequence",
+                [location.has_sequence()],
+                &[Class::Sequence],
+            );
+        }
+    }
+
+    fn document(&mut self, document: &Document) {
+        for component in &document.components {
+            let identity = self.object(component, Class::Component);
+            let attachment = &[Class::Attachment];
+            self.reference(
+                &identity,
+                "has_attachment",
+                &component.has_attachment,
+                attachment,
+            );
+            let sequence = &[Class::Sequence];
+            self.reference(&identity, "has_sequence", &component.has_sequence, sequence);
+            self.reference(
+                &identity,
+                "has_model",
+                &component.has_model,
+                &[Class::Model],
+            );
+            for sub_component in &component.sub_components {
+                let identity = self.object(sub_component, Class::SubComponent);
+                let instance_of = [&sub_component.instance_of];
+                self.reference(&identity, "instance_of", instance_of, &[Class::Component]);
+                self.locations(&sub_component.has_location);
+                self.locations(&sub_component.source_location);
+            }
+            for feature in &component.sequence_features {
+                self.object(feature, Class::SequenceFeature);
+                self.locations(&feature.has_location);
+            }
+            for reference in &component.component_references {
+                let identity = self.object(reference, Class::ComponentReference);
+                let in_child_of = [&reference.in_child_of];
+                self.reference(
+                    &identity,
+                    "in_child_of",
+                    in_child_of,
+                    &[Class::SubComponent],
+                );
+                self.reference(&identity, "refers_to", [&reference.refers_to], FEATURE);
+            }
+            for constraint in &component.has_constraint {
+                let identity = self.object(constraint, Class::Constraint);
+                self.reference(&identity, "subject", [&constraint.subject], FEATURE);
+                self.reference(&identity, "object", [&constraint.object], FEATURE);
+            }
+            for interaction in &component.has_interaction {
+                self.object(interaction, Class::Interaction);
+                for participation in &interaction.has_participation {
+                    let identity = self.object(participation, Class::Participation);
+                    let participant = [&participation.participant];
+                    self.reference(&identity, "participant", participant, FEATURE);
+                }
+            }
+            if let Some(interface) = &component.has_interface {
+                let identity = self.object(interface, Class::Interface);
+                self.reference(&identity, "input", &interface.input, FEATURE);
+                self.reference(&identity, "output", &interface.output, FEATURE);
+                let nondirectional = &interface.nondirectional;
+                self.reference(&identity, "nondirectional", nondirectional, FEATURE);
+            }
+        }
+        for sequence in &document.sequences {
+            let identity = self.object(sequence, Class::Sequence);
+            let attachment = &[Class::Attachment];
+            self.reference(
+                &identity,
+                "has_attachment",
+                &sequence.has_attachment,
+                attachment,
+            );
+        }
+        for activity in &document.activities {
+            let identity = self.object(activity, Class::Activity);
+            let attachment = &[Class::Attachment];
+            self.reference(
+                &identity,
+                "has_attachment",
+                &activity.has_attachment,
+                attachment,
+            );
+            let activity_class = &[Class::Activity];
+            self.reference(
+                &identity,
+                "informed_by",
+                &activity.informed_by,
+                activity_class,
+            );
+            for usage in &activity.has_usage {
+                let identity = self.object(usage, Class::Usage);
+                self.reference(&identity, "entity", [&usage.entity], ANY);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::uri;
+    use crate::{Component, Range, Sequence, SubComponent};
+
+    #[test]
+    fn test_dangling_references() {
+        let namespace = uri("https://example.org");
+        let mut device = Component::new(namespace.clone(), "device").unwrap();
+        let sequence = Sequence::new(namespace.clone(), "device_seq").unwrap();
+        let promoter = Component::new(namespace.clone(), "pTet").unwrap();
+        device.has_sequence.push(sequence.identity.clone());
+        let mut sub_component =
+            SubComponent::new(&device.identity, "promoter", promoter.identity.clone()).unwrap();
+        let range = Range::new(
+            &sub_component.identity,
+            "range",
+            sequence.identity.clone(),
+            1,
+            5,
+        );
+        sub_component
+            .has_location
+            .push(Location::Range(range.unwrap()));
+        device.sub_components.push(sub_component);
+        let mut document = Document::new();
+        document.components.push(device);
+        document.sequences.push(sequence);
+
+        // The promoter is defined in a linked library
+        let mut library = Document::new();
+        library.components.push(promoter);
+        assert!(validate_references(&document, &[&library]).is_ok());
+        let dangling = dangling_references(&document, &[]);
+        assert_eq!(dangling.len(), 1);
+        assert_eq!(dangling[0].issue, ReferenceIssue::Broken);
+        assert_eq!(
+            dangling[0].to_string(),
+            "https://example.org/device/promoter instance_of: https://example.org/pTet does not \
+             exist"
+        );
+
+        let device = &mut document.components[0];
+        let igem = uri("https://synbiohub.org/public/igem/BBa_R0040");
+        device.derived_from.push(igem.clone());
+        device.has_sequence.push(device.identity.clone());
+        let dangling = dangling_references(&document, &[&library]);
+        assert_eq!(
+            dangling,
+            [
+                DanglingReference {
+                    object: document.components[0].identity.clone(),
+                    property: "derived_from",
+                    target: igem,
+                    issue: ReferenceIssue::External,
+                },
+                DanglingReference {
+                    object: document.components[0].identity.clone(),
+                    property: "has_sequence",
+                    target: document.components[0].identity.clone(),
+                    issue: ReferenceIssue::WrongType {
+                        expected: vec![Class::Sequence],
+                        found: Class::Component,
+                    },
+                },
+            ]
+        );
+        assert!(validate_references(&document, &[&library])
+            .unwrap_err()
+            .to_string()
+            .contains("is a Component, expected Sequence"));
+    }
+}